@@ -0,0 +1,36 @@
+//! Records the versions of the Calyx crates that Filament is built against so
+//! that `--version` can report them.
+use std::{env, fs, path::Path};
+
+/// Crates whose versions are reported
+const CALYX_CRATES: [&str; 5] = [
+    "calyx-ir",
+    "calyx-frontend",
+    "calyx-utils",
+    "calyx-opt",
+    "calyx-backend",
+];
+
+/// Find the version of the package `name` in the contents of a lock file
+fn locked_version<'a>(lock: &'a str, name: &str) -> Option<&'a str> {
+    let header = format!("name = \"{name}\"\nversion = \"");
+    let start = lock.find(&header)? + header.len();
+    let len = lock[start..].find('"')?;
+    Some(&lock[start..start + len])
+}
+
+fn main() {
+    let root = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let lock_file = Path::new(&root).join("Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock_file.display());
+    let lock = fs::read_to_string(lock_file).unwrap_or_default();
+    let versions = CALYX_CRATES
+        .iter()
+        .map(|name| {
+            let version = locked_version(&lock, name).unwrap_or("unknown");
+            format!("{name}={version}")
+        })
+        .collect::<Vec<_>>()
+        .join(";");
+    println!("cargo:rustc-env=FILAMENT_CALYX_VERSIONS={versions}");
+}
@@ -1,7 +1,7 @@
 hljs.registerLanguage("filament", (hljs) => ({
     name: "filament",
     keywords: {
-        keyword: "bundle in import extern for if else new where comp",
+        keyword: "bundle in import extern for if else new where comp pub priv",
     },
     contains: [
         hljs.QUOTE_STRING_MODE,
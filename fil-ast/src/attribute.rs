@@ -0,0 +1,71 @@
+use super::{Id, Loc};
+
+#[derive(Clone)]
+/// An attribute attached to a component definition, e.g., `@pipeline(2)`
+pub struct Attribute {
+    pub name: Loc<Id>,
+    /// Value of the attribute if it was provided
+    pub value: Option<u64>,
+    /// String value of the attribute, e.g., `@group("dsp")`
+    pub text: Option<String>,
+}
+
+impl Attribute {
+    pub fn new(name: Loc<Id>, value: Option<u64>) -> Self {
+        Self {
+            name,
+            value,
+            text: None,
+        }
+    }
+
+    /// An attribute with a string value
+    pub fn text(name: Loc<Id>, text: String) -> Self {
+        Self {
+            name,
+            value: None,
+            text: Some(text),
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+/// The attributes attached to a component definition
+pub struct Attributes {
+    attrs: Vec<Attribute>,
+}
+
+impl Attributes {
+    pub fn new(attrs: Vec<Attribute>) -> Self {
+        Self { attrs }
+    }
+
+    /// Get the attribute with the given name
+    pub fn get(&self, name: &str) -> Option<&Attribute> {
+        self.attrs.iter().find(|a| a.name.inner() == name)
+    }
+
+    /// Get the value of the attribute with the given name.
+    /// Attributes without a value have the value 1.
+    pub fn value(&self, name: &str) -> Option<u64> {
+        self.get(name).map(|a| a.value.unwrap_or(1))
+    }
+
+    /// Get the string value of the attribute with the given name
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(|a| a.text.as_deref())
+    }
+
+    /// Check if the attribute is present
+    pub fn has(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Attribute> {
+        self.attrs.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+}
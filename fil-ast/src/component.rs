@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use super::{Command, Id, Signature};
+use super::{Command, Fact, Id, Instance, Signature};
 use fil_gen as gen;
 
 #[derive(Default)]
@@ -36,11 +36,59 @@ pub struct Component {
     pub sig: Signature,
     /// Model for this component
     pub body: Vec<Command>,
+    /// Whether the component can be used by files that import this one
+    pub public: bool,
 }
 
 impl Component {
     pub fn new(sig: Signature, body: Vec<Command>) -> Self {
-        Self { sig, body }
+        Self {
+            sig,
+            body,
+            public: true,
+        }
+    }
+
+    /// The instances created by this component, including the ones in loops
+    /// and conditionals
+    pub fn instances(&self) -> Vec<&Instance> {
+        fn collect<'a>(cmds: &'a [Command], insts: &mut Vec<&'a Instance>) {
+            for cmd in cmds {
+                match cmd {
+                    Command::Instance(inst) => insts.push(inst),
+                    Command::ForLoop(l) => collect(&l.body, insts),
+                    Command::If(i) => {
+                        collect(&i.then, insts);
+                        collect(&i.alt, insts);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        let mut insts = vec![];
+        collect(&self.body, &mut insts);
+        insts
+    }
+
+    /// The `assume` facts of this component, including the ones in loops and
+    /// conditionals. These are not checked by the compiler.
+    pub fn assumptions(&self) -> Vec<&Fact> {
+        fn collect<'a>(cmds: &'a [Command], facts: &mut Vec<&'a Fact>) {
+            for cmd in cmds {
+                match cmd {
+                    Command::Fact(fact) if !fact.checked => facts.push(fact),
+                    Command::ForLoop(l) => collect(&l.body, facts),
+                    Command::If(i) => {
+                        collect(&i.then, facts);
+                        collect(&i.alt, facts);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        let mut facts = vec![];
+        collect(&self.body, &mut facts);
+        facts
     }
 }
 
@@ -154,6 +154,24 @@ impl Expr {
         }
     }
 
+    /// Check if this expression is a negated expression `0-e`
+    pub fn is_neg(&self) -> bool {
+        match self {
+            Expr::Op {
+                op: Op::Sub, left, ..
+            } => matches!(**left, Expr::Concrete(0)),
+            _ => false,
+        }
+    }
+
+    /// The expression `e` in a negated expression `0-e`
+    fn neg_inner(self) -> Self {
+        match self {
+            Expr::Op { right, .. } => *right,
+            _ => unreachable!("expected a negated expression"),
+        }
+    }
+
     fn op_base(op: Op, l: Expr, r: Expr) -> Self {
         Expr::Op {
             op,
@@ -192,7 +210,14 @@ impl std::ops::Add for Expr {
     fn add(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Expr::Concrete(0), e) | (e, Expr::Concrete(0)) => e,
-            (Expr::Concrete(l), Expr::Concrete(r)) => Expr::Concrete(l + r),
+            // Overflowing additions are folded by the IR
+            (Expr::Concrete(l), Expr::Concrete(r)) => match l.checked_add(r) {
+                Some(n) => Expr::Concrete(n),
+                None => Self::op_base(Op::Add, l.into(), r.into()),
+            },
+            // Negated offsets like the one in `'G-1` are subtracted instead
+            (l, r) if l.is_neg() => r - l.neg_inner(),
+            (l, r) if r.is_neg() => l - r.neg_inner(),
             (left, right) => Self::op_base(Op::Add, left, right),
         }
     }
@@ -215,6 +240,8 @@ impl std::ops::Sub for Expr {
                 Some(n) => Expr::Concrete(n),
                 None => Self::op_base(Op::Sub, l.into(), r.into()),
             },
+            // Keep negated offsets in the form `0-e`
+            (l, r) if l.is_neg() => Expr::default() - (l.neg_inner() + r),
             (left, right) => Self::op_base(Op::Sub, left, right),
         }
     }
@@ -229,7 +256,10 @@ impl std::ops::Mul for Expr {
                 Expr::Concrete(0)
             }
             (Expr::Concrete(1), e) | (e, Expr::Concrete(1)) => e,
-            (Expr::Concrete(l), Expr::Concrete(r)) => Expr::Concrete(l * r),
+            (Expr::Concrete(l), Expr::Concrete(r)) => match l.checked_mul(r) {
+                Some(n) => Expr::Concrete(n),
+                None => Self::op_base(Op::Mul, l.into(), r.into()),
+            },
             (left, right) => Self::op_base(Op::Mul, left, right),
         }
     }
@@ -1,3 +1,4 @@
+mod attribute;
 mod bind_map;
 mod component;
 mod constraint;
@@ -8,9 +9,11 @@ mod interval;
 mod loc;
 mod parser;
 mod port;
+mod printer;
 mod signature;
 mod time;
 
+pub use attribute::{Attribute, Attributes};
 pub use bind_map::Binding;
 pub use component::{Component, Extern, Namespace};
 pub use constraint::{Constraint, OrderConstraint, OrderOp};
@@ -23,7 +26,8 @@ pub use fil_utils::Id;
 pub use implication::Implication;
 pub use interval::Range;
 pub use loc::Loc;
-pub use parser::FilamentParser;
-pub use port::{InterfaceDef, PortDef};
+pub use parser::{FilamentParser, Token, STDIN};
+pub use port::{InterfaceDef, LatencyBound, PortDef};
+pub use printer::Printer;
 pub use signature::{EventBind, ParamBind, SigBind, Signature};
-pub use time::{Time, TimeSub};
+pub use time::{PortEnd, Time, TimeSub};
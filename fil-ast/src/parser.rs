@@ -6,9 +6,12 @@ use crate::{self as ast, Loc, TimeSub};
 use fil_utils::{self as utils, FilamentResult};
 use fil_utils::{FileIdx, GPosIdx, GlobalPositionTable};
 use itertools::Itertools;
+use pest::error::{ErrorVariant, InputLocation};
 use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest_consume::{match_nodes, Error, Parser};
+use std::collections::HashMap;
 use std::fs;
+use std::io::{self, Read};
 use std::path::Path;
 
 /// Data associated with parsing the file.
@@ -24,6 +27,7 @@ type ParseResult<T> = Result<T, Error<Rule>>;
 type Node<'i> = pest_consume::Node<'i, Rule, UserData>;
 
 type Ports = Vec<Loc<ast::PortDef>>;
+type LatencyBounds = Vec<(ast::Id, Loc<ast::LatencyBound>)>;
 
 // include the grammar file so that Cargo knows to rebuild this file on grammar changes
 const _GRAMMAR: &str = include_str!("syntax.pest");
@@ -33,7 +37,7 @@ const _GRAMMAR: &str = include_str!("syntax.pest");
 lazy_static::lazy_static! {
     static ref PRATT: PrattParser<Rule> =
     PrattParser::new()
-        .op(Op::infix(Rule::op_add, Assoc::Left) | Op::infix(Rule::op_sub, Assoc::Left))
+        .op(Op::infix(Rule::op_add, Assoc::Left) | Op::infix(Rule::op_sub, Assoc::Left) | Op::prefix(Rule::neg))
         .op(Op::infix(Rule::op_mul, Assoc::Left) | Op::infix(Rule::op_div, Assoc::Left) | Op::infix(Rule::op_mod, Assoc::Left));
 }
 
@@ -44,64 +48,350 @@ pub enum BodyEl {
 
 #[derive(Clone)]
 pub enum FCons {
-    ExprC(ast::OrderConstraint<ast::Expr>),
+    ExprC(ast::Implication<ast::Expr>),
     TimeC(ast::OrderConstraint<ast::Time>),
 }
 
 pub enum Port {
     Pd(Loc<ast::PortDef>),
+    Bounded(Loc<ast::PortDef>, Loc<ast::LatencyBound>),
     Int(ast::InterfaceDef),
     Un((ast::Id, u64)),
 }
 
+/// A lexical token in the source program
+pub struct Token {
+    /// One of `keyword`, `identifier`, `number`, `string`, or `punct`
+    pub kind: &'static str,
+    pub text: String,
+    /// Byte offsets of the token (inclusive, exclusive)
+    pub start: usize,
+    pub end: usize,
+    /// Line and column of the start of the token (1-indexed)
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Token {
+    fn new(
+        kind: &'static str,
+        content: &str,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        let (line, col) =
+            pest::Position::new(content, start).unwrap().line_col();
+        Self {
+            kind,
+            text: content[start..end].to_string(),
+            start,
+            end,
+            line,
+            col,
+        }
+    }
+
+    /// Tokens for the source text that is not captured by any grammar rule,
+    /// i.e., keywords and punctuation from string literals in the grammar.
+    fn gap(content: &str, start: usize, end: usize, tokens: &mut Vec<Token>) {
+        const MULTI: [&str; 8] =
+            [":=", "->", "=>", "..", "::", "<=", ">=", "=="];
+        let mut idx = start;
+        while idx < end {
+            let rest = &content[idx..end];
+            let c = rest.chars().next().unwrap();
+            let len = if c.is_whitespace() {
+                idx += c.len_utf8();
+                continue;
+            } else if rest.starts_with("//") {
+                idx += rest.find('\n').unwrap_or(rest.len());
+                continue;
+            } else if rest.starts_with("/*") {
+                idx += Token::block_comment(rest);
+                continue;
+            } else if c.is_ascii_alphanumeric() || c == '_' {
+                rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len())
+            } else {
+                MULTI
+                    .iter()
+                    .find(|m| rest.starts_with(*m))
+                    .map_or(c.len_utf8(), |m| m.len())
+            };
+            let kind = if c.is_ascii_digit() {
+                "number"
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                "keyword"
+            } else {
+                "punct"
+            };
+            tokens.push(Token::new(kind, content, idx, idx + len));
+            idx += len;
+        }
+    }
+
+    /// Length of the block comment at the start of `s`, including any
+    /// comments nested in it.
+    fn block_comment(s: &str) -> usize {
+        let mut depth = 0;
+        let mut idx = 0;
+        while idx < s.len() {
+            let rest = &s[idx..];
+            if rest.starts_with("/*") {
+                depth += 1;
+                idx += 2;
+            } else if rest.starts_with("*/") {
+                depth -= 1;
+                idx += 2;
+                if depth == 0 {
+                    return idx;
+                }
+            } else {
+                idx += rest.chars().next().unwrap().len_utf8();
+            }
+        }
+        s.len()
+    }
+
+    /// Collect the tokens in the parse tree rooted at `pair`.
+    /// `pos` tracks the end of the last token.
+    fn pair(
+        content: &str,
+        pair: pest::iterators::Pair<Rule>,
+        pos: &mut usize,
+        tokens: &mut Vec<Token>,
+    ) {
+        let span = pair.as_span();
+        let kind = match pair.as_rule() {
+            Rule::identifier => Some("identifier"),
+            Rule::bitwidth => Some("number"),
+            Rule::string_lit => Some("string"),
+            Rule::EOI => return,
+            _ => None,
+        };
+        let mut inner = pair.into_inner().peekable();
+        if kind.is_none() && inner.peek().is_some() {
+            for child in inner {
+                Token::pair(content, child, pos, tokens);
+            }
+            return;
+        }
+        Token::gap(content, *pos, span.start(), tokens);
+        // Rules without children match literals from the grammar
+        let kind = kind.unwrap_or_else(|| {
+            if span.as_str().starts_with(|c: char| c.is_ascii_alphabetic()) {
+                "keyword"
+            } else {
+                "punct"
+            }
+        });
+        if span.start() != span.end() {
+            tokens.push(Token::new(kind, content, span.start(), span.end()));
+        }
+        *pos = span.end();
+    }
+}
+
+/// Name of the file used for programs read from the standard input
+pub const STDIN: &str = "<stdin>";
+
 #[derive(Parser)]
 #[grammar = "syntax.pest"]
 pub struct FilamentParser;
 
 impl FilamentParser {
     pub fn parse_file(path: &Path) -> FilamentResult<ast::Namespace> {
-        let time = std::time::Instant::now();
-        let content = &fs::read(path).map_err(|err| {
+        let content = fs::read(path).map_err(|err| {
             utils::Error::invalid_file(format!(
                 "Failed to read {}: {err}",
                 path.to_string_lossy(),
             ))
         })?;
+        Self::parse_source(&path.to_string_lossy(), &content)
+    }
+
+    /// Parse the program provided on the standard input. Errors refer to the
+    /// program using the name [STDIN].
+    pub fn parse_stdin() -> FilamentResult<ast::Namespace> {
+        let mut content = vec![];
+        io::stdin().read_to_end(&mut content).map_err(|err| {
+            utils::Error::invalid_file(format!("Failed to read {STDIN}: {err}"))
+        })?;
+        Self::parse_source(STDIN, &content)
+    }
+
+    /// Parse the `content` of the file `name`
+    fn parse_source(
+        name: &str,
+        content: &[u8],
+    ) -> FilamentResult<ast::Namespace> {
+        let time = std::time::Instant::now();
         // Add a new file to the position table
-        let string_content = std::str::from_utf8(content)?.to_string();
+        let string_content = std::str::from_utf8(content)
+            .map_err(|err| {
+                let pos = err.valid_up_to();
+                utils::Error::invalid_file(format!(
+                    "{name} is not valid UTF-8: found byte 0x{:02x} at offset {pos}. The file may be binary or use a different text encoding",
+                    content[pos],
+                ))
+            })?
+            .to_string();
         let file = GlobalPositionTable::as_mut()
-            .add_file(path.to_string_lossy().to_string(), string_content);
-        let user_data = UserData { file };
+            .add_file(name.to_string(), string_content);
         let (_, content) = GlobalPositionTable::as_ref().get_file_data(file);
-        // Parse the file
+        let out = Self::parse_content(file, content)
+            .map_err(|err| Self::recover(file, content, err))?;
+        log::info!("Parsed `{name}` in {}ms", time.elapsed().as_millis());
+        Ok(out)
+    }
+
+    /// Parse `content` as the contents of `file`
+    fn parse_content(
+        file: FileIdx,
+        content: &str,
+    ) -> FilamentResult<ast::Namespace> {
+        let user_data = UserData { file };
         let inputs =
             FilamentParser::parse_with_userdata(Rule::file, content, user_data)
-                .map_err(|e| {
-                    utils::Error::misc(format!(
-                        "Failed to parse {}: {}",
-                        e.with_path(&path.to_string_lossy()),
-                        path.to_string_lossy(),
-                    ))
-                })?;
-        let input = inputs.single().map_err(|e| {
-            utils::Error::misc(format!(
-                "Failed to parse {}: {}",
-                e.with_path(&path.to_string_lossy()),
+                .map_err(|e| Self::error(file, e))?;
+        let input = inputs.single().map_err(|e| Self::error(file, e))?;
+        FilamentParser::file(input).map_err(|e| Self::error(file, e))
+    }
+
+    /// Offsets of the lines that begin a top-level component, extern, or
+    /// generate block. Only lines that start in the first column are
+    /// considered and attributes on their own line belong to the following
+    /// component. Extern and generate blocks are never split, even if the
+    /// components inside them start in the first column.
+    fn item_starts(content: &str) -> Vec<usize> {
+        let mut starts = vec![];
+        let mut offset = 0;
+        let mut in_attrs = false;
+        // Unclosed braces of the extern or generate block being scanned
+        let mut depth = 0;
+        let mut in_block = false;
+        for line in content.split_inclusive('\n') {
+            if !in_block {
+                let word = line
+                    .split(|c: char| {
+                        !c.is_ascii_alphanumeric() && c != '_' && c != '@'
+                    })
+                    .next()
+                    .unwrap_or_default();
+                let is_item =
+                    matches!(word, "comp" | "pub" | "extern" | "generate");
+                let is_attr = word.starts_with('@');
+                if (is_item || is_attr) && !in_attrs {
+                    starts.push(offset);
+                }
+                if is_attr {
+                    in_attrs = !line.split_whitespace().any(|w| w == "comp");
+                } else if is_item {
+                    in_attrs = false;
+                }
+                in_block = matches!(word, "extern" | "generate");
+                depth = 0;
+            }
+            if in_block {
+                let code = line.split("//").next().unwrap_or_default();
+                for c in code.chars() {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => (),
+                    }
+                }
+                // The block ends once its braces are all closed
+                in_block = depth > 0 || !code.contains('}');
+            }
+            offset += line.len();
+        }
+        starts
+    }
+
+    /// Parse each top-level item of a file that failed to parse on its own so
+    /// that the errors in all of them are reported together instead of only
+    /// the first one. Returns `err`, the error from parsing the whole file, if
+    /// no item fails on its own.
+    fn recover(
+        file: FileIdx,
+        content: &str,
+        err: utils::Error,
+    ) -> utils::Error {
+        let mut bounds = Self::item_starts(content);
+        bounds.insert(0, 0);
+        bounds.push(content.len());
+        bounds.dedup();
+        let mut errors = bounds
+            .into_iter()
+            .tuple_windows()
+            .filter_map(|(start, end)| {
+                // Blank out the other items so that the positions of the
+                // errors are the same as in the file
+                let mut item = String::with_capacity(content.len());
+                for (i, c) in content.char_indices() {
+                    if (start..end).contains(&i) || c == '\n' {
+                        item.push(c);
+                    } else {
+                        item.extend(std::iter::repeat(' ').take(c.len_utf8()));
+                    }
+                }
+                Self::parse_content(file, &item).err()
+            })
+            .collect_vec();
+        if errors.is_empty() {
+            return err;
+        }
+        let first = errors.remove(0);
+        first.with_others(errors)
+    }
+
+    /// Return the tokens of the program in the file.
+    /// The tokens are derived from the parse tree so they use the same lexical
+    /// rules as the parser.
+    pub fn tokens(path: &Path) -> FilamentResult<Vec<Token>> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            utils::Error::invalid_file(format!(
+                "Failed to read {}: {err}",
                 path.to_string_lossy(),
             ))
         })?;
-        let out = FilamentParser::file(input).map_err(|e| {
-            utils::Error::misc(format!(
-                "Failed to parse {}",
-                e.with_path(&path.to_string_lossy()),
-            ))
-        })?;
-        log::info!(
-            "Parsed `{}` in {}ms",
-            path.to_string_lossy(),
-            time.elapsed().as_millis()
-        );
-        Ok(out)
+        let file =
+            FilamentParser::parse(Rule::file, &content).map_err(|e| {
+                utils::Error::misc(format!(
+                    "Failed to parse {}",
+                    e.with_path(&path.to_string_lossy()),
+                ))
+            })?;
+        let mut tokens = vec![];
+        let mut pos = 0;
+        for node in file {
+            Token::pair(
+                &content,
+                node.as_pair().clone(),
+                &mut pos,
+                &mut tokens,
+            );
+        }
+        Token::gap(&content, pos, content.len(), &mut tokens);
+        Ok(tokens)
+    }
+
+    /// Convert a parse error into an error that points to its location in
+    /// the file.
+    fn error(file: FileIdx, err: Error<Rule>) -> utils::Error {
+        let (start, end) = match err.location {
+            // Point to the character at the position so that it is underlined
+            InputLocation::Pos(p) => {
+                let (_, content) =
+                    GlobalPositionTable::as_ref().get_file_data(file);
+                let len = content[p..].chars().next().map_or(0, char::len_utf8);
+                (p, p + len)
+            }
+            InputLocation::Span(span) => span,
+        };
+        let pos = GlobalPositionTable::as_mut().add_pos(file, start, end);
+        utils::Error::parse(err.variant.message(), GPosIdx(pos))
     }
 
     fn get_span(node: &Node) -> GPosIdx {
@@ -115,6 +405,125 @@ impl FilamentParser {
         GPosIdx(pos)
     }
 
+    /// Constraints on existential parameters can only be unconditional
+    /// parameter constraints.
+    #[allow(clippy::result_large_err)]
+    fn exists_cons(
+        input: &Node,
+        cons: (
+            Vec<Loc<ast::Implication<ast::Expr>>>,
+            Vec<Loc<ast::OrderConstraint<ast::Time>>>,
+        ),
+    ) -> ParseResult<Vec<Loc<ast::OrderConstraint<ast::Expr>>>> {
+        let (expr, ev) = cons;
+        if !ev.is_empty() {
+            return Err(input.error(
+                "Cannot specify event constraints in an existential binding",
+            ));
+        }
+        expr.into_iter()
+            .map(|c| {
+                let pos = c.pos();
+                let ast::Implication { guard, cons } = c.take();
+                if guard.is_some() {
+                    Err(input.error("Cannot specify conditional constraints in an existential binding"))
+                } else {
+                    Ok(Loc::new(cons, pos))
+                }
+            })
+            .collect()
+    }
+
+    /// Resolve times that refer to the interval endpoints of other ports in
+    /// the signature. Cyclic references between ports are rejected.
+    #[allow(clippy::result_large_err)]
+    fn resolve_port_times(
+        input: &Node,
+        ports: &mut [Loc<ast::PortDef>],
+    ) -> ParseResult<()> {
+        let names: HashMap<ast::Id, usize> = ports
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| (p.name().copy(), idx))
+            .collect();
+        let mut done = vec![false; ports.len()];
+        let mut stack = vec![];
+        for idx in 0..ports.len() {
+            Self::resolve_port(
+                input, idx, ports, &names, &mut done, &mut stack,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Resolve the interval of the port at `idx` after resolving the ports
+    /// it refers to. `stack` tracks the ports currently being resolved.
+    #[allow(clippy::result_large_err)]
+    fn resolve_port(
+        input: &Node,
+        idx: usize,
+        ports: &mut [Loc<ast::PortDef>],
+        names: &HashMap<ast::Id, usize>,
+        done: &mut [bool],
+        stack: &mut Vec<usize>,
+    ) -> ParseResult<()> {
+        if done[idx] {
+            return Ok(());
+        }
+        if let Some(pos) = stack.iter().position(|i| *i == idx) {
+            let cycle = stack[pos..]
+                .iter()
+                .chain(std::iter::once(&idx))
+                .map(|i| ports[*i].name().to_string())
+                .join(" -> ");
+            return Err(input.error(format!(
+                "Cyclic reference between port intervals: {cycle}"
+            )));
+        }
+
+        stack.push(idx);
+        let ast::Range { start, end } = ports[idx].liveness().inner().clone();
+        let start =
+            Self::resolve_time(input, start, ports, names, done, stack)?;
+        let end = Self::resolve_time(input, end, ports, names, done, stack)?;
+        **ports[idx].liveness_mut() = ast::Range::new(start, end);
+        stack.pop();
+        done[idx] = true;
+        Ok(())
+    }
+
+    /// Resolve a time that may refer to the interval endpoint of a port.
+    #[allow(clippy::result_large_err)]
+    fn resolve_time(
+        input: &Node,
+        time: ast::Time,
+        ports: &mut [Loc<ast::PortDef>],
+        names: &HashMap<ast::Id, usize>,
+        done: &mut [bool],
+        stack: &mut Vec<usize>,
+    ) -> ParseResult<ast::Time> {
+        let Some((port, endpoint)) = time.port else {
+            return Ok(time);
+        };
+        let Some(&dep) = names.get(port.inner()) else {
+            return Err(input
+                .error(format!("Interval refers to undefined port `{port}'")));
+        };
+        if ports[dep].is_indexed() {
+            return Err(input.error(format!(
+                "Interval cannot refer to bundle `{port}' with index parameters"
+            )));
+        }
+        Self::resolve_port(input, dep, ports, names, done, stack)?;
+        let range = ports[dep].liveness();
+        let mut resolved = match endpoint {
+            ast::PortEnd::Start => range.start.clone(),
+            ast::PortEnd::End => range.end.clone(),
+        };
+        resolved.offset += time.offset;
+        Ok(resolved)
+    }
+
     #[allow(clippy::result_large_err)]
     fn expr_helper(
         ud: UserData,
@@ -138,6 +547,10 @@ impl FilamentParser {
                     _ => unreachable!("Unknown binary operator"),
                 })
             })
+            .map_prefix(|op, rhs| match op.as_rule() {
+                Rule::neg => Ok(ast::Expr::default() - rhs?),
+                _ => unreachable!("Unknown unary operator"),
+            })
             .parse(pairs)
     }
 }
@@ -189,20 +602,12 @@ impl FilamentParser {
             input.clone().into_children();
             [param_var(param), expr(e)] => Ok(Loc::new(ast::SigBind::let_(param, e.take()), sp)),
             [opaque(_), param_var(param), constraints(cons)] => {
-                let (expr, ev) = cons;
-                if !ev.is_empty() {
-                    Err(input.error("Cannot specify event constraints in an existential binding"))
-                } else {
-                    Ok(Loc::new(ast::SigBind::exists(param, true, expr), sp))
-                }
+                let expr = Self::exists_cons(&input, cons)?;
+                Ok(Loc::new(ast::SigBind::exists(param, true, expr), sp))
             },
             [some(_), param_var(param), constraints(cons)] => {
-                let (expr, ev) = cons;
-                if !ev.is_empty() {
-                    Err(input.error("Cannot specify event constraints in an existential binding"))
-                } else {
-                    Ok(Loc::new(ast::SigBind::exists(param, false, expr), sp))
-                }
+                let expr = Self::exists_cons(&input, cons)?;
+                Ok(Loc::new(ast::SigBind::exists(param, false, expr), sp))
             }
         )
     }
@@ -217,30 +622,76 @@ impl FilamentParser {
         Ok(Loc::new(out, sp))
     }
 
-    fn char(input: Node) -> ParseResult<&str> {
-        Ok(input.as_str())
+    fn char(input: Node) -> ParseResult<char> {
+        let s = input.as_str();
+        let Some(escape) = s.strip_prefix('\\') else {
+            return Ok(s.chars().next().unwrap());
+        };
+        match escape {
+            "n" => Ok('\n'),
+            "t" => Ok('\t'),
+            "\\" => Ok('\\'),
+            "\"" => Ok('"'),
+            _ => Err(Error::new_from_pos(
+                ErrorVariant::CustomError {
+                    message: format!(
+                        "invalid escape sequence `{s}'. Strings support `\\n', `\\t', `\\\\', and `\\\"'"
+                    ),
+                },
+                input.as_span().start_pos(),
+            )),
+        }
     }
 
     fn string_lit(input: Node) -> ParseResult<String> {
         Ok(match_nodes!(
             input.into_children();
-            [char(c)..] => c.collect::<Vec<_>>().join("")
+            [char(c)..] => c.collect()
         ))
     }
 
     fn bitwidth(input: Node) -> ParseResult<u64> {
-        input
-            .as_str()
-            .parse::<u64>()
-            .map_err(|_| input.error("Expected valid bitwidth"))
+        let digits = input.as_str().replace('_', "");
+        if let Some(hex) = digits.strip_prefix("0x") {
+            u64::from_str_radix(hex, 16)
+        } else if let Some(bin) = digits.strip_prefix("0b") {
+            u64::from_str_radix(bin, 2)
+        } else {
+            digits.parse::<u64>()
+        }
+        .map_err(|_| input.error("Expected valid bitwidth"))
     }
 
     // ================ Intervals =====================
+    fn port_end(input: Node) -> ParseResult<ast::PortEnd> {
+        Ok(match input.as_str() {
+            "start" => ast::PortEnd::Start,
+            "end" => ast::PortEnd::End,
+            _ => unreachable!(),
+        })
+    }
+
+    fn port_time(input: Node) -> ParseResult<(Loc<ast::Id>, ast::PortEnd)> {
+        Ok(match_nodes!(
+            input.into_children();
+            [identifier(port), port_end(end)] => (port, end)
+        ))
+    }
+
+    fn neg_offset(input: Node) -> ParseResult<ast::Expr> {
+        let ud = input.user_data().clone();
+        Self::expr_helper(ud, input.into_pair().into_inner())
+    }
+
     fn time(input: Node) -> ParseResult<Loc<ast::Time>> {
         let sp = Self::get_span(&input);
         match_nodes!(
             input.clone().into_children();
+            [port_time((port, end)), expr(sts)] => Ok(Loc::new(ast::Time::port_relative(port, end, sts.take()), sp)),
+            [port_time((port, end)), neg_offset(sts)] => Ok(Loc::new(ast::Time::port_relative(port, end, sts), sp)),
+            [port_time((port, end))] => Ok(Loc::new(ast::Time::port_relative(port, end, ast::Expr::default()), sp)),
             [event(ev), expr(sts)] => Ok(Loc::new(ast::Time::new(ev.take(), sts.take()), sp)),
+            [event(ev), neg_offset(sts)] => Ok(Loc::new(ast::Time::new(ev.take(), sts), sp)),
             [expr(sts), event(ev)] => Ok(Loc::new(ast::Time::new(ev.take(), sts.take()), sp)),
             [event(ev)] => Ok(Loc::new(ast::Time::new(ev.take(), ast::Expr::default()), sp)),
             [expr(_)] => {
@@ -304,10 +755,27 @@ impl FilamentParser {
             .map(|e| Loc::new(e, sp))
     }
 
+    fn latency(input: Node) -> ParseResult<Loc<ast::LatencyBound>> {
+        let sp = Self::get_span(&input);
+        match_nodes!(
+            input.clone().into_children();
+            [bitwidth(min), bitwidth(max)] => {
+                if min > max {
+                    Err(input.error(format!("Minimum latency {min} is larger than maximum latency {max}")))
+                } else {
+                    Ok(Loc::new(ast::LatencyBound::new(min, max), sp))
+                }
+            }
+        )
+    }
+
     fn port_def(input: Node) -> ParseResult<Port> {
         let sp = Self::get_span(&input);
         match_nodes!(
             input.clone().into_children();
+            [latency(l), bundle_def(bd)] => {
+                Ok(Port::Bounded(Loc::new(bd.into(), sp), l))
+            },
             [identifier(name), interface(time_var)] => {
                 Ok(Port::Int(ast::InterfaceDef::new(name, time_var)))
             },
@@ -360,21 +828,31 @@ impl FilamentParser {
 
     fn ports(
         input: Node,
-    ) -> ParseResult<(Ports, Vec<ast::InterfaceDef>, Vec<(ast::Id, u64)>)> {
+    ) -> ParseResult<(
+        Ports,
+        Vec<ast::InterfaceDef>,
+        Vec<(ast::Id, u64)>,
+        LatencyBounds,
+    )> {
         Ok(match_nodes!(
             input.into_children();
             [port_def(ins)..] => {
                 let mut interface_signals = vec![];
                 let mut ports = vec![];
                 let mut unannotated_ports = vec![];
+                let mut bounds = vec![];
                 for m in ins {
                     match m {
                         Port::Pd(port) => ports.push(port),
+                        Port::Bounded(port, bound) => {
+                            bounds.push((port.name().copy(), bound));
+                            ports.push(port)
+                        }
                         Port::Int(int) => interface_signals.push(int),
                         Port::Un(un) => unannotated_ports.push(un)
                     }
                 }
-                (ports, interface_signals, unannotated_ports)
+                (ports, interface_signals, unannotated_ports, bounds)
             }
         ))
     }
@@ -383,34 +861,72 @@ impl FilamentParser {
         Ok(())
     }
 
+    /// The output port that implements an output interface. The port is high
+    /// in the cycle in which the event occurs.
+    fn interface_port(int: &ast::InterfaceDef) -> Loc<ast::PortDef> {
+        let pos = int.name.pos();
+        let live = ast::Range::new(
+            ast::Time::unit(int.event, 0),
+            ast::Time::unit(int.event, 1),
+        );
+        Loc::new(
+            ast::PortDef::port(
+                int.name.clone(),
+                Loc::new(live, pos),
+                Loc::new(ast::Expr::concrete(1), pos),
+            ),
+            pos,
+        )
+    }
+
     #[allow(clippy::type_complexity)]
     fn io(
         input: Node,
-    ) -> ParseResult<(Ports, Ports, Vec<ast::InterfaceDef>, Vec<(ast::Id, u64)>)>
-    {
-        match_nodes!(
+    ) -> ParseResult<(
+        Ports,
+        Ports,
+        Vec<ast::InterfaceDef>,
+        Vec<ast::InterfaceDef>,
+        Vec<(ast::Id, u64)>,
+        LatencyBounds,
+    )> {
+        let (ins, outs, interface, out_interface, un, bounds) = match_nodes!(
             input.clone().into_children();
-            [arrow(_)] => Ok((vec![], vec![], vec![], vec![])),
-            [ports((ins, interface, un)), arrow(_)] =>  Ok((ins, vec![], interface, un)),
-            [arrow(_), ports((outs, out_interface, o_un))] =>  {
-                if !out_interface.is_empty() {
-                    Err(input.error("Output interface ports not supported"))
-                } else if !o_un.is_empty() {
+            [arrow(_)] => Ok((vec![], vec![], vec![], vec![], vec![], vec![])),
+            [ports((ins, interface, un, in_bounds)), arrow(_)] =>  {
+                if !in_bounds.is_empty() {
+                    Err(input.error("Latency bounds can only be specified on output ports"))
+                } else {
+                    Ok((ins, vec![], interface, vec![], un, vec![]))
+                }
+            },
+            [arrow(_), ports((outs, out_interface, o_un, bounds))] =>  {
+                if !o_un.is_empty() {
                     Err(input.error("Output ports cannot be unannotated"))
                 } else {
-                    Ok((vec![], outs, vec![], vec![]))
+                    Ok((vec![], outs, vec![], out_interface, vec![], bounds))
                 }
             },
-            [ports((ins, interface, un)), arrow(_), ports((outs, out_interface, o_un))] => {
-                if !out_interface.is_empty() {
-                    Err(input.error("Output interface ports not supported"))
-                } else if !o_un.is_empty() {
+            [ports((ins, interface, un, in_bounds)), arrow(_), ports((outs, out_interface, o_un, bounds))] => {
+                if !o_un.is_empty() {
                     Err(input.error("Output ports cannot be unannotated"))
+                } else if !in_bounds.is_empty() {
+                    Err(input.error("Latency bounds can only be specified on output ports"))
                 } else {
-                    Ok((ins, outs, interface, un))
+                    Ok((ins, outs, interface, out_interface, un, bounds))
                 }
             }
-        )
+        )?;
+
+        // Resolve references to the intervals of other ports
+        let num_ins = ins.len();
+        let mut ports = ins;
+        ports.extend(outs);
+        Self::resolve_port_times(&input, &mut ports)?;
+        let mut outs = ports.split_off(num_ins);
+        // Output interface ports are 1-bit outputs of the component
+        outs.extend(out_interface.iter().map(Self::interface_port));
+        Ok((ports, outs, interface, out_interface, un, bounds))
     }
 
     // ================ Cells =====================
@@ -545,6 +1061,13 @@ impl FilamentParser {
         let sp = Self::get_span(&input);
         match_nodes!(
             input.clone().into_children();
+            [expr_cmp(guard), expr_cmp(cons)] => {
+                let imp = ast::Implication::implies(guard, cons);
+                Ok(Loc::new(FCons::ExprC(imp), sp))
+            },
+            [expr_cmp(_), time(_), order_op(_), time(_)] => {
+                Err(input.error("Conditional constraints can only constrain parameters. Constraints between events cannot depend on a condition"))
+            },
             [
                 time(l),
                 order_op((op, rev)),
@@ -569,7 +1092,7 @@ impl FilamentParser {
                 } else {
                     ast::OrderConstraint::new(r.take(), l.take(), op)
                 };
-                Ok(Loc::new(FCons::ExprC(con), sp))
+                Ok(Loc::new(FCons::ExprC(con.into()), sp))
             }
         )
     }
@@ -578,7 +1101,7 @@ impl FilamentParser {
     fn constraints(
         input: Node,
     ) -> ParseResult<(
-        Vec<Loc<ast::OrderConstraint<ast::Expr>>>,
+        Vec<Loc<ast::Implication<ast::Expr>>>,
         Vec<Loc<ast::OrderConstraint<ast::Time>>>,
     )> {
         Ok(match_nodes!(
@@ -614,8 +1137,39 @@ impl FilamentParser {
         ))
     }
 
+    /// The documentation of the given ports, taken from the `///` comments
+    /// on the lines directly preceding their definitions.
+    fn port_docs(
+        src: &str,
+        ports: &[Loc<ast::PortDef>],
+    ) -> Vec<(ast::Id, String)> {
+        let table = GlobalPositionTable::as_ref();
+        ports
+            .iter()
+            .filter_map(|port| {
+                let start = table.get_pos(port.pos().into_option()?.0).start;
+                let mut lines = src[..start].split('\n').rev();
+                // The port must be the first thing on its line
+                if !lines.next()?.trim().is_empty() {
+                    return None;
+                }
+                let doc = lines
+                    .map(str::trim)
+                    .take_while(|l| {
+                        l.starts_with("///") && !l.starts_with("////")
+                    })
+                    .map(|l| l["///".len()..].trim())
+                    .collect_vec();
+                (!doc.is_empty()).then(|| {
+                    (port.name().copy(), doc.into_iter().rev().join(" "))
+                })
+            })
+            .collect()
+    }
+
     fn signature(input: Node) -> ParseResult<ast::Signature> {
-        Ok(match_nodes!(
+        let src = input.as_span().get_input();
+        let mut sig = match_nodes!(
             input.into_children();
             [
                 identifier(name),
@@ -625,8 +1179,8 @@ impl FilamentParser {
                 sig_bindings(sig_binds),
                 constraints((expr_c, time_c))
             ] => {
-                let (inputs, outputs, interface_signals, unannotated_ports) = io;
-                ast::Signature::new(
+                let (inputs, outputs, interface_signals, out_interfaces, unannotated_ports, bounds) = io;
+                let mut sig = ast::Signature::new(
                     name,
                     params,
                     abstract_vars,
@@ -637,7 +1191,10 @@ impl FilamentParser {
                     expr_c,
                     time_c,
                     sig_binds,
-                 )
+                    bounds,
+                 );
+                sig.output_interfaces = out_interfaces;
+                sig
             },
             [
                 identifier(name),
@@ -646,8 +1203,8 @@ impl FilamentParser {
                 sig_bindings(sig_binds),
                 constraints((expr_c, time_c))
             ] => {
-                let (inputs, outputs, interface_signals, unannotated_ports) = io;
-                ast::Signature::new(
+                let (inputs, outputs, interface_signals, out_interfaces, unannotated_ports, bounds) = io;
+                let mut sig = ast::Signature::new(
                     name,
                     params,
                     vec![],
@@ -658,9 +1215,14 @@ impl FilamentParser {
                     expr_c,
                     time_c,
                     sig_binds,
-                 )
+                    bounds,
+                 );
+                sig.output_interfaces = out_interfaces;
+                sig
             }
-        ))
+        );
+        sig.port_docs = Self::port_docs(src, sig.ports());
+        Ok(sig)
     }
 
     fn connect(input: Node) -> ParseResult<ast::Connect> {
@@ -812,29 +1374,100 @@ impl FilamentParser {
         ))
     }
 
+    fn attribute(input: Node) -> ParseResult<ast::Attribute> {
+        let attr = match_nodes!(
+            input.clone().into_children();
+            [identifier(name), bitwidth(v)] => ast::Attribute::new(name, Some(v)),
+            [identifier(name), string_lit(s)] => ast::Attribute::text(name, s),
+            [identifier(name)] => ast::Attribute::new(name, None),
+        );
+        if attr.name.inner() == "pipeline" && attr.value.is_none() {
+            return Err(input.error(
+                "The `pipeline' attribute requires the number of stages",
+            ));
+        }
+        if attr.name.inner() == "latency" && attr.value.is_none() {
+            return Err(input.error(
+                "The `latency' attribute requires the number of cycles",
+            ));
+        }
+        Ok(attr)
+    }
+
+    fn attributes(input: Node) -> ParseResult<ast::Attributes> {
+        let attrs = match_nodes!(
+            input.clone().into_children();
+            [attribute(attrs)..] => attrs.collect_vec(),
+        );
+        if let Some(dup) =
+            attrs.iter().map(|a| a.name.inner()).duplicates().next()
+        {
+            return Err(input.error(format!(
+                "Attribute `{dup}' is specified multiple times"
+            )));
+        }
+        Ok(ast::Attributes::new(attrs))
+    }
+
+    /// Returns true if the component is public
+    fn visibility(input: Node) -> ParseResult<bool> {
+        Ok(match input.as_str() {
+            "pub" => true,
+            "priv" => false,
+            _ => unreachable!(),
+        })
+    }
+
     fn component(input: Node) -> ParseResult<ast::Component> {
         match_nodes!(
             input.into_children();
             [
+                attributes(attrs),
                 signature(sig),
                 command(body)..
             ] => {
+                let mut sig = sig;
+                sig.attributes = attrs;
                 Ok(ast::Component::new(sig, body.into_iter().flatten().collect()))
+            },
+            [
+                attributes(attrs),
+                visibility(public),
+                signature(sig),
+                command(body)..
+            ] => {
+                let mut sig = sig;
+                sig.attributes = attrs;
+                let body = body.into_iter().flatten().collect();
+                let mut comp = ast::Component::new(sig, body);
+                comp.public = public;
+                Ok(comp)
             }
         )
     }
 
+    fn ext_signature(input: Node) -> ParseResult<ast::Signature> {
+        Ok(match_nodes!(
+            input.into_children();
+            [attributes(attrs), signature(sig)] => {
+                let mut sig = sig;
+                sig.attributes = attrs;
+                sig
+            }
+        ))
+    }
+
     fn external(input: Node) -> ParseResult<ast::Extern> {
         Ok(match_nodes!(
             input.into_children();
-            [string_lit(path), signature(sigs)..] => ast::Extern::new(path, sigs.collect(), None),
+            [string_lit(path), ext_signature(sigs)..] => ast::Extern::new(path, sigs.collect(), None),
         ))
     }
 
     fn generate(input: Node) -> ParseResult<ast::Extern> {
         Ok(match_nodes!(
             input.into_children();
-            [identifier(name), string_lit(path), signature(sigs)..] => {
+            [identifier(name), string_lit(path), ext_signature(sigs)..] => {
                 ast::Extern::new(path, sigs.collect(), Some(name.to_string()))
             }
         ))
@@ -42,6 +42,27 @@ impl PortDef {
             PortDef::Bundle(b) => &b.name,
         }
     }
+
+    /// Availability interval of this port
+    pub fn liveness(&self) -> &Loc<Range> {
+        match self {
+            PortDef::Port { liveness, .. } => liveness,
+            PortDef::Bundle(b) => &b.typ.liveness,
+        }
+    }
+
+    /// Mutable availability interval of this port
+    pub fn liveness_mut(&mut self) -> &mut Loc<Range> {
+        match self {
+            PortDef::Port { liveness, .. } => liveness,
+            PortDef::Bundle(b) => &mut b.typ.liveness,
+        }
+    }
+
+    /// Returns true if the liveness of this port depends on a bundle index
+    pub fn is_indexed(&self) -> bool {
+        matches!(self, PortDef::Bundle(b) if !b.typ.idx.is_empty())
+    }
 }
 impl PortDef {
     /// Resolves all time expressions in this port definition
@@ -64,6 +85,57 @@ impl PortDef {
         }
     }
 
+    /// Delay the start of the availability interval by `n` cycles.
+    pub fn delay_start(self, n: u64) -> Self {
+        let delay = |l: Loc<Range>| {
+            l.map(|Range { mut start, end }| {
+                start.offset += Expr::concrete(n);
+                Range { start, end }
+            })
+        };
+        match self {
+            PortDef::Port {
+                name,
+                liveness,
+                bitwidth,
+            } => PortDef::Port {
+                name,
+                liveness: delay(liveness),
+                bitwidth,
+            },
+            PortDef::Bundle(mut b) => {
+                b.typ.liveness = delay(b.typ.liveness);
+                PortDef::Bundle(b)
+            }
+        }
+    }
+
+    /// Delay the availability interval by `n` cycles.
+    pub fn delay(self, n: u64) -> Self {
+        let delay = |l: Loc<Range>| {
+            l.map(|Range { mut start, mut end }| {
+                start.offset += Expr::concrete(n);
+                end.offset += Expr::concrete(n);
+                Range { start, end }
+            })
+        };
+        match self {
+            PortDef::Port {
+                name,
+                liveness,
+                bitwidth,
+            } => PortDef::Port {
+                name,
+                liveness: delay(liveness),
+                bitwidth,
+            },
+            PortDef::Bundle(mut b) => {
+                b.typ.liveness = delay(b.typ.liveness);
+                PortDef::Bundle(b)
+            }
+        }
+    }
+
     /// Resolves all width expressions in this port definition.
     /// Specifically:
     /// - The bitwidth of the port
@@ -84,6 +156,26 @@ impl PortDef {
     }
 }
 
+#[derive(Clone)]
+/// Bounds on the latency of an output port whose latency is data-dependent.
+/// The interval of the port is specified for the minimum latency and the
+/// value may arrive up to `max - min` cycles later.
+pub struct LatencyBound {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl LatencyBound {
+    pub fn new(min: u64, max: u64) -> Self {
+        Self { min, max }
+    }
+
+    /// Number of cycles the value may be delayed by.
+    pub fn slack(&self) -> u64 {
+        self.max - self.min
+    }
+}
+
 #[derive(Clone)]
 pub struct InterfaceDef {
     /// Name of the port
@@ -0,0 +1,303 @@
+use super::{
+    Attribute, EventBind, Expr, Implication, InterfaceDef, Op, OrderConstraint,
+    OrderOp, ParamBind, PortDef, Range, SigBind, Signature, Time, TimeSub,
+};
+use itertools::Itertools;
+
+/// Renders AST nodes using the concrete syntax of Filament so that the output
+/// can be parsed again. Unlike the [std::fmt::Display] implementations, which
+/// are meant for error messages, the printer quotes events and parenthesizes
+/// every operand that would otherwise change meaning.
+pub struct Printer;
+
+impl Printer {
+    /// Binding strength of an operator. Higher values bind tighter.
+    fn prec(op: &Op) -> u8 {
+        match op {
+            Op::Add | Op::Sub => 0,
+            Op::Mul | Op::Div | Op::Mod => 1,
+        }
+    }
+
+    pub fn expr(e: &Expr) -> String {
+        match e {
+            Expr::Concrete(n) => n.to_string(),
+            Expr::Abstract(v) => v.to_string(),
+            Expr::ParamAccess { inst, param } => format!("{inst}::{param}"),
+            Expr::App { func, args } => {
+                format!("{func}({})", args.iter().map(Self::expr).join(", "))
+            }
+            Expr::Op { op, left, right } => {
+                let left = match left.as_ref() {
+                    Expr::Op { op: l, .. }
+                        if Self::prec(l) < Self::prec(op) =>
+                    {
+                        format!("({})", Self::expr(left))
+                    }
+                    _ => Self::expr(left),
+                };
+                // Operators are left associative so the right operand needs
+                // parentheses unless regrouping cannot change its value
+                let right = match right.as_ref() {
+                    Expr::Op { op: r, .. }
+                        if Self::prec(r) < Self::prec(op)
+                            || (Self::prec(r) == Self::prec(op)
+                                && !(r == op
+                                    && matches!(op, Op::Add | Op::Mul))) =>
+                    {
+                        format!("({})", Self::expr(right))
+                    }
+                    _ => Self::expr(right),
+                };
+                format!("{left}{op}{right}")
+            }
+        }
+    }
+
+    pub fn time(t: &Time) -> String {
+        let offset = match &t.offset {
+            Expr::Concrete(0) => String::new(),
+            // Negative offsets of the form `0-e`
+            Expr::Op { right, .. } if t.offset.is_neg() => {
+                match right.as_ref() {
+                    Expr::Op { op, .. } if Self::prec(op) == 0 => {
+                        format!("-({})", Self::expr(right))
+                    }
+                    _ => format!("-{}", Self::expr(right)),
+                }
+            }
+            e => format!("+{}", Self::expr(e)),
+        };
+        match &t.port {
+            Some((port, end)) => format!("{port}.{end}{offset}"),
+            None => format!("'{}{offset}", t.event),
+        }
+    }
+
+    pub fn timesub(ts: &TimeSub) -> String {
+        match ts {
+            TimeSub::Unit(e) => Self::expr(e),
+            TimeSub::Sym { l, r } => {
+                format!("{}-({})", Self::time(l), Self::time(r))
+            }
+        }
+    }
+
+    pub fn range(r: &Range) -> String {
+        format!("[{}, {}]", Self::time(&r.start), Self::time(&r.end))
+    }
+
+    fn op(op: &OrderOp) -> &'static str {
+        match op {
+            OrderOp::Gt => ">",
+            OrderOp::Gte => ">=",
+            OrderOp::Eq => "==",
+        }
+    }
+
+    pub fn expr_constraint(c: &OrderConstraint<Expr>) -> String {
+        format!(
+            "{} {} {}",
+            Self::expr(&c.left),
+            Self::op(&c.op),
+            Self::expr(&c.right)
+        )
+    }
+
+    pub fn time_constraint(c: &OrderConstraint<Time>) -> String {
+        format!(
+            "{} {} {}",
+            Self::time(&c.left),
+            Self::op(&c.op),
+            Self::time(&c.right)
+        )
+    }
+
+    pub fn implication(imp: &Implication<Expr>) -> String {
+        match &imp.guard {
+            Some(g) => format!(
+                "{} => {}",
+                Self::expr_constraint(g),
+                Self::expr_constraint(&imp.cons)
+            ),
+            None => Self::expr_constraint(&imp.cons),
+        }
+    }
+
+    fn attribute(attr: &Attribute) -> String {
+        match attr.value {
+            Some(v) => format!("@{}({v})", attr.name),
+            None => format!("@{}", attr.name),
+        }
+    }
+
+    fn param(p: &ParamBind) -> String {
+        match &p.default {
+            Some(d) => format!("?{}={}", p.param, Self::expr(d)),
+            None => p.param.to_string(),
+        }
+    }
+
+    fn event(ev: &EventBind) -> String {
+        let bind = format!("'{}: {}", ev.event, Self::timesub(&ev.delay));
+        match &ev.default {
+            Some(d) => format!("?{bind}={}", Self::time(d)),
+            None => bind,
+        }
+    }
+
+    fn port(sig: &Signature, pd: &PortDef) -> String {
+        let name = pd.name();
+        let bound = sig
+            .latency_bounds
+            .iter()
+            .find(|(p, _)| p == name.inner())
+            .map_or(String::new(), |(_, b)| {
+                format!("@latency({}..{}) ", b.min, b.max)
+            });
+        let def = match pd {
+            PortDef::Port {
+                liveness, bitwidth, ..
+            } => format!(
+                "{name}: {} {}",
+                Self::range(liveness),
+                Self::expr(bitwidth)
+            ),
+            PortDef::Bundle(b) => {
+                let typ = &b.typ;
+                let idx = if typ.idx.is_empty() {
+                    String::new()
+                } else {
+                    format!("for<{}> ", typ.idx.iter().join(", "))
+                };
+                format!(
+                    "{name}{}: {idx}{} {}",
+                    typ.len
+                        .iter()
+                        .map(|l| format!("[{}]", Self::expr(l)))
+                        .join(""),
+                    Self::range(&typ.liveness),
+                    Self::expr(&typ.bitwidth)
+                )
+            }
+        };
+        format!("{bound}{def}")
+    }
+
+    /// Render a list of ports with one port per line
+    fn ports(ports: Vec<String>) -> String {
+        if ports.is_empty() {
+            return String::new();
+        }
+        let ports = ports.into_iter().map(|p| format!("  {p}")).join(",\n");
+        format!("\n{ports},\n")
+    }
+
+    fn sig_bind(bind: &SigBind) -> String {
+        match bind {
+            SigBind::Let { param, bind } => {
+                format!("let {param} = {};", Self::expr(bind))
+            }
+            SigBind::Exists {
+                param,
+                opaque,
+                cons,
+            } => {
+                let kw = if *opaque { "opaque" } else { "some" };
+                if cons.is_empty() {
+                    format!("{kw} {param};")
+                } else {
+                    format!(
+                        "{kw} {param} where {};",
+                        cons.iter()
+                            .map(|c| Self::expr_constraint(c))
+                            .join(", ")
+                    )
+                }
+            }
+        }
+    }
+
+    /// Render the signature of a component as it would be written in the
+    /// definition of a component or an external, without the trailing body
+    /// or semicolon. Documentation comments are not rendered.
+    pub fn signature(sig: &Signature) -> String {
+        let mut out = String::new();
+        // Textual attributes, like groups, only matter to the compiler
+        for attr in sig.attributes.iter().filter(|a| a.text.is_none()) {
+            out += &Self::attribute(attr);
+            out += " ";
+        }
+        out += &format!("comp {}", sig.name);
+        if !sig.params.is_empty() {
+            out += &format!(
+                "[{}]",
+                sig.params.iter().map(|p| Self::param(p)).join(", ")
+            );
+        }
+        if !sig.events.is_empty() {
+            out += &format!(
+                "<{}>",
+                sig.events.iter().map(|e| Self::event(e)).join(", ")
+            );
+        }
+
+        let unannotated = sig
+            .unannotated_ports
+            .iter()
+            .map(|(name, width)| format!("{name}: {width}"));
+        let interface =
+            sig.interface_signals
+                .iter()
+                .map(|InterfaceDef { name, event }| {
+                    format!("{name}: interface['{event}]")
+                });
+        let inputs = unannotated
+            .chain(interface)
+            .chain(sig.inputs().map(|p| Self::port(sig, p)))
+            .collect_vec();
+        let outputs = sig
+            .outputs()
+            .map(|p| {
+                match sig
+                    .output_interfaces
+                    .iter()
+                    .find(|i| i.name.inner() == p.name().inner())
+                {
+                    Some(InterfaceDef { name, event }) => {
+                        format!("{name}: interface['{event}]")
+                    }
+                    None => Self::port(sig, p),
+                }
+            })
+            .collect_vec();
+        out +=
+            &format!("({}) -> ({})", Self::ports(inputs), Self::ports(outputs));
+
+        if !sig.sig_bindings.is_empty() {
+            out += " with {\n";
+            for bind in &sig.sig_bindings {
+                out += &format!("  {}\n", Self::sig_bind(bind));
+            }
+            out += "}";
+        }
+
+        let cons = sig
+            .param_constraints
+            .iter()
+            .map(|c| Self::implication(c))
+            .chain(
+                sig.event_constraints
+                    .iter()
+                    .map(|c| Self::time_constraint(c)),
+            )
+            .collect_vec();
+        if !cons.is_empty() {
+            out += &format!(
+                " where\n{}",
+                cons.into_iter().map(|c| format!("  {c}")).join(",\n")
+            );
+        }
+        out
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
-    Binding, Expr, Id, InterfaceDef, Loc, OrderConstraint, PortDef, Time,
-    TimeSub,
+    Attributes, Binding, Expr, Id, Implication, InterfaceDef, LatencyBound, Loc,
+    OrderConstraint, PortDef, Printer, Time, TimeSub,
 };
 use fil_utils::GPosIdx;
 
@@ -120,14 +120,26 @@ pub struct Signature {
     /// Mapping from name of signals to the abstract variable they provide
     /// evidence for.
     pub interface_signals: Vec<InterfaceDef>,
+    /// Output ports that signal an event to the environment. They are also
+    /// part of the outputs as 1-bit ports that are high in the cycle in which
+    /// the event occurs.
+    pub output_interfaces: Vec<InterfaceDef>,
     /// Names of abstract variables bound by the component
     pub events: Vec<Loc<EventBind>>,
-    /// Constraints over the parameters in the signature
-    pub param_constraints: Vec<Loc<OrderConstraint<Expr>>>,
+    /// Constraints over the parameters in the signature. A constraint may be
+    /// guarded by a comparison over parameters in which case it only needs to
+    /// hold when the guard is true.
+    pub param_constraints: Vec<Loc<Implication<Expr>>>,
     /// Constraints over events in the signature
     pub event_constraints: Vec<Loc<OrderConstraint<Time>>>,
     /// All the input/output ports.
     pub ports: Vec<Loc<PortDef>>,
+    /// Latency bounds of output ports with data-dependent latency
+    pub latency_bounds: Vec<(Id, Loc<LatencyBound>)>,
+    /// Documentation of ports from the `///` comments preceding them
+    pub port_docs: Vec<(Id, String)>,
+    /// Attributes attached to the component definition
+    pub attributes: Attributes,
     /// Index of the first output port in the ports vector
     outputs_idx: usize,
 }
@@ -142,24 +154,41 @@ impl Signature {
         interface_signals: Vec<InterfaceDef>,
         mut inputs: Vec<Loc<PortDef>>,
         mut outputs: Vec<Loc<PortDef>>,
-        param_constraints: Vec<Loc<OrderConstraint<Expr>>>,
+        param_constraints: Vec<Loc<Implication<Expr>>>,
         event_constraints: Vec<Loc<OrderConstraint<Time>>>,
         sig_bindings: Vec<Loc<SigBind>>,
+        latency_bounds: Vec<(Id, Loc<LatencyBound>)>,
     ) -> Self {
         let outputs_idx = inputs.len();
         inputs.append(&mut outputs);
-        Self {
+        let mut sig = Self {
             name,
             params,
             sig_bindings,
             events,
             unannotated_ports,
             interface_signals,
+            output_interfaces: Vec::default(),
             ports: inputs,
             outputs_idx,
             param_constraints,
             event_constraints,
-        }
+            latency_bounds,
+            port_docs: Vec::default(),
+            attributes: Attributes::default(),
+        };
+        sig.sort_constraints();
+        sig
+    }
+
+    /// Sort the constraints by their rendered form so that signatures that
+    /// only differ in the order of their constraints are checked, and report
+    /// errors, in the same order. The locations of the constraints are kept.
+    fn sort_constraints(&mut self) {
+        self.param_constraints
+            .sort_by_cached_key(|c| Printer::implication(c));
+        self.event_constraints
+            .sort_by_cached_key(|c| Printer::time_constraint(c));
     }
 
     /// Events bound by the signature
@@ -182,4 +211,9 @@ impl Signature {
     pub fn ports(&self) -> &Vec<Loc<PortDef>> {
         &self.ports
     }
+
+    /// Check if the signature defines a port with the given name
+    pub fn has_port(&self, name: &Id) -> bool {
+        self.ports.iter().any(|p| p.name().inner() == name)
+    }
 }
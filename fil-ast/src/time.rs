@@ -1,6 +1,22 @@
-use super::{Binding, Expr, Id};
+use super::{Binding, Expr, Id, Loc};
 use std::fmt::Display;
 
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+/// An endpoint of the availability interval of a port
+pub enum PortEnd {
+    Start,
+    End,
+}
+
+impl Display for PortEnd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortEnd::Start => write!(f, "start"),
+            PortEnd::End => write!(f, "end"),
+        }
+    }
+}
+
 #[derive(Clone, Hash)]
 /// Represents expression of the form `G+1+k`
 pub struct Time {
@@ -8,11 +24,28 @@ pub struct Time {
     pub event: Id,
     /// The offsets for the time expression
     pub offset: Expr,
+    /// The port interval endpoint this time is relative to.
+    /// References are resolved when the signature is parsed and the event is
+    /// meaningless until then.
+    pub port: Option<(Loc<Id>, PortEnd)>,
 }
 
 impl Time {
     pub fn new(event: Id, offset: Expr) -> Self {
-        Self { event, offset }
+        Self {
+            event,
+            offset,
+            port: None,
+        }
+    }
+
+    /// A time expression of the form `p.start+k` or `p.end+k`
+    pub fn port_relative(port: Loc<Id>, end: PortEnd, offset: Expr) -> Self {
+        Self {
+            event: port.copy(),
+            offset,
+            port: Some((port, end)),
+        }
     }
 
     /// Get the offset associated with this time expression
@@ -22,10 +55,7 @@ impl Time {
 
     /// Unit time expression that occurs when the event occurs
     pub fn unit(event: Id, state: u64) -> Self {
-        Time {
-            event,
-            offset: Expr::concrete(state),
-        }
+        Time::new(event, Expr::concrete(state))
     }
 
     /// Resolve the events bound in this time expression
@@ -38,8 +68,8 @@ impl Time {
     /// Resolve all expressions bound in this time expression
     pub fn resolve_expr(self, bind: &Binding<Expr>) -> Self {
         Time {
-            event: self.event,
             offset: self.offset.resolve(bind),
+            ..self
         }
     }
 
@@ -57,7 +87,11 @@ impl From<Id> for Time {
 
 impl Display for Time {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}+{}", self.event, self.offset)?;
+        if let Some((port, end)) = &self.port {
+            write!(f, "{port}.{end}+{}", self.offset)?;
+        } else {
+            write!(f, "{}+{}", self.event, self.offset)?;
+        }
         Ok(())
     }
 }
@@ -121,6 +121,15 @@ impl GenExec {
         self.tools.insert(tool.name.clone(), tool);
     }
 
+    /// The files generated by executing tools, sorted by path
+    pub fn generated_files(&self) -> Vec<PathBuf> {
+        self.generated
+            .values()
+            .flat_map(|outputs| outputs.values().map(|out| out.file.clone()))
+            .sorted()
+            .collect()
+    }
+
     /// Generate a new file with the given name in the output directory
     pub fn gen_file(&mut self, name: String) -> PathBuf {
         let path = self.output_dir.path().join(name);
@@ -2,7 +2,7 @@ use super::{
     AddCtx, Cmp, CmpOp, Command, Ctx, Event, EventIdx, Expr, ExprIdx, Fact,
     IndexStore, Info, InfoIdx, InstIdx, Instance, InterfaceSrc, Interned,
     InvIdx, Invoke, MutCtx, Param, ParamIdx, Port, PortIdx, Prop, PropIdx,
-    Time, TimeSub,
+    Time, TimeOverflow, TimeSub,
 };
 use crate::{utils::Idx, ParamOwner};
 use fil_ast as ast;
@@ -73,8 +73,14 @@ pub struct Component {
     /// Externally facing interface information, used to preserve interface in compilation.
     /// Must be `Some` for toplevel components and externals.
     pub src_info: Option<InterfaceSrc>,
+    /// Name of the component in the source program
+    pub name: Option<ast::Id>,
     /// unannotated ports associated with this component
     pub unannotated_ports: Box<Vec<(ast::Id, u64)>>,
+    /// Attributes attached to the source definition of this component
+    pub attrs: ast::Attributes,
+    /// Behavior of constant folding when arithmetic overflows
+    pub time_overflow: TimeOverflow,
 
     // ============== Component structure ===============
     /// Commands in the component
@@ -107,7 +113,7 @@ impl Component {
 
     /// Return source name of the component if present
     pub fn source_name(&self) -> Option<ast::Id> {
-        self.src_info.as_ref().map(|si| si.name)
+        self.src_info.as_ref().map(|si| si.name).or(self.name)
     }
 
     /// Add a number to the context and get handle to it.
@@ -495,7 +501,8 @@ impl Component {
     }
 
     /// Evaluates a function, assuming that all parms have been substituted for
-    /// concrete expressions in monomorphization
+    /// concrete expressions in monomorphization. Operations that cannot be
+    /// folded are kept and reported by [ExprIdx::fold_error].
     pub fn func(&mut self, expr: Expr) -> ExprIdx {
         //let expr = self.get(eidx);
         match expr {
@@ -508,29 +515,21 @@ impl Component {
             }
             Expr::Fn {op, args} => {
                 let args = args.iter().map(|arg| { let arg = self.get(*arg); self.func(arg.clone()) }).collect_vec();
-                let args = args.into_iter().map(|arg| arg.as_concrete(self).unwrap()).collect_vec();
-                self.add(Expr::Concrete(op.eval(args)))
+                self.add(Expr::Fn { op, args })
             }
         }
     }
 
     /// Simplifies an expression, assuming that all params have been substituted for
-    /// concrete expressions in monomorphization
+    /// concrete expressions in monomorphization. Operations that cannot be
+    /// folded are kept and reported by [ExprIdx::fold_error].
     pub fn bin(&mut self, expr: Expr) -> ExprIdx {
         match expr {
             Expr::Concrete(_) => self.add(expr),
             Expr::Bin { op, lhs, rhs } => {
                 let lhs = self.bin(self.get(lhs).clone());
-                let lhs = lhs.as_concrete(self).unwrap();
                 let rhs = self.bin(self.get(rhs).clone());
-                let rhs = rhs.as_concrete(self).unwrap();
-                match op {
-                    ast::Op::Add => self.add(Expr::Concrete(lhs + rhs)),
-                    ast::Op::Mul => self.add(Expr::Concrete(lhs * rhs)),
-                    ast::Op::Sub => self.add(Expr::Concrete(lhs - rhs)),
-                    ast::Op::Div => self.add(Expr::Concrete(lhs / rhs)),
-                    ast::Op::Mod => self.add(Expr::Concrete(lhs % rhs)),
-                }
+                self.add(Expr::Bin { op, lhs, rhs })
             }
             Expr::Param(pidx) => {
                 self.internal_error(format!(
@@ -571,13 +570,15 @@ impl AddCtx<Expr> for Component {
                         lhs: *rhs,
                         rhs: self.exprs.intern(Expr::Concrete(l)),
                     },
-                    (op, Some(l), Some(r)) => Expr::Concrete(match op {
-                        ast::Op::Add => l + r,
-                        ast::Op::Sub => l - r,
-                        ast::Op::Mul => l * r,
-                        ast::Op::Div => l / r,
-                        ast::Op::Mod => l % r,
-                    }),
+                    // Operations that cannot be folded are kept symbolic so
+                    // that they can be reported. Negative results, e.g. from
+                    // `'G-1`, are reported by the discharge.
+                    (op, Some(l), Some(r)) => {
+                        match self.time_overflow.fold(*op, l, r) {
+                            Ok(v) => Expr::Concrete(v),
+                            Err(_) => val,
+                        }
+                    }
                     _ => val,
                 };
                 self.exprs.intern(e)
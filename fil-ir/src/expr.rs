@@ -1,7 +1,66 @@
 use super::{AddCtx, Component, Ctx, ExprIdx, ParamIdx};
 use crate::construct_binop;
 use fil_ast as ast;
+use fil_utils::{Error, FilamentResult};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+/// Behavior of constant folding when arithmetic on concrete values overflows
+pub enum TimeOverflow {
+    /// Report an error
+    #[default]
+    Error,
+    /// Clamp the result to the representable range
+    Saturate,
+    /// Wrap around the representable range
+    Wrap,
+}
+
+impl TimeOverflow {
+    /// Fold a binary operation over concrete values. Fails if the operation
+    /// divides by zero or if it overflows when overflows are errors.
+    pub fn fold(self, op: ast::Op, l: u64, r: u64) -> FilamentResult<u64> {
+        let res = match (self, op) {
+            (_, ast::Op::Div) => l.checked_div(r),
+            (_, ast::Op::Mod) => l.checked_rem(r),
+            (TimeOverflow::Error, ast::Op::Add) => l.checked_add(r),
+            (TimeOverflow::Error, ast::Op::Sub) => l.checked_sub(r),
+            (TimeOverflow::Error, ast::Op::Mul) => l.checked_mul(r),
+            (TimeOverflow::Saturate, ast::Op::Add) => Some(l.saturating_add(r)),
+            (TimeOverflow::Saturate, ast::Op::Sub) => Some(l.saturating_sub(r)),
+            (TimeOverflow::Saturate, ast::Op::Mul) => Some(l.saturating_mul(r)),
+            (TimeOverflow::Wrap, ast::Op::Add) => Some(l.wrapping_add(r)),
+            (TimeOverflow::Wrap, ast::Op::Sub) => Some(l.wrapping_sub(r)),
+            (TimeOverflow::Wrap, ast::Op::Mul) => Some(l.wrapping_mul(r)),
+        };
+        res.ok_or_else(|| {
+            let kind = match op {
+                ast::Op::Div | ast::Op::Mod => "division by zero",
+                _ => "overflow",
+            };
+            Error::malformed(format!("{kind} when computing `{l} {op} {r}'"))
+        })
+    }
+}
+
+impl FromStr for TimeOverflow {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(TimeOverflow::Error),
+            "saturate" => Ok(TimeOverflow::Saturate),
+            "wrap" => Ok(TimeOverflow::Wrap),
+            _ => Err(format!(
+                "unknown overflow behavior: {s}. Known behaviors are: error, saturate, wrap"
+            )),
+        }
+    }
+}
 
 #[derive(PartialEq, Eq, Hash, Clone)]
 pub enum Expr {
@@ -66,6 +125,25 @@ impl ExprIdx {
         c
     }
 
+    /// The error from folding the first operation in this expression whose
+    /// operands are concrete but whose result could not be computed.
+    pub fn fold_error(self, comp: &Component) -> Option<Error> {
+        match comp.get(self) {
+            Expr::Param(_) | Expr::Concrete(_) => None,
+            Expr::Bin { op, lhs, rhs } => lhs
+                .fold_error(comp)
+                .or_else(|| rhs.fold_error(comp))
+                .or_else(|| {
+                    let l = lhs.as_concrete(comp)?;
+                    let r = rhs.as_concrete(comp)?;
+                    comp.time_overflow.fold(*op, l, r).err()
+                }),
+            Expr::Fn { args, .. } => {
+                args.iter().find_map(|arg| arg.fold_error(comp))
+            }
+        }
+    }
+
     /// Returns true if this expression is a constant.
     /// Note that this process *does not* automatically reduce the expression.
     /// For example, `1 + 1` is not going to be reduced to `2`.
@@ -1,5 +1,6 @@
 use super::{idxs::PropIdx, AddCtx, Ctx, ExprIdx, InfoIdx, TimeIdx, TimeSub};
-use crate::{construct_binop, EventIdx, Expr, ParamIdx, Time};
+use crate::{construct_binop, Component, EventIdx, Expr, ParamIdx, Time};
+use fil_utils::Error;
 use std::fmt::{self, Display};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -288,6 +289,32 @@ impl PropIdx {
         self.relevant_vars_acc(ctx, &mut params, &mut events);
         (params, events)
     }
+
+    /// The error from folding the first operation in this proposition whose
+    /// operands are concrete but whose result could not be computed.
+    pub fn fold_error(self, comp: &Component) -> Option<Error> {
+        let time = |t: TimeIdx| comp.get(t).offset.fold_error(comp);
+        let time_sub = |ts: &TimeSub| match ts {
+            TimeSub::Unit(e) => e.fold_error(comp),
+            TimeSub::Sym { l, r } => time(*l).or_else(|| time(*r)),
+        };
+        match comp.get(self) {
+            Prop::True | Prop::False => None,
+            Prop::Cmp(CmpOp { lhs, rhs, .. }) => {
+                lhs.fold_error(comp).or_else(|| rhs.fold_error(comp))
+            }
+            Prop::TimeCmp(CmpOp { lhs, rhs, .. }) => {
+                time(*lhs).or_else(|| time(*rhs))
+            }
+            Prop::TimeSubCmp(CmpOp { lhs, rhs, .. }) => {
+                time_sub(lhs).or_else(|| time_sub(rhs))
+            }
+            Prop::Not(p) => p.fold_error(comp),
+            Prop::And(l, r) | Prop::Or(l, r) | Prop::Implies(l, r) => {
+                l.fold_error(comp).or_else(|| r.fold_error(comp))
+            }
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq)]
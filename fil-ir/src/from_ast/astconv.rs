@@ -8,7 +8,7 @@ use crate::{
     ParamIdx, PortIdx, PropIdx, TimeIdx,
 };
 use fil_ast as ast;
-use fil_utils::{Diagnostics, Error, GPosIdx};
+use fil_utils::{Diagnostics, Error, Failure, GPosIdx};
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::{iter, rc::Rc};
@@ -112,7 +112,7 @@ impl<'prog> BuildCtx<'prog> {
         let inst = self.comp().get_mut(idx);
         inst.params.extend(params);
 
-        self.add_inst(name.copy(), idx);
+        self.add_inst(name, idx)?;
         // Track the component binding for this instance
         self.inst_to_sig
             .push(idx, (Rc::new(binding), component.clone()));
@@ -164,10 +164,20 @@ impl<'prog> BuildCtx<'prog> {
 
         // Define the output port from the invoke
         for (p, idx) in sig.outputs.clone().into_iter() {
-            let resolved = p
+            let mut resolved = p
                 .take()
                 .resolve_exprs(&param_binding)
                 .resolve_event(&event_binding);
+            // Users of a port with variable latency can only rely on it once
+            // the value arrives with the maximum latency.
+            if let Some(slack) = sig.latency_slack(resolved.name().inner()) {
+                resolved = resolved.delay_start(slack);
+            }
+            // Outputs of pipelined components are available after the
+            // inserted registers.
+            if sig.pipeline > 0 {
+                resolved = resolved.delay(sig.pipeline);
+            }
 
             let base = ir::Foreign::new(idx, foreign_comp);
 
@@ -342,6 +352,19 @@ impl<'prog> BuildCtx<'prog> {
     }
 
     fn time(&mut self, t: ast::Time) -> BuildRes<TimeIdx> {
+        // References to port intervals are resolved while parsing signatures
+        if let Some((port, end)) = t.port {
+            let info = self.diag().add_info(
+                "port interval endpoint used outside a signature",
+                port.pos(),
+            );
+            return self.fail(
+                Error::malformed(format!(
+                    "`{port}.{end}' can only be used in the interval of a signature port"
+                )),
+                [info],
+            );
+        }
         let event = self.get_event(&t.event)?;
         let offset = self.expr(t.offset)?;
         Ok(self.comp().add(ir::Time { event, offset }))
@@ -521,6 +544,37 @@ impl<'prog> BuildCtx<'prog> {
         Ok((self.expr(access.start)?, self.expr(access.end)?))
     }
 
+    /// Get a port defined by an invocation. If the invoked component does not
+    /// define the port, the error lists the ports it does define.
+    fn inv_port(
+        &mut self,
+        inv: ir::InvIdx,
+        dir: ir::Direction,
+        name: ast::Loc<Id>,
+    ) -> BuildRes<ir::PortIdx> {
+        let owner = OwnedPort::Inv(inv, dir, name.clone());
+        if let Some(port) = self.find_port(&owner) {
+            return Ok(port);
+        }
+
+        let inst = inv.inst(self.comp());
+        let (_, comp) = self.inst_to_sig.get(inst).clone();
+        let sig = self.get_sig(&comp)?;
+        if sig.has_port(name.inner()) {
+            return self.get_port(&owner);
+        }
+        let present = sig.port_names().join(", ");
+        let msg = format!(
+            "component `{comp}' does not define port `{name}'. Defined ports: {}",
+            if present.is_empty() { "none" } else { &present }
+        );
+        let info = self.diag().add_info(
+            format!("`{name}' is not a port of `{comp}'"),
+            name.pos(),
+        );
+        self.fail(Error::malformed(msg), [info])
+    }
+
     /// Get the index associated with an AST port. The port must have been
     /// previously defined.
     fn get_access(
@@ -545,8 +599,7 @@ impl<'prog> BuildCtx<'prog> {
             }
             ast::Port::InvPort { invoke, name } => {
                 let inv = self.get_inv(&invoke)?;
-                let owner = OwnedPort::Inv(inv, dir, name);
-                ir::Access::port(self.get_port(&owner)?, self.comp())
+                ir::Access::port(self.inv_port(inv, dir, name)?, self.comp())
             }
             ast::Port::Bundle { name, access } => {
                 // NOTE(rachit): The AST does not distinguish between bundles
@@ -571,8 +624,7 @@ impl<'prog> BuildCtx<'prog> {
                 access,
             } => {
                 let inv = self.get_inv(&invoke)?;
-                let owner = OwnedPort::Inv(inv, dir, port);
-                let port = self.get_port(&owner)?;
+                let port = self.inv_port(inv, dir, port)?;
                 let ranges = access
                     .into_iter()
                     .map(|a| self.access(a.take()))
@@ -668,9 +720,86 @@ impl<'prog> BuildCtx<'prog> {
                 self.port(port.inner().clone(), ir::PortOwner::sig_in())?;
             conv_sig.outputs.push((port.clone(), idx));
         }
+        // Attach the documentation of ports to their information
+        for (name, doc) in &sig.port_docs {
+            let Some((_, idx)) = conv_sig
+                .inputs
+                .iter()
+                .chain(&conv_sig.outputs)
+                .find(|(p, _)| p.name().inner() == name)
+            else {
+                unreachable!("documentation for unknown port `{name}'")
+            };
+            let port = self.comp().get(*idx).info;
+            let ir::Info::Port(info) = self.comp().get(port).clone() else {
+                unreachable!("port `{name}' does not have port information")
+            };
+            let info = self.comp().add(
+                ir::info::Port {
+                    doc: Some(doc.clone()),
+                    ..info
+                }
+                .into(),
+            );
+            self.comp().get_mut(*idx).info = info;
+        }
+        // Mark the ports that implement output interfaces
+        for ast::InterfaceDef { name, event } in &sig.output_interfaces {
+            let Some((_, idx)) = conv_sig
+                .outputs
+                .iter()
+                .find(|(p, _)| p.name().inner() == name.inner())
+            else {
+                unreachable!("output interface for unknown port `{name}'")
+            };
+            let port = self.comp().get(*idx).info;
+            let ir::Info::Port(info) = self.comp().get(port).clone() else {
+                unreachable!("port `{name}' does not have port information")
+            };
+            let info = self.comp().add(
+                ir::info::Port {
+                    interface: Some(*event),
+                    ..info
+                }
+                .into(),
+            );
+            self.comp().get_mut(*idx).info = info;
+        }
+        // Ports with variable latency must be available for longer than the
+        // number of cycles they may be delayed by.
+        for (name, bound) in &sig.latency_bounds {
+            let Some((pd, idx)) = conv_sig
+                .outputs
+                .iter()
+                .find(|(p, _)| p.name().inner() == name)
+            else {
+                unreachable!("latency bound for unknown port `{name}'")
+            };
+            let live_loc = match pd.inner() {
+                ast::PortDef::Port { liveness, .. } => liveness.pos(),
+                ast::PortDef::Bundle(b) => b.typ.liveness.pos(),
+            };
+            let ir::Range { start, end } =
+                self.comp().get(*idx).live.range.clone();
+            let slack = self.comp().num(bound.inner().slack());
+            let start = match start.add(
+                &ir::TimeSub::Unit(slack),
+                live_loc,
+                self.comp(),
+            ) {
+                Ok(start) => start,
+                Err(err) => return self.fail(err, []),
+            };
+            let prop = end.gt(start, self.comp());
+            let info = self.comp().add(ir::Info::assert(
+                ir::info::Reason::well_formed_interval(live_loc, (start, end)),
+            ));
+            sig_cons.extend(self.comp().assert(prop, info));
+        }
         for (name, width) in &sig.unannotated_ports {
             self.comp().unannotated_ports.push((*name, *width));
         }
+        self.comp().attrs = sig.attributes.clone();
         // Constraints defined by the signature
         for ec in &sig.event_constraints {
             let info = self.comp().add(ir::Info::assert(
@@ -684,7 +813,7 @@ impl<'prog> BuildCtx<'prog> {
             let info = self.comp().add(ir::Info::assert(
                 ir::info::Reason::misc("Signature assumption", pc.pos()),
             ));
-            let prop = self.expr_cons(pc.inner().clone())?;
+            let prop = self.implication(pc.inner().clone())?;
             sig_cons.extend(self.comp().assume(prop, info));
             self.comp().add_param_assert([prop]);
         }
@@ -711,8 +840,9 @@ impl<'prog> BuildCtx<'prog> {
                 );
                 let p = f.take().resolve_expr(&binding);
                 // This is a checked fact because the calling component needs to
-                // honor it.
-                self.expr_cons(p).map(|p| self.comp().assert(p, reason))
+                // honor it. Conditional constraints are only checked when
+                // the guard holds for this instantiation.
+                self.implication(p).map(|p| self.comp().assert(p, reason))
             })
             .collect::<BuildRes<Vec<_>>>()?
             .into_iter()
@@ -1048,7 +1178,10 @@ impl<'prog> BuildCtx<'prog> {
     }
 }
 
-fn try_transform(ns: ast::Namespace) -> BuildRes<ir::Context> {
+fn try_transform(
+    ns: ast::Namespace,
+    time_overflow: ir::TimeOverflow,
+) -> BuildRes<ir::Context> {
     // creates an empty context with the main index.
     let mut ctx = ir::Context {
         entrypoint: ns
@@ -1099,6 +1232,8 @@ fn try_transform(ns: ast::Namespace) -> BuildRes<ir::Context> {
         .map(|(idx, (typ, ext_info, sig, body))| {
             let idx = ir::CompIdx::new(idx);
             let mut builder = BuildCtx::new(ir::Component::new(typ), &sig_map);
+            builder.comp().name = Some(sig.name.copy());
+            builder.comp().time_overflow = time_overflow;
 
             // enable source information saving if this is main
             if Some(idx) == ctx.entrypoint {
@@ -1152,8 +1287,13 @@ fn try_transform(ns: ast::Namespace) -> BuildRes<ir::Context> {
     Ok(ctx)
 }
 
-pub fn transform(ns: ast::Namespace) -> Result<ir::Context, u64> {
-    match try_transform(ns) {
+/// Convert the program to the IR. Constant folding in its components handles
+/// overflows with `time_overflow`.
+pub fn transform(
+    ns: ast::Namespace,
+    time_overflow: ir::TimeOverflow,
+) -> Result<ir::Context, Failure> {
+    match try_transform(ns, time_overflow) {
         Ok(ctx) => Ok(ctx),
         Err(mut e) => Err(e.report_all().unwrap()),
     }
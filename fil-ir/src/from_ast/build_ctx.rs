@@ -3,7 +3,7 @@ use super::{BuildRes, ScopeMap, SigMap};
 use crate::{self as ir, Ctx, DenseIndexInfo, PortIdx};
 use fil_ast as ast;
 use fil_utils::{self as utils, Error, Id};
-use std::rc::Rc;
+use std::{iter, rc::Rc};
 use utils::InfoIdx;
 
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
@@ -209,7 +209,13 @@ impl<'prog> BuildCtx<'prog> {
                     format!("signature `{id}' is not defined"),
                     id.pos(),
                 );
-                self.fail(Error::undefined(*name, "signature"), [info])
+                let similar = self.sigs.similar(name).map(|similar| {
+                    self.diag.add_message(format!("did you mean `{similar}'?"))
+                });
+                self.fail(
+                    Error::undefined(*name, "signature"),
+                    iter::once(info).chain(similar),
+                )
             }
         }
     }
@@ -294,8 +300,31 @@ impl<'prog> BuildCtx<'prog> {
         self.fail(err, [])
     }
 
-    pub fn add_inst(&mut self, name: Id, inst: ir::InstIdx) {
-        self.inst_map.insert(name, inst);
+    /// Bind the instance `name`. Fails if another instance with the same name
+    /// is defined in the same scope.
+    pub fn add_inst(
+        &mut self,
+        name: &ast::Loc<Id>,
+        inst: ir::InstIdx,
+    ) -> BuildRes<()> {
+        let id = *name.inner();
+        if let Some(prev) = self.inst_map.get_local(&id).copied() {
+            let info = self.comp.get(prev).info;
+            let prev_loc = self
+                .comp
+                .get(info)
+                .as_instance()
+                .map_or(utils::GPosIdx::UNKNOWN, |inst| inst.bind_loc);
+            let redef = self.diag.add_info(
+                format!("instance `{id}' is defined again here"),
+                name.pos(),
+            );
+            let first = self.diag.add_info("first defined here", prev_loc);
+            return self
+                .fail(Error::already_bound(id, "an instance"), [redef, first]);
+        }
+        self.inst_map.insert(id, inst);
+        Ok(())
     }
 
     pub fn get_inst(&mut self, id: &ast::Loc<Id>) -> BuildRes<ir::InstIdx> {
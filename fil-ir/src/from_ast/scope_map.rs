@@ -55,6 +55,11 @@ where
         scope.insert(id, idx);
     }
 
+    /// Return the value bound in the innermost scope level
+    pub fn get_local(&self, id: &K) -> Option<&V> {
+        self.map.last().unwrap().get(id)
+    }
+
     /// Return the value by searching through the scope levels
     pub fn get(&self, id: &K) -> Option<&V> {
         for scope in self.map.iter().rev() {
@@ -27,11 +27,15 @@ pub struct Sig {
     /// The AST representation of output ports of the signature
     pub raw_outputs: Vec<ast::PortDef>,
     /// Constraints on input parameters
-    pub param_cons: Vec<ast::Loc<ast::OrderConstraint<ast::Expr>>>,
+    pub param_cons: Vec<ast::Loc<ast::Implication<ast::Expr>>>,
     /// Constraints on existentially bound parameters
     pub exist_cons: Vec<ast::Loc<ast::OrderConstraint<ast::Expr>>>,
     /// Constraints on events
     pub event_cons: Vec<ast::Loc<ast::OrderConstraint<ast::Time>>>,
+    /// Latency bounds on output ports with variable latency
+    pub latency_bounds: Vec<(Id, ast::Loc<ast::LatencyBound>)>,
+    /// Number of register stages inserted on the outputs by `@pipeline`
+    pub pipeline: u64,
 }
 
 impl Sig {
@@ -51,6 +55,8 @@ impl Sig {
                 })
                 .collect(),
             event_cons: sig.event_constraints.clone(),
+            latency_bounds: sig.latency_bounds.clone(),
+            pipeline: sig.attributes.value("pipeline").unwrap_or(0),
             // Filled in later
             sig_binding: Vec::default(),
             inputs: Vec::default(),
@@ -58,6 +64,28 @@ impl Sig {
         }
     }
 
+    /// Names of the ports defined by this signature
+    pub fn port_names(&self) -> impl Iterator<Item = &Id> {
+        self.inputs
+            .iter()
+            .chain(self.outputs.iter())
+            .map(|(p, _)| p.inner().name().inner())
+    }
+
+    /// Check if the signature defines a port with the given name
+    pub fn has_port(&self, name: &Id) -> bool {
+        self.port_names().any(|n| n == name)
+    }
+
+    /// The number of cycles the value on an output port may be delayed by if
+    /// it has variable latency.
+    pub fn latency_slack(&self, port: &Id) -> Option<u64> {
+        self.latency_bounds
+            .iter()
+            .find(|(p, _)| p == port)
+            .map(|(_, b)| b.inner().slack())
+    }
+
     /// Construct a param binding from this Signature's parameters and the given
     /// arguments.
     ///
@@ -111,20 +139,13 @@ impl Sig {
                 .zip(args.iter().cloned()),
         );
 
-        // Skip the events that have been bound and fill in the rest with
-        // default values
-        let remaining = self
-            .raw_params
-            .iter()
-            .skip(args.len())
-            .map(|pb| {
-                let bind =
-                    pb.default.as_ref().unwrap().clone().resolve(&partial_map);
-                (pb.name(), bind)
-            })
-            .collect_vec();
-
-        partial_map.extend(remaining);
+        // Skip the parameters that have been bound and fill in the rest with
+        // default values in order so that defaults can use earlier defaults
+        for pb in self.raw_params.iter().skip(args.len()) {
+            let bind =
+                pb.default.as_ref().unwrap().clone().resolve(&partial_map);
+            partial_map.insert(pb.name(), bind);
+        }
 
         Ok(partial_map)
     }
@@ -182,23 +203,17 @@ impl Sig {
                 .zip(args.iter().cloned()),
         );
 
-        // Skip the events that have been bound
-        let remaining = self
-            .raw_events
-            .iter()
-            .skip(args.len())
-            .map(|eb| {
-                let bind = eb
-                    .default
-                    .as_ref()
-                    .unwrap()
-                    .clone()
-                    .resolve_event(&partial_map);
-                (*eb.event.inner(), bind)
-            })
-            .collect_vec();
-
-        partial_map.extend(remaining);
+        // Skip the events that have been bound and fill in the rest with
+        // default values in order so that defaults can use earlier defaults
+        for eb in self.raw_events.iter().skip(args.len()) {
+            let bind = eb
+                .default
+                .as_ref()
+                .unwrap()
+                .clone()
+                .resolve_event(&partial_map);
+            partial_map.insert(*eb.event.inner(), bind);
+        }
 
         Ok(partial_map)
     }
@@ -216,6 +231,35 @@ impl SigMap {
     pub fn get(&self, id: &Id) -> Option<&Sig> {
         self.map.get(id)
     }
+
+    /// The name of the bound signature that is closest to `id` if it is close
+    /// enough to be a likely misspelling.
+    pub fn similar(&self, id: &Id) -> Option<Id> {
+        let name = id.as_ref();
+        let max = (name.len() / 3).max(1);
+        self.map
+            .keys()
+            .map(|k| (edit_distance(name, k.as_ref()), k.as_ref()))
+            .filter(|(dist, _)| *dist <= max)
+            .min()
+            .map(|(_, k)| Id::from(k))
+    }
+}
+
+/// The number of single character insertions, deletions, or substitutions
+/// needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect_vec();
+    let mut prev = (0..=b.len()).collect_vec();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let sub = prev[j] + usize::from(ca != *cb);
+            cur.push(sub.min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
 }
 
 impl FromIterator<(Id, Sig)> for SigMap {
@@ -82,6 +82,10 @@ pub struct Port {
     pub bind_loc: GPosIdx,
     pub width_loc: GPosIdx,
     pub live_loc: GPosIdx,
+    /// Documentation from the `///` comments on the port
+    pub doc: Option<String>,
+    /// Event signaled by the port if it is an output interface port
+    pub interface: Option<ast::Id>,
 }
 
 /// Information associated with the IR.
@@ -212,6 +216,8 @@ impl Info {
             bind_loc,
             width_loc,
             live_loc,
+            doc: None,
+            interface: None,
         }
         .into()
     }
@@ -345,6 +351,19 @@ pub enum Reason {
         dst_width: ExprIdx,
         src_width: ExprIdx,
     },
+    /// An argument of an invocation has the width of the input port
+    InvokeArgWidth {
+        /// Location of the invocation
+        inv_loc: GPosIdx,
+        /// Location of the argument
+        arg_loc: GPosIdx,
+        /// Defining location for the input port
+        def_loc: GPosIdx,
+        /// Index of the argument
+        arg: usize,
+        expected: ExprIdx,
+        actual: ExprIdx,
+    },
     /// An access is within bounds
     InBoundsAccess {
         // Defining location for the port
@@ -415,6 +434,17 @@ pub enum Reason {
         /// Location of the binding
         time_expr_loc: GPosIdx,
     },
+    /// Invocations of a `@shared` component are never active at the same time
+    SharedOverlap {
+        /// Location of the earlier invocation's event binding
+        first_loc: GPosIdx,
+        /// Range in which the earlier invocation is active
+        first_range: (TimeIdx, TimeIdx),
+        /// Location of the later invocation's event binding
+        second_loc: GPosIdx,
+        /// Range in which the later invocation is active
+        second_range: (TimeIdx, TimeIdx),
+    },
     // =============== Generic Constraints =======================
     /// A simple reason
     Misc { reason: String, def_loc: GPosIdx },
@@ -564,6 +594,24 @@ impl Reason {
         }
     }
 
+    pub fn invoke_arg_width(
+        inv_loc: GPosIdx,
+        arg_loc: GPosIdx,
+        def_loc: GPosIdx,
+        arg: usize,
+        expected: ExprIdx,
+        actual: ExprIdx,
+    ) -> Self {
+        Self::InvokeArgWidth {
+            inv_loc,
+            arg_loc,
+            def_loc,
+            arg,
+            expected,
+            actual,
+        }
+    }
+
     pub fn well_formed_interval(
         range_loc: GPosIdx,
         range: (TimeIdx, TimeIdx),
@@ -585,6 +633,20 @@ impl Reason {
         }
     }
 
+    pub fn shared_overlap(
+        first_loc: GPosIdx,
+        first_range: (TimeIdx, TimeIdx),
+        second_loc: GPosIdx,
+        second_range: (TimeIdx, TimeIdx),
+    ) -> Self {
+        Self::SharedOverlap {
+            first_loc,
+            first_range,
+            second_loc,
+            second_range,
+        }
+    }
+
     pub fn generated(reason: String, src: Reason) -> Self {
         Self::Generated {
             reason,
@@ -608,6 +670,7 @@ impl Reason {
             Reason::ExistsConstraint { .. } => "ExistsConstraint",
             Reason::BundleLenMatch { .. } => "BundleLenMatch",
             Reason::BundleWidthMatch { .. } => "BundleWidthMatch",
+            Reason::InvokeArgWidth { .. } => "InvokeArgWidth",
             Reason::InBoundsAccess { .. } => "InBoundsAccess",
             Reason::Liveness { .. } => "Liveness",
             Reason::BundleDelay { .. } => "BundleDelay",
@@ -615,6 +678,7 @@ impl Reason {
             Reason::EventLive { .. } => "EventLive",
             Reason::EventLiveDelay { .. } => "EventLiveDelay",
             Reason::EventTrig { .. } => "EventTrig",
+            Reason::SharedOverlap { .. } => "SharedOverlap",
             Reason::Misc { .. } => "Misc",
             Reason::Generated { .. } => "Generated",
         }
@@ -735,6 +799,28 @@ impl Reason {
                     .with_message(format!("required bundle of width `{dw}' but found bundle of width `{sw}'"))
                     .with_labels(vec![src, dst])
             }
+            Reason::InvokeArgWidth {
+                inv_loc,
+                arg_loc,
+                def_loc,
+                arg,
+                expected,
+                actual,
+            } => {
+                let ew = ctx.display(*expected);
+                let aw = ctx.display(*actual);
+                let arg_label = arg_loc
+                    .primary()
+                    .with_message(format!("argument has width {aw}"));
+                let inv =
+                    inv_loc.secondary().with_message("in this invocation");
+                let def = def_loc
+                    .secondary()
+                    .with_message(format!("input port has width {ew}"));
+                Diagnostic::error()
+                    .with_message(format!("argument {arg} of invocation requires width `{ew}' but found width `{aw}'"))
+                    .with_labels(vec![arg_label, inv, def])
+            }
             Reason::Liveness {
                 dst_loc,
                 src_loc,
@@ -859,6 +945,28 @@ impl Reason {
                     .with_message("event's delay must be greater than the instance's borrow length")
                     .with_labels(vec![live, ev])
             }
+            Reason::SharedOverlap {
+                first_loc,
+                first_range: (first_start, first_end),
+                second_loc,
+                second_range: (second_start, second_end),
+            } => {
+                let second = second_loc.primary().with_message(format!(
+                    "invocation active in [{}, {}]",
+                    ctx.display(*second_start),
+                    ctx.display(*second_end)
+                ));
+                let first = first_loc.secondary().with_message(format!(
+                    "conflicts with invocation active in [{}, {}]",
+                    ctx.display(*first_start),
+                    ctx.display(*first_end)
+                ));
+                Diagnostic::error()
+                    .with_message(
+                        "invocations of a shared component may be active at the same time",
+                    )
+                    .with_labels(vec![second, first])
+            }
             Reason::Generated { reason, src } => {
                 src.diag(ctx).with_notes(vec![reason.clone()])
             }
@@ -21,7 +21,7 @@ pub use control::{
     Command, Connect, EventBind, Exists, If, Instance, Invoke, Let, Loop,
 };
 pub use ctx::{AddCtx, Ctx, MutCtx};
-pub use expr::Expr;
+pub use expr::{Expr, TimeOverflow};
 pub use fact::{Cmp, CmpOp, Fact, Prop};
 pub use from_ast::astconv::transform;
 pub use idxs::{
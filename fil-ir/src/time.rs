@@ -1,4 +1,5 @@
 use crate::DisplayCtx;
+use fil_utils::{Error, FilamentResult, GPosIdx};
 
 use super::{
     AddCtx, Component, Ctx, EventIdx, Expr, ExprIdx, Foldable, ParamIdx,
@@ -34,17 +35,29 @@ impl TimeIdx {
         }
     }
 
-    /// Add a [TimeSub] to a time expression
-    pub fn add(self, ts: &TimeSub, ctx: &mut Component) -> TimeIdx {
+    /// Add a [TimeSub] to a time expression.
+    /// Symbolic differences cannot be represented as an offset and result in
+    /// an internal error located at `pos`.
+    pub fn add(
+        self,
+        ts: &TimeSub,
+        pos: GPosIdx,
+        ctx: &mut Component,
+    ) -> FilamentResult<TimeIdx> {
         match ts {
             TimeSub::Unit(e) => {
                 let Time { event, offset } = ctx.get(self).clone();
                 let offset = offset.add(*e, ctx);
-                ctx.add(Time { event, offset })
-            }
-            TimeSub::Sym { .. } => {
-                todo!("Cannot add `{}' and `{}'. Please report this as a bug with the program that triggered it.", ctx.display(self), ctx.display(ts));
+                Ok(ctx.add(Time { event, offset }))
             }
+            TimeSub::Sym { .. } => Err(Error::internal(
+                format!(
+                    "cannot add `{}' and `{}'",
+                    ctx.display(self),
+                    ctx.display(ts)
+                ),
+                pos,
+            )),
         }
     }
 
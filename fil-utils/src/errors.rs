@@ -1,15 +1,69 @@
 //! Errors generated by the compiler.
-use super::{Id, InfoIdx};
+use super::{GPosIdx, GlobalPositionTable, Id, InfoIdx};
+use crate::reporter::{
+    capture, collect_json, emit_diagnostic, render_diagnostic,
+};
+use codespan_reporting::diagnostic::{Diagnostic, Severity};
+use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 use itertools::Itertools;
+use std::sync::Mutex;
 
-#[derive(PartialEq, Eq, Hash)]
+/// Shown before internal errors to distinguish them from mistakes in the
+/// program
+pub const INTERNAL_PREAMBLE: &str = "this is a compiler bug, please report it along with the program that triggered it";
+
+/// Warnings collected during compilation that have not been reported yet
+static WARNINGS: Mutex<Vec<Warning>> = Mutex::new(Vec::new());
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+/// The kind of problem described by an [Error]
+pub enum ErrorKind {
+    /// A mistake in the program
+    Program(String),
+    /// A violation of the invariants of the compiler with the location that
+    /// triggered it, which is [GPosIdx::UNKNOWN] when it is not known
+    Internal(String, GPosIdx),
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::Program(msg) | ErrorKind::Internal(msg, _) => {
+                write!(f, "{msg}")
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone)]
 pub struct Error {
-    pub kind: String,
+    pub kind: ErrorKind,
     pub notes: Vec<InfoIdx>,
+    /// Location of errors that are reported on their own instead of through
+    /// [crate::Diagnostics], such as parse errors
+    pub pos: Option<GPosIdx>,
+    /// Errors found along with this one that are reported after it, such as
+    /// syntax errors in other components of the same file
+    pub others: Vec<Error>,
+    /// The diagnostic of an error that was captured when it was reported. It
+    /// describes the error instead of the kind and the position.
+    pub(crate) reported: Option<Diagnostic<usize>>,
 }
 
 impl std::fmt::Debug for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let ErrorKind::Internal(msg, pos) = &self.kind {
+            writeln!(f, "{INTERNAL_PREAMBLE}")?;
+            write!(f, "internal compiler error: {msg}")?;
+            if let Some(pos) = pos.into_option() {
+                let table = GlobalPositionTable::as_ref();
+                let data = table.get_pos(pos.0);
+                let loc =
+                    table.describe(data.file.get(), &(data.start..data.end));
+                write!(f, " at {loc}")?;
+            }
+            return Ok(());
+        }
         write!(
             f,
             "{}: {}",
@@ -23,60 +77,354 @@ impl std::fmt::Debug for Error {
     }
 }
 
+/// Renders the error the way it is reported to the user. Errors with a known
+/// location show the offending line of the source like the errors reported by
+/// [crate::Diagnostics].
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.kind, self.pos.and_then(GPosIdx::into_option)) {
+            _ if self.reported.is_some() => write!(
+                f,
+                "{}",
+                render_diagnostic(self.reported.as_ref().unwrap())
+            )?,
+            (ErrorKind::Program(msg), Some(pos)) => {
+                let diag = Diagnostic::error()
+                    .with_message(msg)
+                    .with_labels(vec![pos.primary()]);
+                write!(f, "{}", render_diagnostic(&diag))?;
+            }
+            _ => writeln!(f, "Error: {self:?}")?,
+        }
+        self.others.iter().try_for_each(|e| write!(f, "{e}"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Errors that stopped compilation after they were reported
+pub struct Failure {
+    /// Number of errors reported
+    pub errors: u64,
+    /// One of the errors is an internal error
+    pub internal: bool,
+}
+
+impl Failure {
+    /// Combine with the errors reported by another failure
+    pub fn merge(self, other: Failure) -> Failure {
+        Failure {
+            errors: self.errors + other.errors,
+            internal: self.internal || other.internal,
+        }
+    }
+}
+
+/// Errors in the program
+impl From<u64> for Failure {
+    fn from(errors: u64) -> Self {
+        Failure {
+            errors,
+            internal: false,
+        }
+    }
+}
+
+/// The errors reported by [Error::report]
+impl From<&Error> for Failure {
+    fn from(err: &Error) -> Self {
+        err.others.iter().fold(
+            Failure {
+                errors: 1,
+                internal: err.is_internal(),
+            },
+            |acc, e| acc.merge(e.into()),
+        )
+    }
+}
+
+/// Displays the number of errors
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.errors)
+    }
+}
+
 impl Error {
     pub fn add_note(mut self, note: InfoIdx) -> Self {
         self.notes.push(note);
         self
     }
 
+    /// Report the error at `pos` when it is reported on its own
+    pub fn with_pos(mut self, pos: GPosIdx) -> Self {
+        self.pos = Some(pos);
+        self
+    }
+
+    /// Report the `others` errors along with this one
+    pub fn with_others(mut self, others: Vec<Error>) -> Self {
+        self.others.extend(others);
+        self
+    }
+
+    /// Print the error on the standard error, or collect it along with the
+    /// other diagnostics when they are printed as JSON or captured
+    pub fn report(&self) {
+        if capture(self) {
+            return;
+        }
+        if !collect_json(&self.diagnostic()) {
+            eprint!("{self}");
+            return;
+        }
+        self.others.iter().for_each(Error::report);
+    }
+
+    /// The diagnostic that describes this error when it is printed as JSON
+    fn diagnostic(&self) -> Diagnostic<usize> {
+        if let Some(diag) = &self.reported {
+            return diag.clone();
+        }
+        if let ErrorKind::Internal(msg, pos) = &self.kind {
+            return Diagnostic::bug()
+                .with_message(format!("internal compiler error: {msg}"))
+                .with_labels(
+                    pos.into_option()
+                        .map(|p| p.primary())
+                        .into_iter()
+                        .collect(),
+                )
+                .with_notes(vec![INTERNAL_PREAMBLE.to_string()]);
+        }
+        Diagnostic::error()
+            .with_message(self.kind.to_string())
+            .with_labels(
+                self.pos
+                    .and_then(GPosIdx::into_option)
+                    .map(|p| p.primary())
+                    .into_iter()
+                    .collect(),
+            )
+    }
+
+    /// Number of errors reported by this error
+    pub fn count(&self) -> u64 {
+        1 + self.others.len() as u64
+    }
+
     pub fn invalid_file(f: String) -> Self {
         Self {
-            kind: format!("invalid file: {}", f),
+            kind: ErrorKind::Program(format!("invalid file: {}", f)),
             notes: vec![],
+            pos: None,
+            others: vec![],
+            reported: None,
         }
     }
 
     pub fn write_error(e: String) -> Self {
         Self {
-            kind: format!("failed to write output: {}", e),
+            kind: ErrorKind::Program(format!("failed to write output: {}", e)),
             notes: vec![],
+            pos: None,
+            others: vec![],
+            reported: None,
         }
     }
 
     pub fn malformed<S: ToString>(msg: S) -> Self {
         Self {
-            kind: msg.to_string(),
+            kind: ErrorKind::Program(msg.to_string()),
             notes: vec![],
+            pos: None,
+            others: vec![],
+            reported: None,
         }
     }
 
     pub fn undefined<I: ToString, S: ToString>(name: I, kind: S) -> Self {
         Self {
-            kind: format!(
+            kind: ErrorKind::Program(format!(
                 "undefined {} name: {}",
                 kind.to_string(),
                 name.to_string(),
-            ),
+            )),
             notes: vec![],
+            pos: None,
+            others: vec![],
+            reported: None,
         }
     }
 
     pub fn already_bound<S: ToString>(name: Id, kind: S) -> Self {
         Self {
-            kind: format!(
+            kind: ErrorKind::Program(format!(
                 "name `{name}' is already bound by {}",
                 kind.to_string()
-            ),
+            )),
             notes: vec![],
+            pos: None,
+            others: vec![],
+            reported: None,
         }
     }
 
     pub fn misc(msg: String) -> Self {
         Self {
-            kind: msg,
+            kind: ErrorKind::Program(msg),
             notes: vec![],
+            pos: None,
+            others: vec![],
+            reported: None,
         }
     }
+
+    /// An error in the syntax of the program at `pos`
+    pub fn parse<S: ToString>(msg: S, pos: GPosIdx) -> Self {
+        Self {
+            kind: ErrorKind::Program(msg.to_string()),
+            notes: vec![],
+            pos: Some(pos),
+            others: vec![],
+            reported: None,
+        }
+    }
+
+    /// An error caused by a bug in the compiler rather than the program.
+    /// `pos` is the location of the construct that triggered it, if known.
+    pub fn internal<S: ToString>(msg: S, pos: GPosIdx) -> Self {
+        Self {
+            kind: ErrorKind::Internal(msg.to_string(), pos),
+            notes: vec![],
+            pos: None,
+            others: vec![],
+            reported: None,
+        }
+    }
+
+    /// An error described by a diagnostic that was reported while errors
+    /// were captured
+    pub(crate) fn from_diagnostic(diag: &Diagnostic<usize>) -> Self {
+        let kind = if diag.severity == Severity::Bug {
+            ErrorKind::Internal(diag.message.clone(), GPosIdx::UNKNOWN)
+        } else {
+            ErrorKind::Program(diag.message.clone())
+        };
+        Self {
+            kind,
+            notes: vec![],
+            pos: None,
+            others: vec![],
+            reported: Some(diag.clone()),
+        }
+    }
+
+    /// Returns true if this error is caused by a bug in the compiler
+    pub fn is_internal(&self) -> bool {
+        matches!(self.kind, ErrorKind::Internal(..))
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+/// A problem in the program that does not stop compilation. Passes add
+/// warnings with [Warning::add] and they are reported together by
+/// [Warning::report_all] once compilation is done.
+pub struct Warning {
+    pub kind: String,
+    /// Locations of the warning and their messages. The first one is the
+    /// location of the problem.
+    pub labels: Vec<(GPosIdx, String)>,
+    pub notes: Vec<String>,
+}
+
+impl Warning {
+    pub fn new<S: ToString>(kind: S) -> Self {
+        Self {
+            kind: kind.to_string(),
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    /// Point to `pos` with `msg`. Unknown positions are ignored.
+    pub fn with_label<S: ToString>(mut self, pos: GPosIdx, msg: S) -> Self {
+        if pos.into_option().is_some() {
+            self.labels.push((pos, msg.to_string()));
+        }
+        self
+    }
+
+    pub fn with_note<S: ToString>(mut self, note: S) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// Add the warning to the warnings reported at the end of compilation.
+    /// Warnings that were already added are ignored.
+    pub fn add(self) {
+        let mut warnings = WARNINGS.lock().unwrap();
+        if !warnings.contains(&self) {
+            warnings.push(self);
+        }
+    }
+
+    /// Number of warnings that have not been reported yet
+    pub fn count() -> u64 {
+        WARNINGS.lock().unwrap().len() as u64
+    }
+
+    fn diagnostic(&self) -> Diagnostic<usize> {
+        let labels = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(idx, (pos, msg))| {
+                let label = if idx == 0 {
+                    pos.primary()
+                } else {
+                    pos.secondary()
+                };
+                label.with_message(msg)
+            })
+            .collect();
+        Diagnostic::warning()
+            .with_message(&self.kind)
+            .with_labels(labels)
+            .with_notes(self.notes.clone())
+    }
+
+    /// Remove the warnings added since the last report and return them in
+    /// the order they were added.
+    pub fn take_all() -> Vec<Warning> {
+        std::mem::take(&mut *WARNINGS.lock().unwrap())
+    }
+
+    /// Report the warning on the standard error
+    pub fn report(&self) {
+        let is_tty = atty::is(atty::Stream::Stderr);
+        let writer = StandardStream::stderr(if is_tty {
+            ColorChoice::Always
+        } else {
+            ColorChoice::Never
+        });
+        emit_diagnostic(&writer, &self.diagnostic());
+    }
+
+    /// Report the warnings added since the last report in the order they were
+    /// added and return their number.
+    pub fn report_all() -> u64 {
+        let warnings = Self::take_all();
+        for warning in &warnings {
+            warning.report();
+        }
+        warnings.len() as u64
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", render_diagnostic(&self.diagnostic()))
+    }
 }
 
 /// Convience wrapper to represent success or meaningul compiler error.
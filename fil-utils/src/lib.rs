@@ -6,9 +6,11 @@ mod math;
 mod position;
 mod reporter;
 
-pub use errors::{Error, FilamentResult};
+pub use errors::{Error, ErrorKind, Failure, FilamentResult, Warning};
 pub use gsym::GSym;
 pub use id::Id;
 pub use math::{all_indices, flat_idx, nd_idx};
 pub use position::{FileIdx, GPosIdx, GlobalPositionTable, PosData};
-pub use reporter::{Diagnostics, InfoIdx};
+pub use reporter::{
+    capture_errors, emit_diagnostic, Diagnostics, ErrorFormat, InfoIdx,
+};
@@ -1,6 +1,6 @@
 //! Tracking of source positions
 use codespan_reporting::{diagnostic::Label, files::SimpleFiles};
-use std::{mem, sync};
+use std::{mem, ops, sync};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 /// Handle to a position in a [PositionTable]
@@ -93,6 +93,30 @@ impl PositionTable {
         &self.indices[pos.0 as usize]
     }
 
+    /// Check if the source text of the span in the file can be shown
+    pub fn has_source(&self, file: usize, span: &ops::Range<usize>) -> bool {
+        self.files.get(file).map_or(false, |f| {
+            !f.source().is_empty() && f.source().get(span.clone()).is_some()
+        })
+    }
+
+    /// Describe a span using its file, line, and offset without relying on
+    /// the source text being available
+    pub fn describe(&self, file: usize, span: &ops::Range<usize>) -> String {
+        let Ok(f) = self.files.get(file) else {
+            return format!("<unknown file> (offset {})", span.start);
+        };
+        match f.source().get(..span.start) {
+            Some(prefix) => format!(
+                "{}:{} (offset {})",
+                f.name(),
+                prefix.matches('\n').count() + 1,
+                span.start
+            ),
+            None => format!("{} (offset {})", f.name(), span.start),
+        }
+    }
+
     /// Return the name and source of the file
     pub fn get_file_data(&self, file: FileIdx) -> (&str, &str) {
         let file = &self.files.get(file.0).unwrap();
@@ -126,6 +150,12 @@ impl GlobalPositionTable {
     pub fn as_ref() -> &'static PositionTable {
         Self::as_mut()
     }
+
+    /// Remove the files and positions added so far. Positions created before
+    /// the reset must not be used afterwards.
+    pub fn reset() {
+        *Self::as_mut() = PositionTable::new();
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
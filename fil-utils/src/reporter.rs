@@ -1,10 +1,199 @@
-use crate::{Error, GPosIdx, GlobalPositionTable};
-use codespan_reporting::term::termcolor::ColorChoice;
+use crate::{
+    errors::INTERNAL_PREAMBLE, Error, ErrorKind, Failure, GPosIdx,
+    GlobalPositionTable,
+};
+use codespan_reporting::term::termcolor::{ColorChoice, NoColor};
 use codespan_reporting::{
-    diagnostic::{Diagnostic, Label, LabelStyle},
+    diagnostic::{Diagnostic, Label, LabelStyle, Severity},
+    files::Files,
     term::{self, termcolor::StandardStream},
 };
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, iter, mem, str::FromStr, sync::Mutex};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Format used to report errors and warnings
+pub enum ErrorFormat {
+    /// Source snippets meant to be read by people
+    #[default]
+    Human,
+    /// A JSON array printed once compilation is done, meant to be read by
+    /// editors and other tools
+    Json,
+}
+
+/// Diagnostics collected to be printed as JSON. `None` when diagnostics are
+/// printed as soon as they are reported.
+static JSON_DIAGNOSTICS: Mutex<Option<Vec<serde_json::Value>>> =
+    Mutex::new(None);
+
+impl ErrorFormat {
+    /// Set the format of all subsequent diagnostics
+    pub fn set(self) {
+        *JSON_DIAGNOSTICS.lock().unwrap() =
+            (self == ErrorFormat::Json).then(Vec::new);
+    }
+
+    /// The current format
+    pub fn get() -> Self {
+        if JSON_DIAGNOSTICS.lock().unwrap().is_some() {
+            ErrorFormat::Json
+        } else {
+            ErrorFormat::Human
+        }
+    }
+
+    /// Print the diagnostics collected since the last call as a JSON array on
+    /// the standard error. Does nothing when diagnostics are printed for
+    /// people.
+    pub fn flush() {
+        if let Some(diags) = JSON_DIAGNOSTICS.lock().unwrap().as_mut() {
+            let diags = serde_json::Value::Array(mem::take(diags));
+            eprintln!("{}", serde_json::to_string_pretty(&diags).unwrap());
+        }
+    }
+}
+
+impl FromStr for ErrorFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(format!(
+                "unknown error format: {s}. Known formats are: human, json"
+            )),
+        }
+    }
+}
+
+/// Describe a diagnostic with the message, the file and byte span of its
+/// primary label, all its labels, and its notes.
+fn to_json(diag: &Diagnostic<usize>) -> serde_json::Value {
+    let files = GlobalPositionTable::as_ref().files();
+    let file = |id: usize| files.name(id).ok();
+    let severity = match diag.severity {
+        Severity::Bug => "bug",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    };
+    let primary = diag.labels.iter().find(|l| l.style == LabelStyle::Primary);
+    let labels = diag
+        .labels
+        .iter()
+        .map(|l| {
+            serde_json::json!({
+                "file": file(l.file_id),
+                "span": { "start": l.range.start, "end": l.range.end },
+                "message": l.message,
+                "primary": l.style == LabelStyle::Primary,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "severity": severity,
+        "message": diag.message,
+        "file": primary.and_then(|l| file(l.file_id)),
+        "span": primary.map(|l| serde_json::json!({
+            "start": l.range.start,
+            "end": l.range.end,
+        })),
+        "labels": labels,
+        "notes": diag.notes,
+    })
+}
+
+/// Errors collected instead of being reported while [capture_errors] runs.
+/// `None` when errors are reported.
+static CAPTURED: Mutex<Option<Vec<Error>>> = Mutex::new(None);
+
+/// Run `f` and return the errors reported while it runs instead of printing
+/// them. Warnings are still collected by [crate::Warning::add].
+pub fn capture_errors<T>(f: impl FnOnce() -> T) -> (T, Vec<Error>) {
+    *CAPTURED.lock().unwrap() = Some(vec![]);
+    let out = f();
+    let errors = CAPTURED.lock().unwrap().take().unwrap_or_default();
+    (out, errors)
+}
+
+/// Collect the error if errors are captured. Returns false if the error must
+/// be reported instead.
+pub(crate) fn capture(err: &Error) -> bool {
+    match CAPTURED.lock().unwrap().as_mut() {
+        Some(errors) => {
+            errors.push(err.clone());
+            true
+        }
+        None => false,
+    }
+}
+
+/// Collect the diagnostic if diagnostics are printed as JSON. Returns false
+/// if the diagnostic must be printed instead.
+pub(crate) fn collect_json(diag: &Diagnostic<usize>) -> bool {
+    match JSON_DIAGNOSTICS.lock().unwrap().as_mut() {
+        Some(diags) => {
+            diags.push(to_json(diag));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Move the labels whose source text cannot be shown, such as synthetic
+/// positions in files without source, to the notes of the diagnostic with the
+/// file, line, and offset of the position instead of a snippet.
+fn with_sources(diag: &Diagnostic<usize>) -> Diagnostic<usize> {
+    let table = GlobalPositionTable::as_ref();
+    let mut diag = diag.clone();
+    let (labels, missing): (Vec<_>, Vec<_>) = mem::take(&mut diag.labels)
+        .into_iter()
+        .partition(|l| table.has_source(l.file_id, &l.range));
+    diag.labels = labels;
+    diag.notes.extend(missing.into_iter().map(|l| {
+        let loc = table.describe(l.file_id, &l.range);
+        if l.message.is_empty() {
+            loc
+        } else {
+            format!("{loc}: {}", l.message)
+        }
+    }));
+    diag
+}
+
+/// Emit a diagnostic using the sources in the [GlobalPositionTable]. The
+/// diagnostic is collected instead when the [ErrorFormat] is JSON or when it
+/// describes an error and errors are captured.
+pub fn emit_diagnostic(writer: &StandardStream, diag: &Diagnostic<usize>) {
+    let is_error = matches!(diag.severity, Severity::Error | Severity::Bug);
+    if (is_error && capture(&Error::from_diagnostic(diag)))
+        || collect_json(diag)
+    {
+        return;
+    }
+    term::emit(
+        &mut writer.lock(),
+        &term::Config::default(),
+        GlobalPositionTable::as_ref().files(),
+        &with_sources(diag),
+    )
+    .unwrap();
+}
+
+/// Render a diagnostic without colors using the sources in the
+/// [GlobalPositionTable].
+pub(crate) fn render_diagnostic(diag: &Diagnostic<usize>) -> String {
+    let mut buf = NoColor::new(Vec::new());
+    term::emit(
+        &mut buf,
+        &term::Config::default(),
+        GlobalPositionTable::as_ref().files(),
+        &with_sources(diag),
+    )
+    .unwrap();
+    String::from_utf8(buf.into_inner()).unwrap()
+}
 
 #[derive(PartialOrd, Ord, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 /// Index for information associated with a [Diagnostic] instance.
@@ -79,9 +268,10 @@ impl Diagnostics {
         }
     }
 
-    /// Report all errors and return the number of errors.
+    /// Report all errors and return the number of errors and whether one of
+    /// them is an internal error.
     /// Returns None if there are no errors.
-    pub fn report_all(&mut self) -> Option<u64> {
+    pub fn report_all(&mut self) -> Option<Failure> {
         let is_tty = atty::is(atty::Stream::Stderr);
         let writer = StandardStream::stderr(if is_tty {
             ColorChoice::Always
@@ -93,10 +283,37 @@ impl Diagnostics {
         }
 
         let mut total = 0;
+        let mut internal = false;
 
         // Deduplicate errors based on the location attached to the error
         let mut error_map = BTreeMap::new();
         for mut error in self.errors.drain(..) {
+            // Internal errors are reported on their own
+            if let ErrorKind::Internal(msg, pos) = &error.kind {
+                internal = true;
+                let labels = pos
+                    .into_option()
+                    .map(|pos| pos.primary().with_message("triggered here"))
+                    .into_iter()
+                    .collect();
+                let notes = iter::once(INTERNAL_PREAMBLE.to_string())
+                    .chain(
+                        error
+                            .notes
+                            .iter()
+                            .map(|n| self.infos[n.0].message.clone()),
+                    )
+                    .collect();
+                total += 1;
+                emit_diagnostic(
+                    &writer,
+                    &Diagnostic::bug()
+                        .with_message(format!("internal compiler error: {msg}"))
+                        .with_labels(labels)
+                        .with_notes(notes),
+                );
+                continue;
+            }
             if !error.notes.is_empty() {
                 // Sort everything except the first element
                 let first = error.notes.remove(0);
@@ -107,7 +324,7 @@ impl Diagnostics {
             error_map
                 .entry(error.notes)
                 .or_insert_with(Vec::new)
-                .push(error.kind);
+                .push(error.kind.to_string());
         }
 
         let table = GlobalPositionTable::as_ref();
@@ -141,19 +358,19 @@ impl Diagnostics {
             };
 
             total += 1;
-            term::emit(
-                &mut writer.lock(),
-                &term::Config::default(),
-                table.files(),
+            emit_diagnostic(
+                &writer,
                 &Diagnostic::error()
                     .with_message(msg)
                     .with_labels(labels)
                     .with_notes(notes),
-            )
-            .unwrap();
+            );
         }
 
-        Some(total)
+        Some(Failure {
+            errors: total,
+            internal,
+        })
     }
 }
 
@@ -0,0 +1,31 @@
+//! Tests for reporting diagnostics at positions whose source cannot be shown.
+use codespan_reporting::diagnostic::Diagnostic;
+use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+use fil_utils::{emit_diagnostic, Error, GPosIdx, GlobalPositionTable};
+
+#[test]
+fn missing_source() {
+    let table = GlobalPositionTable::as_mut();
+    // Synthetic files have no source at all
+    let empty = table.add_file("synthetic.fil".to_string(), String::new());
+    let synthetic = GPosIdx(table.add_pos(empty, 4, 9));
+    // Spans can also extend past the end of the source
+    let short = table.add_file("short.fil".to_string(), "a\nb".to_string());
+    let past_end = GPosIdx(table.add_pos(short, 2, 10));
+
+    // The position is described instead of showing a snippet
+    let msg = Error::parse("unexpected token", synthetic).to_string();
+    assert!(msg.starts_with("error: unexpected token"));
+    assert!(msg.contains("synthetic.fil (offset 4)"));
+    let msg = Error::parse("unexpected token", past_end).to_string();
+    assert!(msg.contains("short.fil:2 (offset 2)"));
+
+    // Emitting the diagnostic directly does not panic either
+    let diag = Diagnostic::error()
+        .with_message("unexpected token")
+        .with_labels(vec![
+            synthetic.primary().with_message("token"),
+            past_end.secondary(),
+        ]);
+    emit_diagnostic(&StandardStream::stderr(ColorChoice::Never), &diag);
+}
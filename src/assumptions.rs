@@ -0,0 +1,27 @@
+//! Reporting of the `assume` facts written in a program.
+use fil_ast as ast;
+use fil_utils::Warning;
+
+/// Warn about every `assume` fact in the program and return the number of
+/// warnings added. Assumptions are trusted by the solver without being
+/// checked, so using them must be acknowledged with `--allow-assume`.
+pub fn report(ns: &ast::Namespace) -> u64 {
+    let warnings: Vec<_> = ns
+        .components
+        .iter()
+        .flat_map(|comp| {
+            comp.assumptions().into_iter().map(|fact| {
+                Warning::new(format!(
+                    "component `{}` assumes a fact that is not checked",
+                    comp.sig.name
+                ))
+                .with_label(fact.cons.pos(), "assumption is trusted by the solver")
+                .with_note("pass --allow-assume to acknowledge the assumptions in the program")
+            })
+        })
+        .collect();
+
+    let count = warnings.len() as u64;
+    warnings.into_iter().for_each(Warning::add);
+    count
+}
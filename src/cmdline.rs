@@ -1,7 +1,9 @@
 use argh::FromArgs;
+use serde::{Deserialize, Serialize};
 use std::{path::PathBuf, str::FromStr};
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 /// Solver to use in the pass
 pub enum Solver {
     #[default]
@@ -26,7 +28,8 @@ impl FromStr for Solver {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Backend {
     #[default]
     Verilog,
@@ -46,63 +49,423 @@ impl FromStr for Backend {
     }
 }
 
-#[derive(FromArgs, Debug)]
-/// The Filament pipeline verifier
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// Encoding of the FSMs generated for the events of a component
+pub enum FsmEncoding {
+    /// Chains of binary counters
+    #[default]
+    Binary,
+    /// A shift register with one bit per state
+    OneHot,
+    /// Chains of counters that count in gray code
+    Gray,
+}
+
+impl FromStr for FsmEncoding {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "binary" => Ok(FsmEncoding::Binary),
+            "onehot" => Ok(FsmEncoding::OneHot),
+            "gray" => Ok(FsmEncoding::Gray),
+            _ => Err(format!(
+                "unknown fsm encoding: {s}. Known encodings are: binary, onehot, gray"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+/// Model used to discharge the constraints generated by the checker
+pub enum IntervalModel {
+    /// Discharge all constraints using the solver
+    #[default]
+    Exact,
+    /// Decide constraints using numeric bounds and only use the solver for
+    /// the ones that cannot be decided
+    IntervalAbstract,
+}
+
+impl FromStr for IntervalModel {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(IntervalModel::Exact),
+            "interval-abstract" => Ok(IntervalModel::IntervalAbstract),
+            _ => Err(format!(
+                "unknown interval model: {s}. Known models are: exact, interval-abstract"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for IntervalModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntervalModel::Exact => write!(f, "exact"),
+            IntervalModel::IntervalAbstract => write!(f, "interval-abstract"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// How much of the program is checked by `--check`. Each level runs a prefix
+/// of the pass pipeline.
+pub enum CheckLevel {
+    /// Only type check the program
+    Types,
+    /// Type check the program and check the availability intervals without
+    /// discharging the constraints
+    Intervals,
+    /// Run all checks and discharge the constraints
+    All,
+}
+
+impl FromStr for CheckLevel {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "types" => Ok(CheckLevel::Types),
+            "intervals" => Ok(CheckLevel::Intervals),
+            "all" => Ok(CheckLevel::All),
+            _ => Err(format!(
+                "unknown check level: {s}. Known levels are: types, intervals, all"
+            )),
+        }
+    }
+}
+
+#[derive(FromArgs, Debug, Clone, Serialize, Deserialize)]
+/// The Filament pipeline verifier. Plans record the options that affect the
+/// compilation. The paths of the files written by the compiler and the
+/// options that only change how the results are reported are skipped.
 pub struct Opts {
-    /// path to the primitives library
+    /// path to the input program or `-` to read it from the standard input
     #[argh(positional)]
+    #[serde(skip)]
     pub input: PathBuf,
 
+    /// print the version of Filament and of the Calyx crates it was built
+    /// with. The input program is not required
+    #[argh(switch, long = "version")]
+    #[serde(skip)]
+    pub version: bool,
+
     /// print out the IR
     #[argh(option, long = "dump-after")]
+    #[serde(skip)]
     pub dump_after: Vec<String>,
 
     /// print out assignments that falsify the constraints
     #[argh(switch, long = "show-models")]
+    #[serde(skip)]
     pub show_models: bool,
 
-    /// path to search for imports
-    #[argh(option, long = "library", short = 'l', default = "\".\".into()")]
-    pub library: PathBuf,
+    /// path to search for imports that are not found relative to the
+    /// importing file (default: .). Can be repeated to search several
+    /// directories
+    #[argh(option, long = "library", short = 'l')]
+    pub library: Vec<PathBuf>,
 
     /// only check the program without compilation.
     #[argh(switch, short = 'c', long = "check")]
     pub check: bool,
 
+    /// only check the program up to the given level: types, intervals, or
+    /// all. Can also be written as `--check=<level>`. Implies `--check`
+    #[argh(option, long = "check-level")]
+    pub check_level: Option<CheckLevel>,
+
+    /// recompile the program whenever the input file or one of its imports
+    /// changes
+    #[argh(switch, long = "watch")]
+    #[serde(skip)]
+    pub watch: bool,
+
+    /// stop watching after recompiling the given number of times
+    #[argh(option, long = "watch-limit")]
+    #[serde(skip)]
+    pub watch_limit: Option<u64>,
+
+    /// only check the bitwidths and bundle accesses of the program. Skips the
+    /// checks on intervals for fast feedback
+    #[argh(switch, long = "check-widths-only")]
+    pub check_widths_only: bool,
+
+    /// report an error for ports wider than the given number of bits. Only
+    /// widths that do not depend on parameters are checked
+    #[argh(option, long = "max-port-width")]
+    pub max_port_width: Option<u64>,
+
     /// output the interface.json for the input program
     #[argh(switch, long = "dump-interface")]
     pub dump_interface: bool,
 
+    /// output the interface of every specialization of the components in the
+    /// input program, keyed by the values bound to their parameters
+    #[argh(switch, long = "dump-specialized-interfaces")]
+    pub dump_specialized_interfaces: bool,
+
+    /// explain how the interval of the given port is derived. Ports are
+    /// named using <comp>.<port> or <comp>.<invoke>.<port>
+    #[argh(option, long = "explain-interval")]
+    pub explain_interval: Option<String>,
+
+    /// write the call graph of the program in the DOT format to the given
+    /// file. Invocation edges are labeled with the times they bind.
+    #[argh(option, long = "callgraph")]
+    #[serde(skip)]
+    pub callgraph: Option<PathBuf>,
+
+    /// check whether the availability intervals of the two ports given after
+    /// the input program overlap. Ports are named as <comp>.<port>
+    #[argh(switch, long = "overlap")]
+    pub overlap: bool,
+
+    /// the ports compared by --overlap or the new interface dump compared by
+    /// --interface-diff
+    #[argh(positional)]
+    pub ports: Vec<String>,
+
+    /// print the constraints that the signature of the given component places
+    /// on its parameters and events in JSON format
+    #[argh(option, long = "dump-constraints-json")]
+    pub dump_constraints_json: Option<String>,
+
+    /// print the `extern` definition that other programs can use to
+    /// instantiate the compiled module of the given component
+    #[argh(option, long = "emit-extern-stub")]
+    pub emit_extern_stub: Option<String>,
+
+    /// compare two interface dumps given as `--interface-diff <old.json>
+    /// <new.json>` and report breaking changes
+    #[argh(switch, long = "interface-diff")]
+    #[serde(skip)]
+    pub interface_diff: bool,
+
+    /// write the order in which definitions dominate each other after
+    /// domination is built to the given file
+    #[argh(option, long = "dump-dominance")]
+    #[serde(skip)]
+    pub dump_dominance: Option<PathBuf>,
+
+    /// write the files the program depends on to the given file as a make
+    /// rule for the file given by --output
+    #[argh(option, long = "dump-dep-file")]
+    #[serde(skip)]
+    pub dump_dep_file: Option<PathBuf>,
+
+    /// write the tokens of the input program as JSON to the given file
+    #[argh(option, long = "tokens")]
+    #[serde(skip)]
+    pub tokens: Option<PathBuf>,
+
     /// set log level
     #[argh(option, long = "log", default = "log::LevelFilter::Warn")]
+    #[serde(skip, default = "default_log_level")]
     pub log_level: log::LevelFilter,
 
     /// set toplevel
     #[argh(option, long = "toplevel", default = "\"main\".into()")]
     pub toplevel: String,
 
+    /// only compile the components tagged with `@group("<tag>")` and the
+    /// components they instantiate
+    #[argh(option, long = "only-tagged")]
+    pub only_tagged: Option<String>,
+
     /// skip the discharge pass (unsafe)
     #[argh(switch, long = "unsafe-skip-discharge")]
     pub unsafe_skip_discharge: bool,
 
+    /// treat warnings as errors
+    #[argh(switch, long = "deny-warnings")]
+    pub deny_warnings: bool,
+
+    /// format of the errors and warnings (default: human): human, or json to
+    /// print them as a JSON array once compilation is done
+    #[argh(
+        option,
+        long = "error-format",
+        default = "fil_utils::ErrorFormat::Human"
+    )]
+    #[serde(skip)]
+    pub error_format: fil_utils::ErrorFormat,
+
+    /// warn about extern components in the input file that are never
+    /// instantiated
+    #[argh(switch, long = "warn-unused-externs")]
+    pub warn_unused_externs: bool,
+
+    /// allow `assume` facts in the program without warnings. Assumptions are
+    /// trusted by the solver without being checked
+    #[argh(switch, long = "allow-assume")]
+    pub allow_assume: bool,
+
+    /// warn about phantom events that are not mentioned by any constraint in
+    /// the signature of their component
+    #[argh(switch, long = "warn-unconstrained-phantoms")]
+    pub warn_unconstrained_phantoms: bool,
+
+    /// warn about invocations whose outputs only forward their inputs and
+    /// are reconnected unchanged
+    #[argh(switch, long = "warn-pass-through")]
+    pub warn_pass_through: bool,
+
+    /// behavior of constant folding when time arithmetic overflows
+    /// (default: error): error, saturate, wrap
+    #[argh(
+        option,
+        long = "time-overflow",
+        default = "fil_ir::TimeOverflow::Error"
+    )]
+    pub time_overflow: fil_ir::TimeOverflow,
+
+    /// print a per-component summary of the constraints checked on the
+    /// standard error once all constraints are proven
+    #[argh(switch, long = "summarize-constraints")]
+    pub summarize_constraints: bool,
+
+    /// print the number of assertions discharged by the solver, folded
+    /// trivially, and assumed on the standard error once all constraints are
+    /// proven
+    #[argh(switch, long = "discharge-summary")]
+    #[serde(skip)]
+    pub discharge_summary: bool,
+
+    /// print the number of proof obligations, how many were discharged
+    /// trivially, by numeric bounds, or by the solver, and how many failed on
+    /// the standard error
+    #[argh(switch, long = "stats")]
+    #[serde(skip)]
+    pub stats: bool,
+
+    /// write the components generated by monomorphization, in dependency
+    /// order, with their number of instantiations and compile times to the
+    /// given file
+    #[argh(option, long = "compile-log")]
+    #[serde(skip)]
+    pub compile_log: Option<PathBuf>,
+
+    /// report each instantiation handled by monomorphization with its
+    /// parameter bindings and the component generated for it
+    #[argh(switch, long = "trace-monomorphization")]
+    #[serde(skip)]
+    pub trace_monomorphization: bool,
+
+    /// write the pass pipeline and compilation options to the given file
+    #[argh(option, long = "dump-plan")]
+    #[serde(skip)]
+    pub dump_plan: Option<PathBuf>,
+
+    /// load the pass pipeline and compilation options from the given file
+    #[argh(option, long = "load-plan")]
+    #[serde(skip)]
+    pub load_plan: Option<PathBuf>,
+
+    /// write a JSON manifest of the files written by the compilation, with
+    /// their sizes and hashes, to the given file
+    #[argh(option, long = "emit-manifest")]
+    #[serde(skip)]
+    pub emit_manifest: Option<PathBuf>,
+
+    /// write the wall-clock time of each pass and of the whole compilation to
+    /// the given file as JSON
+    #[argh(option, long = "timing-json")]
+    #[serde(skip)]
+    pub timing_json: Option<PathBuf>,
+
     // `gen` options
     /// the output directory to store files generated from external tools
     #[argh(option, long = "out-dir")]
+    #[serde(skip)]
     pub out_dir: Option<PathBuf>,
 
     // Backend options
     /// backend to use (default: verilog): calyx, verilog
     #[argh(option, long = "backend", default = "Backend::Verilog")]
     pub backend: Backend,
-    /// disable generation of slow FSMs in the backend
+    /// write the compiled program to the given file instead of the standard
+    /// output. Missing parent directories are created
+    #[argh(option, long = "output")]
+    #[serde(skip)]
+    pub output: Option<PathBuf>,
+    /// encoding of the FSMs generated in the backend (default: binary):
+    /// binary, onehot, gray
+    #[argh(option, long = "fsm-encoding", default = "FsmEncoding::Binary")]
+    pub fsm_encoding: FsmEncoding,
+    /// disable generation of slow FSMs in the backend. Implies
+    /// `--fsm-encoding onehot`
     #[argh(switch, long = "disable-slow-fsms")]
     pub disable_slow_fsms: bool,
+    /// merge components that are identical after monomorphization. Components
+    /// marked `@opaque` are never merged
+    #[argh(switch, long = "dedup-components")]
+    pub dedup_components: bool,
+    /// do not remove instances whose outputs are never read before
+    /// compilation
+    #[argh(switch, long = "keep-dead")]
+    pub keep_dead: bool,
+    /// report output ports that are never assigned as warnings instead of
+    /// errors and drive them with zero
+    #[argh(switch, long = "allow-unconnected-outputs")]
+    pub allow_unconnected_outputs: bool,
+    /// do not warn about events in the signature of a component that are
+    /// never used
+    #[argh(switch, long = "allow-unused-events")]
+    pub allow_unused_events: bool,
+    /// JSON file mapping the hardware generated by compilation (register,
+    /// adder) to custom Calyx primitives
+    #[argh(option, long = "primitive-map")]
+    pub primitive_map: Option<PathBuf>,
     /// preserves original port names during compilation.
     #[argh(switch, long = "preserve-names")]
     pub preserve_names: bool,
+    /// prefix added to the names of all generated modules and instances
+    #[argh(option, long = "name-prefix")]
+    pub name_prefix: Option<String>,
+    /// check that compiling through the Calyx text format generates the same
+    /// verilog as direct compilation
+    #[argh(switch, long = "cross-check")]
+    pub cross_check: bool,
+    /// print the number of adders, muxes, registers, and memories used by each
+    /// component instead of generating code
+    #[argh(switch, long = "resource-estimate")]
+    pub resource_estimate: bool,
+    /// append the source of extern modules that the backend does not already
+    /// include to the generated verilog
+    #[argh(switch, long = "inline-externs")]
+    pub inline_externs: bool,
+    /// embed SystemVerilog assertions that check the availability intervals
+    /// of the toplevel ports in the generated verilog
+    #[argh(switch, long = "emit-sva")]
+    pub emit_sva: bool,
+    /// write the cycles in which the ports of the toplevel component carry
+    /// valid data to the given file for use with VCD post-processors
+    #[argh(option, long = "emit-timing-hints")]
+    #[serde(skip)]
+    pub emit_timing_hints: Option<PathBuf>,
+    /// write the components of the program, with their ports, instances,
+    /// invocations, and interval signatures, to the given file as JSON
+    #[argh(option, long = "emit-json")]
+    #[serde(skip)]
+    pub emit_json: Option<PathBuf>,
+    /// write a graph of the instances of the monomorphized program, with the
+    /// connections and invocations between them, to the given file in the
+    /// DOT format
+    #[argh(option, long = "emit-dot")]
+    #[serde(skip)]
+    pub emit_dot: Option<PathBuf>,
+    /// strip machine-specific data, such as absolute paths, from the output
+    /// so that it does not depend on where the compiler is run
+    #[argh(switch, long = "reproducible")]
+    pub reproducible: bool,
 
     // Solver specific configuration
-    /// solver to use (default: cvc5): cvc5, z3
+    /// solver to use (default: z3): z3, cvc5, boolector, bitwuzla
     #[argh(option, long = "solver", default = "Solver::Z3")]
     pub solver: Solver,
     /// solve assertions separately rather than all at once
@@ -110,8 +473,226 @@ pub struct Opts {
     pub discharge_separate: bool,
     /// dump interactions with the solver in the given file
     #[argh(option, long = "dump-solver-log")]
+    #[serde(skip)]
     pub solver_replay_file: Option<String>,
+    /// timeout in milliseconds for each query to the solver. Constraints that
+    /// the solver cannot decide in time are reported as unproven
+    #[argh(option, long = "smt-timeout")]
+    pub smt_timeout: Option<u64>,
+    /// send every query to the solver instead of reusing the result of an
+    /// identical query from another component
+    #[argh(switch, long = "no-smt-cache")]
+    pub no_smt_cache: bool,
+    /// number of solvers used to discharge the proof obligations in parallel
+    /// (default: 1)
+    #[argh(option, long = "jobs", short = 'j', default = "1")]
+    pub jobs: usize,
     /// use bitvector encoding for proofs
     #[argh(option, long = "solver-bv")]
     pub solver_bv: Option<u8>,
+    /// model used to discharge constraints (default: exact): exact,
+    /// interval-abstract. The abstract model decides constraints using
+    /// numeric bounds and only queries the solver when they are inconclusive
+    #[argh(option, long = "interval-model", default = "IntervalModel::Exact")]
+    pub interval_model: IntervalModel,
+}
+
+impl Opts {
+    /// Parse the options from the command line arguments. `--check=<level>`
+    /// is accepted as a shorthand for `--check-level <level>`.
+    pub fn from_env() -> Self {
+        let args: Vec<String> = std::env::args()
+            .flat_map(|arg| match arg.strip_prefix("--check=") {
+                Some(level) => {
+                    vec!["--check-level".to_string(), level.to_string()]
+                }
+                None => vec![arg],
+            })
+            .collect();
+        let cmd = args[0].rsplit('/').next().unwrap_or(&args[0]);
+        let rest: Vec<&str> = args[1..].iter().map(String::as_str).collect();
+        let mut opts = match Self::from_args(&[cmd], &rest) {
+            Ok(opts) => opts,
+            Err(argh::EarlyExit { output, status }) => {
+                // The version does not require an input program so the
+                // arguments are parsed again with a placeholder for it
+                let placeholder = [&rest[..], &["-"]].concat();
+                if let Ok(opts) = Self::from_args(&[cmd], &placeholder) {
+                    if opts.version {
+                        return opts;
+                    }
+                }
+                if status.is_ok() {
+                    println!("{output}");
+                    std::process::exit(0)
+                }
+                eprintln!("{output}\nRun {cmd} --help for more information.");
+                std::process::exit(1)
+            }
+        };
+        if opts.interface_diff {
+            if opts.overlap || opts.ports.len() != 1 {
+                eprintln!("--interface-diff expects the old and the new interface dumps\nRun {cmd} --help for more information.");
+                std::process::exit(1)
+            }
+            return opts;
+        }
+        match (opts.overlap, opts.ports.len()) {
+            (true, 2) | (false, 0) => (),
+            (true, n) => {
+                eprintln!("--overlap expects two ports but found {n}\nRun {cmd} --help for more information.");
+                std::process::exit(1)
+            }
+            (false, _) => {
+                eprintln!("Unrecognized argument: {}\nRun {cmd} --help for more information.", opts.ports[0]);
+                std::process::exit(1)
+            }
+        }
+        if opts.unsafe_skip_discharge
+            && (opts.summarize_constraints
+                || opts.discharge_summary
+                || opts.stats)
+        {
+            eprintln!("--summarize-constraints, --discharge-summary, and --stats report the constraints checked and cannot be used with --unsafe-skip-discharge\nRun {cmd} --help for more information.");
+            std::process::exit(1)
+        }
+        opts.check |= opts.check_level.is_some();
+        opts
+    }
+
+    /// Replace the options recorded by plans with the ones of `plan`. The
+    /// options that plans skip, like the input file, are kept.
+    pub fn restore(&mut self, plan: Opts) {
+        // All the options are named so that new options must be recorded or
+        // skipped explicitly
+        let Opts {
+            input: _,
+            version: _,
+            dump_after: _,
+            show_models: _,
+            library,
+            check,
+            check_level,
+            watch: _,
+            watch_limit: _,
+            check_widths_only,
+            max_port_width,
+            dump_interface,
+            dump_specialized_interfaces,
+            explain_interval,
+            callgraph: _,
+            overlap,
+            ports,
+            dump_constraints_json,
+            emit_extern_stub,
+            interface_diff: _,
+            dump_dominance: _,
+            dump_dep_file: _,
+            tokens: _,
+            log_level: _,
+            toplevel,
+            only_tagged,
+            unsafe_skip_discharge,
+            deny_warnings,
+            error_format: _,
+            warn_unused_externs,
+            allow_assume,
+            warn_unconstrained_phantoms,
+            warn_pass_through,
+            time_overflow,
+            summarize_constraints,
+            discharge_summary: _,
+            stats: _,
+            compile_log: _,
+            trace_monomorphization: _,
+            dump_plan: _,
+            load_plan: _,
+            emit_manifest: _,
+            timing_json: _,
+            out_dir: _,
+            backend,
+            output: _,
+            fsm_encoding,
+            disable_slow_fsms,
+            dedup_components,
+            keep_dead,
+            allow_unconnected_outputs,
+            allow_unused_events,
+            primitive_map,
+            preserve_names,
+            name_prefix,
+            cross_check,
+            resource_estimate,
+            inline_externs,
+            emit_sva,
+            emit_timing_hints: _,
+            emit_json: _,
+            emit_dot: _,
+            reproducible,
+            solver,
+            discharge_separate,
+            solver_replay_file: _,
+            smt_timeout,
+            no_smt_cache,
+            jobs,
+            solver_bv,
+            interval_model,
+        } = plan;
+        self.library = library;
+        self.check = check;
+        self.check_level = check_level;
+        self.check_widths_only = check_widths_only;
+        self.max_port_width = max_port_width;
+        self.dump_interface = dump_interface;
+        self.dump_specialized_interfaces = dump_specialized_interfaces;
+        self.explain_interval = explain_interval;
+        self.overlap = overlap;
+        self.ports = ports;
+        self.dump_constraints_json = dump_constraints_json;
+        self.emit_extern_stub = emit_extern_stub;
+        self.toplevel = toplevel;
+        self.only_tagged = only_tagged;
+        self.unsafe_skip_discharge = unsafe_skip_discharge;
+        self.deny_warnings = deny_warnings;
+        self.warn_unused_externs = warn_unused_externs;
+        self.allow_assume = allow_assume;
+        self.warn_unconstrained_phantoms = warn_unconstrained_phantoms;
+        self.warn_pass_through = warn_pass_through;
+        self.time_overflow = time_overflow;
+        self.summarize_constraints = summarize_constraints;
+        self.backend = backend;
+        self.fsm_encoding = fsm_encoding;
+        self.disable_slow_fsms = disable_slow_fsms;
+        self.dedup_components = dedup_components;
+        self.keep_dead = keep_dead;
+        self.allow_unconnected_outputs = allow_unconnected_outputs;
+        self.allow_unused_events = allow_unused_events;
+        self.primitive_map = primitive_map;
+        self.preserve_names = preserve_names;
+        self.name_prefix = name_prefix;
+        self.cross_check = cross_check;
+        self.resource_estimate = resource_estimate;
+        self.inline_externs = inline_externs;
+        self.emit_sva = emit_sva;
+        self.reproducible = reproducible;
+        self.solver = solver;
+        self.discharge_separate = discharge_separate;
+        self.smt_timeout = smt_timeout;
+        self.no_smt_cache = no_smt_cache;
+        self.jobs = jobs;
+        self.solver_bv = solver_bv;
+        self.interval_model = interval_model;
+    }
+
+    /// The checks to run before returning or `None` if the program is
+    /// compiled. `--check` without a level runs all the checks.
+    pub fn check_level(&self) -> Option<CheckLevel> {
+        self.check
+            .then(|| self.check_level.unwrap_or(CheckLevel::All))
+    }
+}
+
+/// The log level of options loaded from a plan
+fn default_log_level() -> log::LevelFilter {
+    log::LevelFilter::Warn
 }
@@ -0,0 +1,60 @@
+//! Compilation of Filament programs to Calyx for use as a library.
+use crate::{
+    cmdline, ir_passes as ip,
+    pipeline::{self, Pipeline, Stage},
+    resolver::Resolver,
+};
+use calyx_ir as calyx;
+use fil_utils::{self as utils, Error, Failure, FilamentResult};
+
+/// Run the full pass pipeline on the program described by `opts` and return
+/// the generated Calyx program without printing anything. The errors found in
+/// the program are returned instead of being reported. Options that only
+/// affect the output of the binary, like the backend, the debugging dumps, or
+/// `--check`, are ignored.
+///
+/// The warnings found in the program are kept until they are taken with
+/// [utils::Warning::take_all].
+///
+/// Modules produced by generators are deleted once compilation finishes
+/// unless `opts.out_dir` is set, so programs that use them must set it to
+/// compile the returned context further.
+pub fn compile(opts: &cmdline::Opts) -> FilamentResult<calyx::Context> {
+    // Dumping the program is left to the binary
+    let opts = cmdline::Opts {
+        dump_after: vec![],
+        ..opts.clone()
+    };
+    let (out, errors) = utils::capture_errors(|| lower(&opts));
+    out.map_err(|failure| {
+        let mut errors = errors.into_iter();
+        match errors.next() {
+            Some(first) => first.with_others(errors.collect()),
+            // Only warnings fail compilation without reporting an error
+            None => Error::misc(format!(
+                "compilation failed because of {failure} warnings"
+            )),
+        }
+    })
+}
+
+/// Compile the program described by `opts` to Calyx
+fn lower(opts: &cmdline::Opts) -> Result<calyx::Context, Failure> {
+    let report = |e: Error| {
+        e.report();
+        Failure::from(&e)
+    };
+    let ns =
+        Pipeline::parse(opts, &mut Resolver::from(opts)).map_err(report)?;
+    if !opts.unsafe_skip_discharge {
+        ip::Discharge::find_solver(opts).map_err(report)?;
+    }
+    let mut pipeline = Pipeline::new(opts, &ns);
+
+    let mut ir = pipeline.astconv(ns)?;
+    for stage in Stage::COMPILE {
+        ir = pipeline.run(stage, ir)?;
+    }
+    let prims = pipeline::primitives(opts).map_err(report)?;
+    pipeline.compile(ir, &prims).map_err(report)
+}
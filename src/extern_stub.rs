@@ -0,0 +1,78 @@
+//! Generation of `extern` definitions for compiled components.
+use crate::cmdline;
+use fil_ast as ast;
+use fil_utils::{Error, FilamentResult};
+use std::path::Path;
+
+/// Ports that every compiled module has. Components do not mention them in
+/// their signature but externs must declare them to be connected.
+const INTERFACE_PORTS: [&str; 2] = ["clk", "reset"];
+
+/// Generate the `extern` definition that a program would use to instantiate
+/// the compiled module of the component `name` as a black box.
+/// The stub for a component uses the name of the module that the component
+/// is compiled to and refers to the file given by `--output`, or to
+/// `<module>.sv` without it. Parametric components are compiled to a module
+/// for every instantiation and have no stub. The stub for an extern component
+/// refers to the file that defines it.
+pub fn generate(
+    ns: &ast::Namespace,
+    name: &str,
+    opts: &cmdline::Opts,
+) -> FilamentResult<String> {
+    let ext = ns.externs.iter().find_map(|ext| {
+        let sig = ext.comps.iter().find(|sig| sig.name.inner() == name)?;
+        Some((sig.clone(), file_name(Path::new(&ext.path))))
+    });
+    let (mut sig, file) = match ext {
+        Some(ext) => ext,
+        None => {
+            let comp = ns
+                .components
+                .iter()
+                .find(|comp| comp.sig.name.inner() == name)
+                .ok_or_else(|| {
+                    Error::misc(format!("Unknown component `{name}`"))
+                })?;
+            if !comp.sig.params.is_empty() {
+                return Err(Error::misc(format!(
+                    "Cannot generate a stub for `{name}` because parametric components are compiled to a module for every instantiation"
+                ))
+                .with_pos(comp.sig.name.pos()));
+            }
+            let mut sig = comp.sig.clone();
+            // Modules are named like the lowering names compiled components
+            let module =
+                format!("{}{name}", opts.name_prefix.as_deref().unwrap_or(""));
+            sig.name = ast::Loc::new(module.as_str().into(), sig.name.pos());
+            let file = opts
+                .output
+                .as_deref()
+                .map_or_else(|| format!("{module}.sv"), file_name);
+            (sig, file)
+        }
+    };
+
+    let missing = INTERFACE_PORTS
+        .iter()
+        .filter(|port| !sig.unannotated_ports.iter().any(|(p, _)| *p == **port))
+        .map(|port| (ast::Id::from(*port), 1))
+        .collect::<Vec<_>>();
+    sig.unannotated_ports =
+        missing.into_iter().chain(sig.unannotated_ports).collect();
+
+    let sig = ast::Printer::signature(&sig)
+        .lines()
+        .map(|l| format!("  {l}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(format!("extern \"{file}\" {{\n{sig};\n}}\n"))
+}
+
+/// The name of the file at `path`, which externs resolve next to the program
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.to_string_lossy().to_string(),
+        |f| f.to_string_lossy().to_string(),
+    )
+}
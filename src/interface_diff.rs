@@ -0,0 +1,254 @@
+//! Structured comparison of two interface dumps generated by `--dump-interface`.
+use fil_utils::{Error, FilamentResult};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+#[derive(Deserialize)]
+/// An interface signal in the interface dump
+struct Signal {
+    event: String,
+    delay: u64,
+}
+
+#[derive(Deserialize)]
+/// An output port that is high when an event occurs
+struct OutputSignal {
+    name: String,
+    event: String,
+}
+
+#[derive(Deserialize)]
+/// A port in the interface dump
+struct PortInfo {
+    event: String,
+    name: String,
+    width: u64,
+    start: u64,
+    end: u64,
+}
+
+impl PortInfo {
+    fn interval(&self) -> String {
+        format!(
+            "['{ev}+{}, '{ev}+{}]",
+            self.start,
+            self.end,
+            ev = self.event
+        )
+    }
+
+    /// Returns true if the interval of `self` is contained in `other`.
+    fn within(&self, other: &PortInfo) -> bool {
+        self.event == other.event
+            && self.start >= other.start
+            && self.end <= other.end
+    }
+}
+
+#[derive(Deserialize)]
+/// The interface of a component as generated by `--dump-interface`
+struct Interface {
+    interfaces: Vec<Signal>,
+    #[serde(default)]
+    output_interfaces: Vec<OutputSignal>,
+    inputs: Vec<PortInfo>,
+    outputs: Vec<PortInfo>,
+}
+
+impl Interface {
+    fn load(path: &Path) -> FilamentResult<Self> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            Error::invalid_file(format!(
+                "Failed to read {}: {err}",
+                path.to_string_lossy()
+            ))
+        })?;
+        serde_json::from_str(&content).map_err(|err| {
+            Error::malformed(format!(
+                "Failed to parse interface {}: {err}",
+                path.to_string_lossy()
+            ))
+        })
+    }
+}
+
+#[derive(Serialize)]
+/// A single change between two interfaces
+struct Change {
+    kind: &'static str,
+    name: String,
+    breaking: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new: Option<String>,
+}
+
+#[derive(Serialize)]
+/// The report generated by comparing two interfaces
+pub struct InterfaceDiff {
+    breaking: bool,
+    changes: Vec<Change>,
+}
+
+impl InterfaceDiff {
+    /// Compare the interface dumps at `old` and `new`.
+    pub fn compute(old: &Path, new: &Path) -> FilamentResult<Self> {
+        let old = Interface::load(old)?;
+        let new = Interface::load(new)?;
+        let mut changes = vec![];
+
+        // Interface signals are identified by their events
+        for sig in &old.interfaces {
+            match new.interfaces.iter().find(|s| s.event == sig.event) {
+                None => changes.push(Change {
+                    kind: "removed-event",
+                    name: sig.event.clone(),
+                    breaking: true,
+                    old: Some(sig.delay.to_string()),
+                    new: None,
+                }),
+                Some(n) if n.delay != sig.delay => changes.push(Change {
+                    kind: "delay-changed",
+                    name: sig.event.clone(),
+                    breaking: true,
+                    old: Some(sig.delay.to_string()),
+                    new: Some(n.delay.to_string()),
+                }),
+                Some(_) => (),
+            }
+        }
+        for sig in &new.interfaces {
+            if !old.interfaces.iter().any(|s| s.event == sig.event) {
+                changes.push(Change {
+                    kind: "added-event",
+                    name: sig.event.clone(),
+                    breaking: true,
+                    old: None,
+                    new: Some(sig.delay.to_string()),
+                })
+            }
+        }
+
+        Self::output_interfaces(
+            &old.output_interfaces,
+            &new.output_interfaces,
+            &mut changes,
+        );
+        Self::ports(&old.inputs, &new.inputs, true, &mut changes);
+        Self::ports(&old.outputs, &new.outputs, false, &mut changes);
+
+        Ok(Self {
+            breaking: changes.iter().any(|c| c.breaking),
+            changes,
+        })
+    }
+
+    /// Compare the output interface ports, identified by their names.
+    /// Users may ignore new output interfaces but rely on the existing ones
+    /// being high when their event occurs.
+    fn output_interfaces(
+        old: &[OutputSignal],
+        new: &[OutputSignal],
+        changes: &mut Vec<Change>,
+    ) {
+        for sig in old {
+            match new.iter().find(|s| s.name == sig.name) {
+                None => changes.push(Change {
+                    kind: "removed-output-interface",
+                    name: sig.name.clone(),
+                    breaking: true,
+                    old: Some(sig.event.clone()),
+                    new: None,
+                }),
+                Some(n) if n.event != sig.event => changes.push(Change {
+                    kind: "output-interface-changed",
+                    name: sig.name.clone(),
+                    breaking: true,
+                    old: Some(sig.event.clone()),
+                    new: Some(n.event.clone()),
+                }),
+                Some(_) => (),
+            }
+        }
+        for sig in new {
+            if !old.iter().any(|s| s.name == sig.name) {
+                changes.push(Change {
+                    kind: "added-output-interface",
+                    name: sig.name.clone(),
+                    breaking: false,
+                    old: None,
+                    new: Some(sig.event.clone()),
+                })
+            }
+        }
+    }
+
+    /// Compare the ports in the interface.
+    /// An input may require its value for a shorter interval and an output may
+    /// provide its value for a longer interval without breaking users.
+    fn ports(
+        old: &[PortInfo],
+        new: &[PortInfo],
+        is_input: bool,
+        changes: &mut Vec<Change>,
+    ) {
+        for port in old {
+            let Some(n) = new.iter().find(|p| p.name == port.name) else {
+                changes.push(Change {
+                    kind: "removed-port",
+                    name: port.name.clone(),
+                    breaking: true,
+                    old: Some(port.interval()),
+                    new: None,
+                });
+                continue;
+            };
+            if n.width != port.width {
+                changes.push(Change {
+                    kind: "width-changed",
+                    name: port.name.clone(),
+                    breaking: true,
+                    old: Some(port.width.to_string()),
+                    new: Some(n.width.to_string()),
+                });
+            }
+            if n.interval() != port.interval() {
+                let compatible = if is_input {
+                    n.within(port)
+                } else {
+                    port.within(n)
+                };
+                changes.push(Change {
+                    kind: "interval-changed",
+                    name: port.name.clone(),
+                    breaking: !compatible,
+                    old: Some(port.interval()),
+                    new: Some(n.interval()),
+                });
+            }
+        }
+        for port in new {
+            if !old.iter().any(|p| p.name == port.name) {
+                // New inputs must be driven by existing users
+                changes.push(Change {
+                    kind: "added-port",
+                    name: port.name.clone(),
+                    breaking: is_input,
+                    old: None,
+                    new: Some(port.interval()),
+                });
+            }
+        }
+    }
+
+    /// Returns true if the changes break users of the old interface.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking
+    }
+
+    /// Print out the report in JSON format
+    pub fn print(&self) {
+        println!("{}", serde_json::to_string_pretty(self).unwrap());
+    }
+}
@@ -3,21 +3,27 @@ use crate::{
     ir_visitor::{Action, Construct, Visitor, VisitorData},
 };
 use fil_ir::{self as ir, Connect, Context, Ctx, DisplayCtx, PortIdx};
-use fil_utils::{self as utils, Diagnostics, Error, GPosIdx};
+use fil_utils::{self as utils, Diagnostics, Error, Failure, GPosIdx};
 use itertools::Itertools;
 use linked_hash_map::LinkedHashMap;
+use std::collections::HashSet;
 
-/// Makes sure each index in a port is only written to at most once
+/// Makes sure each index in a port is only written to at most once and
+/// reports all the connects that drive the same location.
 /// Must occur after monomorphization.
 pub struct AssignCheck {
     ports: LinkedHashMap<(PortIdx, usize), Vec<Option<GPosIdx>>>,
+    /// Output ports that are never assigned are reported by
+    /// [super::UndrivenOutputs] and allowed with `--allow-unconnected-outputs`
+    allow_unconnected: bool,
     diag: Diagnostics,
 }
 
 impl Construct for AssignCheck {
-    fn from(_: &cmdline::Opts, _: &mut Context) -> Self {
+    fn from(opts: &cmdline::Opts, _: &mut Context) -> Self {
         Self {
             ports: LinkedHashMap::new(),
+            allow_unconnected: opts.allow_unconnected_outputs,
             diag: Diagnostics::default(),
         }
     }
@@ -95,8 +101,18 @@ impl Visitor for AssignCheck {
         // Track all the port locations that have no assignment
         let mut unassigned: LinkedHashMap<PortIdx, Vec<usize>> =
             LinkedHashMap::new();
+        // Locations of each port assigned by the same connects more than once
+        let mut conflicts: LinkedHashMap<
+            (PortIdx, Vec<Option<GPosIdx>>),
+            Vec<usize>,
+        > = LinkedHashMap::new();
+        // Ports with at least one assigned location
+        let mut assigned = HashSet::new();
         for ((port, idx), connects) in self.ports.drain() {
             let con_len = connects.len();
+            if con_len > 0 {
+                assigned.insert(port);
+            }
             // If there is exactly one assignment to this location, then it is valid
             if con_len == 1 {
                 continue;
@@ -105,22 +121,47 @@ impl Visitor for AssignCheck {
                 unassigned.entry(port).or_default().push(idx);
                 continue;
             }
+            conflicts.entry((port, connects)).or_default().push(idx);
+        }
 
-            // generate the base error message
-            let err = Error::malformed(format!(
-                "port `{}{{{}}}' is assigned to {con_len} times",
-                data.comp.display(port),
-                idx,
-            ));
-
-            // Add all assignments with location information
-            let err = connects.into_iter().flatten().fold(err, |err, pos| {
-                err.add_note(diag.add_info("assigned here", pos))
-            });
+        // Report the locations driven by the same connects together
+        for ((port, connects), idxs) in conflicts {
+            let p = data.comp.get(port);
+            let is_bundle =
+                p.live.lens.iter().any(|l| l.concrete(&data.comp) != 1);
+            let name = data.comp.display(port);
+            let msg = if is_bundle {
+                format!(
+                    "bundle `{name}' is driven {} times at indices {}",
+                    connects.len(),
+                    idxs.iter().join(", ")
+                )
+            } else {
+                format!("port `{name}' is driven {} times", connects.len())
+            };
+
+            // Point to all connects with location information
+            let err = connects.into_iter().flatten().enumerate().fold(
+                Error::malformed(msg),
+                |err, (i, pos)| {
+                    let msg = if i == 0 {
+                        "first driven here"
+                    } else {
+                        "also driven here"
+                    };
+                    err.add_note(diag.add_info(msg, pos))
+                },
+            );
             diag.add_error(err)
         }
 
         for (port, mut idxs) in unassigned {
+            if self.allow_unconnected
+                && data.comp.get(port).is_sig_out()
+                && !assigned.contains(&port)
+            {
+                continue;
+            }
             idxs.sort();
             let err = Error::malformed(format!(
                 "bundle `{}' has {} unassigned locations",
@@ -147,7 +188,7 @@ impl Visitor for AssignCheck {
         }
     }
 
-    fn after_traversal(&mut self) -> Option<u64> {
+    fn after_traversal(&mut self) -> Option<Failure> {
         self.diag.report_all()
     }
 }
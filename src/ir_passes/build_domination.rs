@@ -152,6 +152,71 @@ impl BuildDomination {
     fn add_let(&mut self, let_: ir::Let) {
         self.plets.last_mut().unwrap().push(let_.into());
     }
+
+    /// Add the definitions in `cmds` to `lines` in the order they occur.
+    fn dump_scope(
+        comp: &ir::Component,
+        cmds: &[ir::Command],
+        depth: usize,
+        lines: &mut Vec<String>,
+    ) {
+        let indent = "  ".repeat(depth);
+        for cmd in cmds {
+            match cmd {
+                ir::Command::Instance(inst) => lines
+                    .push(format!("{indent}instance {}", comp.display(*inst))),
+                ir::Command::Invoke(inv) => {
+                    lines.push(format!("{indent}invoke {}", comp.display(*inv)))
+                }
+                ir::Command::Let(ir::Let { param, .. }) => {
+                    lines.push(format!("{indent}let {}", comp.display(*param)))
+                }
+                ir::Command::ForLoop(ir::Loop {
+                    index,
+                    start,
+                    end,
+                    body,
+                }) => {
+                    lines.push(format!(
+                        "{indent}for {} in {}..{}:",
+                        comp.display(*index),
+                        comp.display(*start),
+                        comp.display(*end)
+                    ));
+                    Self::dump_scope(comp, body, depth + 1, lines);
+                }
+                ir::Command::If(ir::If { cond, then, alt }) => {
+                    lines.push(format!("{indent}if {}:", comp.display(*cond)));
+                    Self::dump_scope(comp, then, depth + 1, lines);
+                    lines.push(format!("{indent}else:"));
+                    Self::dump_scope(comp, alt, depth + 1, lines);
+                }
+                ir::Command::BundleDef(_)
+                | ir::Command::Connect(_)
+                | ir::Command::Fact(_)
+                | ir::Command::Exists(_) => (),
+            }
+        }
+    }
+
+    /// The order of the definitions in each scope of the components in the
+    /// context. A definition dominates the definitions listed after it in the
+    /// same scope and the definitions in scopes nested below them.
+    pub fn dump(ctx: &ir::Context) -> String {
+        let mut lines = vec![];
+        for (idx, comp) in ctx.comps.iter() {
+            if comp.is_ext() || comp.is_gen() {
+                continue;
+            }
+            let name = comp
+                .source_name()
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| ctx.display(idx));
+            lines.push(format!("comp {name}:"));
+            Self::dump_scope(comp, &comp.cmds, 1, &mut lines);
+        }
+        lines.join("\n") + "\n"
+    }
 }
 
 impl Visitor for BuildDomination {
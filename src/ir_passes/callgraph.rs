@@ -0,0 +1,47 @@
+use fil_ir::{self as ir, Ctx, DisplayCtx};
+use itertools::Itertools;
+
+/// Generates the call graph of a program in the DOT format.
+/// Each invocation is an edge from the invoking component to the invoked
+/// component labeled with the name of the invocation and the times it binds
+/// the events of the invoked component to.
+pub struct CallGraph;
+
+impl CallGraph {
+    /// The name of the component used for its node in the graph
+    fn name(ctx: &ir::Context, idx: ir::CompIdx) -> String {
+        ctx.get(idx)
+            .source_name()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| ctx.display(idx))
+    }
+
+    /// Generate the call graph for the components in the context
+    pub fn dot(ctx: &ir::Context) -> String {
+        let mut lines = vec!["digraph callgraph {".to_string()];
+        for (idx, comp) in ctx.comps.iter() {
+            // External components do not invoke anything and only appear as
+            // targets of invocations
+            if comp.is_ext() || comp.is_gen() {
+                continue;
+            }
+            let name = Self::name(ctx, idx);
+            lines.push(format!("  \"{name}\";"));
+            for (inv, invoke) in comp.invocations().iter() {
+                let callee = comp.get(invoke.inst).comp;
+                let times = invoke
+                    .events
+                    .iter()
+                    .map(|eb| comp.display(eb.arg))
+                    .join(", ");
+                lines.push(format!(
+                    "  \"{name}\" -> \"{}\" [label=\"{}<{times}>\"];",
+                    Self::name(ctx, callee),
+                    comp.display(inv)
+                ));
+            }
+        }
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    cmdline,
+    ir_visitor::{Action, Construct, Visitor, VisitorData},
+};
+use fil_ir::{self as ir, Ctx};
+use fil_utils::{Diagnostics, Error, Failure, GPosIdx};
+use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Combinational paths between the ports of a component. Each edge is
+/// labeled with the location of the connection that induces it.
+type Graph = HashMap<ir::PortIdx, Vec<(ir::PortIdx, GPosIdx)>>;
+
+/// Combinational paths from the inputs to the outputs of a component
+type Paths = HashSet<(ir::PortIdx, ir::PortIdx)>;
+
+/// Detects combinational loops in components.
+/// Values flow combinationally through connections and through invocations
+/// whose outputs depend on their inputs in the same cycle. The outputs of an
+/// external component only depend on its inputs when they start in the same
+/// cycle as the inputs, so loops through registers, which shift time by at
+/// least one cycle, are allowed and a loop is only rejected if its total time
+/// shift is zero. Externs marked `@registered` read their outputs from
+/// registers and never have combinational paths.
+/// Must occur after bundle elimination.
+pub struct CombLoopCheck {
+    /// Combinational paths of the components checked so far
+    paths: HashMap<ir::CompIdx, Paths>,
+    diag: Diagnostics,
+}
+
+impl Construct for CombLoopCheck {
+    fn from(_: &cmdline::Opts, _: &mut ir::Context) -> Self {
+        Self {
+            paths: HashMap::new(),
+            diag: Diagnostics::default(),
+        }
+    }
+
+    fn clear_data(&mut self) {
+        /* Paths and diagnostics are shared across components */
+    }
+}
+
+impl CombLoopCheck {
+    /// Check if the output `out` of an invocation starts in the same cycle
+    /// as its input `inp`. Times are interned so equal times share an index.
+    fn same_cycle(
+        comp: &ir::Component,
+        inp: ir::PortIdx,
+        out: ir::PortIdx,
+    ) -> bool {
+        comp.get(inp).live.range.start == comp.get(out).live.range.start
+    }
+
+    /// Combinational paths from the inputs to the outputs of the component
+    /// `idx`.
+    fn paths(&mut self, ctx: &ir::Context, idx: ir::CompIdx) -> &Paths {
+        if !self.paths.contains_key(&idx) {
+            let comp = ctx.get(idx);
+            let graph = self.graph(ctx, comp);
+            let paths = Self::summarize(comp, &graph);
+            self.paths.insert(idx, paths);
+        }
+        &self.paths[&idx]
+    }
+
+    /// Compute the outputs reachable from each input of the component
+    fn summarize(comp: &ir::Component, graph: &Graph) -> Paths {
+        let mut paths = Paths::new();
+        for (inp, _) in comp.inputs() {
+            let mut seen = HashSet::new();
+            let mut stack = vec![inp];
+            while let Some(port) = stack.pop() {
+                if !seen.insert(port) {
+                    continue;
+                }
+                if comp.get(port).is_sig_out() {
+                    paths.insert((inp, port));
+                }
+                stack.extend(
+                    graph.get(&port).into_iter().flatten().map(|e| e.0),
+                );
+            }
+        }
+        paths
+    }
+
+    /// Build the combinational graph of the component
+    fn graph(&mut self, ctx: &ir::Context, comp: &ir::Component) -> Graph {
+        let mut graph = Graph::new();
+        for cmd in &comp.cmds {
+            if let ir::Command::Connect(con) = cmd {
+                let loc = comp
+                    .get(con.info)
+                    .as_connect()
+                    .map_or(GPosIdx::UNKNOWN, |c| c.src_loc);
+                graph
+                    .entry(con.src.port)
+                    .or_default()
+                    .push((con.dst.port, loc));
+            }
+        }
+
+        // The inputs and outputs of each invocation along with the ports of
+        // the invoked component they correspond to
+        let mut invs: BTreeMap<ir::InvIdx, (Vec<_>, Vec<_>)> = BTreeMap::new();
+        for (idx, port) in comp.ports().iter() {
+            if let ir::PortOwner::Inv { inv, dir, base } = &port.owner {
+                let (ins, outs) = invs.entry(*inv).or_default();
+                let ports = match dir {
+                    ir::Direction::In => ins,
+                    ir::Direction::Out => outs,
+                };
+                ports.push((idx, base.owner(), base.key()));
+            }
+        }
+
+        for (ins, outs) in invs.into_values() {
+            for (&(inp, callee_idx, inp_key), &(out, _, out_key)) in
+                ins.iter().cartesian_product(outs.iter())
+            {
+                let callee = ctx.get(callee_idx);
+                let comb = if callee.is_ext() {
+                    !callee.attrs.has("registered")
+                        && Self::same_cycle(comp, inp, out)
+                } else {
+                    // Outputs of pipelined components are registered
+                    callee.attrs.value("pipeline").unwrap_or(0) == 0
+                        && self
+                            .paths(ctx, callee_idx)
+                            .contains(&(inp_key, out_key))
+                };
+                if comb {
+                    graph.entry(inp).or_default().push((out, GPosIdx::UNKNOWN));
+                }
+            }
+        }
+        graph
+    }
+
+    /// Find a cycle in the graph. Returns the ports on the cycle, starting
+    /// and ending with the same port, and the locations of the connections
+    /// that form it.
+    fn find_cycle(graph: &Graph) -> Option<(Vec<ir::PortIdx>, Vec<GPosIdx>)> {
+        // Ports whose successors have all been explored
+        let mut done = HashSet::new();
+        for start in graph.keys().copied().sorted() {
+            if done.contains(&start) {
+                continue;
+            }
+            // The current path with the location of the edge into each port
+            // and the index of the next successor to explore
+            let mut path = vec![(start, GPosIdx::UNKNOWN, 0)];
+            while let Some(&(port, _, next)) = path.last() {
+                let succs = graph.get(&port).map_or(&[][..], Vec::as_slice);
+                let Some(&(succ, loc)) = succs.get(next) else {
+                    done.insert(port);
+                    path.pop();
+                    continue;
+                };
+                path.last_mut().unwrap().2 += 1;
+                if let Some(pos) = path.iter().position(|e| e.0 == succ) {
+                    let ports = path[pos..]
+                        .iter()
+                        .map(|e| e.0)
+                        .chain(Some(succ))
+                        .collect();
+                    let locs = path[pos + 1..]
+                        .iter()
+                        .map(|e| e.1)
+                        .chain(Some(loc))
+                        .collect();
+                    return Some((ports, locs));
+                }
+                if !done.contains(&succ) {
+                    path.push((succ, loc, 0));
+                }
+            }
+        }
+        None
+    }
+
+    /// Name of a port for error messages
+    fn port_name(comp: &ir::Component, port: ir::PortIdx) -> String {
+        let p = comp.get(port);
+        let name = comp
+            .get(p.info)
+            .as_port()
+            .map(|p| p.name.to_string())
+            .unwrap_or_else(|| "_".to_string());
+        let ir::PortOwner::Inv { inv, .. } = &p.owner else {
+            return name;
+        };
+        let inv = comp
+            .get(comp.get(*inv).info)
+            .as_invoke()
+            .map(|i| i.name.to_string())
+            .unwrap_or_else(|| "_".to_string());
+        format!("{inv}.{name}")
+    }
+}
+
+impl Visitor for CombLoopCheck {
+    fn name() -> &'static str {
+        "comb-loop-check"
+    }
+
+    fn start(&mut self, data: &mut VisitorData) -> Action {
+        if data.comp.is_ext() {
+            return Action::Stop;
+        }
+        let comp = &data.comp;
+        let graph = self.graph(data.mut_ctx, comp);
+        self.paths.insert(data.idx, Self::summarize(comp, &graph));
+
+        let Some((ports, locs)) = Self::find_cycle(&graph) else {
+            return Action::Stop;
+        };
+        let ports = ports
+            .into_iter()
+            .map(|p| format!("`{}'", Self::port_name(comp, p)))
+            .join(" -> ");
+        let mut err =
+            Error::malformed(format!("combinational loop through {ports}"));
+        for loc in locs.into_iter().filter_map(GPosIdx::into_option) {
+            err = err.add_note(self.diag.add_info("part of the loop", loc));
+        }
+        let hint = self.diag.add_message(
+            "values in a loop must pass through a register that delays them by at least one cycle",
+        );
+        self.diag.add_error(err.add_note(hint));
+        Action::Stop
+    }
+
+    fn after_traversal(&mut self) -> Option<Failure> {
+        self.diag.report_all()
+    }
+}
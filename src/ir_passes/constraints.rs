@@ -0,0 +1,97 @@
+use fil_ir::{self as ir, Ctx, DisplayCtx};
+use fil_utils::{Error, FilamentResult, GPosIdx, GlobalPositionTable};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// Location of a constraint in the source
+pub struct Span {
+    pub file: String,
+    /// 1-based line of the start of the constraint
+    pub line: usize,
+    /// 1-based column of the start of the constraint
+    pub column: usize,
+}
+
+impl Span {
+    fn from_pos(pos: GPosIdx) -> Option<Self> {
+        let pos = pos.into_option()?;
+        let table = GlobalPositionTable::as_ref();
+        let data = table.get_pos(pos.0);
+        let (file, src) = table.get_file_data(data.file);
+        let prefix = src.get(..data.start)?;
+        let line_start = prefix.rfind('\n').map_or(0, |i| i + 1);
+        Some(Self {
+            file: file.to_string(),
+            line: prefix.matches('\n').count() + 1,
+            column: prefix[line_start..].chars().count() + 1,
+        })
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// A constraint defined by the signature of a component
+pub struct Constraint {
+    /// Whether the constraint is over the `param`s or `event`s of the
+    /// component
+    pub kind: &'static str,
+    /// The constraint after names have been resolved
+    pub constraint: String,
+    /// Where the constraint is defined, if known
+    pub span: Option<Span>,
+}
+
+/// Queries the constraints that the signature of a component places on its
+/// parameters and events.
+pub struct Constraints;
+
+impl Constraints {
+    /// The location of the assumption of `prop` in the component
+    fn pos(comp: &ir::Component, prop: ir::PropIdx) -> GPosIdx {
+        comp.cmds
+            .iter()
+            .find_map(|cmd| match cmd {
+                ir::Command::Fact(f) if f.is_assume() && f.prop == prop => {
+                    comp.get(f.reason).as_assert()
+                }
+                _ => None,
+            })
+            .and_then(|ir::info::Assert(reason)| match reason {
+                ir::info::Reason::Misc { def_loc, .. } => Some(*def_loc),
+                _ => None,
+            })
+            .unwrap_or(GPosIdx::UNKNOWN)
+    }
+
+    /// The constraints of the component named `name`. Constraints on
+    /// parameters come before the constraints on events.
+    pub fn of(
+        ctx: &ir::Context,
+        name: &str,
+    ) -> FilamentResult<Vec<Constraint>> {
+        let comp = ctx
+            .comps
+            .iter()
+            .map(|(_, c)| c)
+            .find(|c| c.source_name().map_or(false, |n| n == name))
+            .ok_or_else(|| {
+                Error::misc(format!("Unknown component `{name}`"))
+            })?;
+        let params = comp.get_param_asserts().iter().map(|p| ("param", *p));
+        let events = comp.get_event_asserts().iter().map(|p| ("event", *p));
+        Ok(params
+            .chain(events)
+            .map(|(kind, prop)| Constraint {
+                kind,
+                constraint: comp.display(prop),
+                span: Span::from_pos(Self::pos(comp, prop)),
+            })
+            .collect())
+    }
+
+    /// Print the constraints of the component named `name` in JSON format
+    pub fn print(ctx: &ir::Context, name: &str) -> FilamentResult<()> {
+        let cons = Self::of(ctx, name)?;
+        println!("{}", serde_json::to_string_pretty(&cons).unwrap());
+        Ok(())
+    }
+}
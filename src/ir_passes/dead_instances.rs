@@ -0,0 +1,102 @@
+use fil_ir::{self as ir, Ctx, MutCtx};
+use std::collections::HashSet;
+
+/// Removes instances whose output ports are never read after
+/// monomorphization. Removing an instance also removes the connections to
+/// the inputs of its invocations which can make other instances unused.
+/// Instances of components without output ports can only be used for their
+/// side effects and instances of components marked `@opaque` are never
+/// removed.
+pub struct DeadInstances;
+
+impl DeadInstances {
+    /// Whether instances of the component `comp` can have side effects
+    fn has_effects(comp: &ir::Component) -> bool {
+        comp.attrs.has("opaque") || comp.outputs().next().is_none()
+    }
+
+    /// The instances of `comp` whose output ports are never read
+    fn dead(
+        comp: &ir::Component,
+        effects: &HashSet<ir::CompIdx>,
+    ) -> HashSet<ir::InstIdx> {
+        // After monomorphization, all commands are at the top-level
+        let read: HashSet<_> = comp
+            .cmds
+            .iter()
+            .filter_map(|cmd| match cmd {
+                ir::Command::Connect(con) => Some(con.src.port),
+                _ => None,
+            })
+            .collect();
+
+        let mut dead: HashSet<_> = comp
+            .instances()
+            .iter()
+            .filter(|(_, inst)| !effects.contains(&inst.comp))
+            .map(|(idx, _)| idx)
+            .collect();
+        for (_, inv) in comp.invocations().iter() {
+            let used = inv
+                .ports
+                .iter()
+                .any(|p| comp.get(*p).is_inv_out() && read.contains(p));
+            if used {
+                dead.remove(&inv.inst);
+            }
+        }
+        dead
+    }
+
+    /// Remove the dead instances of `comp` until no more can be removed
+    fn remove(comp: &mut ir::Component, effects: &HashSet<ir::CompIdx>) {
+        loop {
+            let dead = Self::dead(comp, effects);
+            if dead.is_empty() {
+                return;
+            }
+            let invs: HashSet<_> = comp
+                .invocations()
+                .iter()
+                .filter(|(_, inv)| dead.contains(&inv.inst))
+                .map(|(idx, _)| idx)
+                .collect();
+            let ports: HashSet<_> = invs
+                .iter()
+                .flat_map(|inv| comp.get(*inv).ports.iter().copied())
+                .collect();
+            log::debug!("Removing {} dead instances", dead.len());
+
+            comp.cmds.retain(|cmd| match cmd {
+                ir::Command::Instance(inst) => !dead.contains(inst),
+                ir::Command::Invoke(inv) => !invs.contains(inv),
+                ir::Command::Connect(con) => !ports.contains(&con.dst.port),
+                _ => true,
+            });
+            for port in ports {
+                comp.delete(port);
+            }
+            for inv in invs {
+                comp.delete(inv);
+            }
+            for inst in dead {
+                comp.delete(inst);
+            }
+        }
+    }
+
+    pub fn transform(mut ctx: ir::Context) -> ir::Context {
+        let effects = ctx
+            .comps
+            .iter()
+            .filter(|(_, comp)| Self::has_effects(comp))
+            .map(|(idx, _)| idx)
+            .collect();
+        for (_, comp) in ctx.comps.iter_mut() {
+            if !comp.is_ext() && !comp.is_gen() {
+                Self::remove(comp, &effects);
+            }
+        }
+        ctx
+    }
+}
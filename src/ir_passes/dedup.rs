@@ -0,0 +1,106 @@
+use fil_ir::{self as ir, Ctx, MutCtx};
+use itertools::Itertools;
+use std::collections::{hash_map::Entry, HashMap};
+
+/// Merges components that are structurally identical after monomorphization
+/// and rewrites their instances to use a single copy.
+/// Components marked `@opaque` and components with an externally visible
+/// interface, like the toplevel component, are never merged.
+pub struct Dedup;
+
+impl Dedup {
+    /// The canonical form of a component or `None` if it cannot be merged.
+    /// Invocations refer to the signature of the instantiated component using
+    /// indices, so merged components must also agree on those.
+    fn key(comp: &ir::Component) -> Option<String> {
+        if comp.is_ext()
+            || comp.is_gen()
+            || comp.src_info.is_some()
+            || comp.attrs.has("opaque")
+        {
+            return None;
+        }
+        let attrs = comp
+            .attrs
+            .iter()
+            .map(|attr| format!("@{}({:?})", attr.name.inner(), attr.value))
+            .join(" ");
+        let ports = comp
+            .inputs()
+            .chain(comp.outputs())
+            .map(|(idx, port)| {
+                let name = comp.get(port.info).as_port().map(|p| p.name);
+                format!(
+                    "{idx}:{}",
+                    name.map_or(String::new(), |n| n.to_string())
+                )
+            })
+            .join(", ");
+        let events = comp
+            .event_args()
+            .iter()
+            .map(|idx| {
+                let ev = comp.get(*idx);
+                let interface = comp
+                    .get(ev.info)
+                    .as_event()
+                    .and_then(|e| e.interface_name)
+                    .filter(|_| ev.has_interface);
+                format!(
+                    "{idx}:{}",
+                    interface.map_or(String::new(), |n| n.to_string())
+                )
+            })
+            .join(", ");
+        let params = comp
+            .param_args()
+            .iter()
+            .copied()
+            .chain(comp.exist_params())
+            .join(", ");
+        Some(format!(
+            "{attrs}\n{:?}\n{ports}\n{events}\n{params}\n{}",
+            comp.unannotated_ports,
+            ir::Printer::comp_str(comp)
+        ))
+    }
+
+    /// Merge identical components until no more components can be merged.
+    /// Merging components can make the components instantiating them
+    /// identical, so this runs until a fixed point is reached.
+    pub fn transform(mut ctx: ir::Context) -> ir::Context {
+        loop {
+            let mut canonical = HashMap::new();
+            let mut merged = HashMap::new();
+            for (idx, comp) in ctx.comps.iter() {
+                let Some(key) = Self::key(comp) else {
+                    continue;
+                };
+                match canonical.entry(key) {
+                    Entry::Occupied(e) => {
+                        merged.insert(idx, *e.get());
+                    }
+                    Entry::Vacant(e) => {
+                        e.insert(idx);
+                    }
+                }
+            }
+            if merged.is_empty() {
+                return ctx;
+            }
+
+            log::debug!("Merging {} components", merged.len());
+            for idx in merged.keys() {
+                ctx.comps.delete(*idx);
+            }
+            for (_, comp) in ctx.comps.iter_mut() {
+                for inst in comp.instances().idx_iter() {
+                    let inst = comp.get_mut(inst);
+                    if let Some(idx) = merged.get(&inst.comp) {
+                        inst.comp = *idx;
+                    }
+                }
+            }
+        }
+    }
+}
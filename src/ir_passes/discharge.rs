@@ -1,19 +1,21 @@
 use crate::cmdline;
 use crate::ir_visitor::{Action, Construct, Visitor, VisitorData};
 use crate::log_time;
-use crate::utils::HoistFacts;
+use crate::utils::{HoistFacts, NumericBounds};
 use codespan_reporting::diagnostic::Diagnostic;
 use codespan_reporting::{diagnostic as cr, term};
 use easy_smt as smt;
 use fil_ast as ast;
-use fil_ir::{self as ir, Ctx, DisplayCtx};
-use fil_utils::GlobalPositionTable;
+use fil_ir::{self as ir, AddCtx, Ctx, DisplayCtx, MutCtx};
+use fil_utils::{emit_diagnostic, Error, Failure, FilamentResult, GPosIdx};
 use itertools::Itertools;
-use std::collections::HashMap;
-use std::{fs, iter};
+use rayon::prelude::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::{env, fs, iter};
 use term::termcolor::{ColorChoice, StandardStream};
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 struct Assign(Vec<(ir::ParamIdx, String)>);
 
 impl Assign {
@@ -35,16 +37,203 @@ impl Assign {
     }
 }
 
+#[derive(Default)]
+/// Counts of the facts handled by [Discharge]
+struct Counts {
+    /// Constraints declared in the signature
+    declared: usize,
+    /// Assertions in the component
+    asserts: usize,
+    /// Assertions checked using the solver
+    solver: usize,
+    /// Assertions decided using numeric bounds
+    bounds: usize,
+    /// Assertions that are trivially true under their path condition
+    folded: usize,
+    /// Assumptions available to prove the assertions
+    assumed: usize,
+    /// Assertions that could not be proved
+    failed: usize,
+}
+
+impl Counts {
+    /// Add the counts of another component
+    fn merge(&mut self, other: &Counts) {
+        self.declared += other.declared;
+        self.asserts += other.asserts;
+        self.solver += other.solver;
+        self.bounds += other.bounds;
+        self.folded += other.folded;
+        self.assumed += other.assumed;
+        self.failed += other.failed;
+    }
+
+    /// Count the assumptions in the commands and return the number of
+    /// assertions
+    fn facts(&mut self, cmds: &[ir::Command]) -> usize {
+        let mut asserts = 0;
+        for cmd in cmds {
+            match cmd {
+                ir::Command::Fact(f) if f.is_assume() => self.assumed += 1,
+                ir::Command::Fact(_) => asserts += 1,
+                ir::Command::ForLoop(l) => asserts += self.facts(&l.body),
+                ir::Command::If(i) => {
+                    asserts += self.facts(&i.then) + self.facts(&i.alt)
+                }
+                _ => (),
+            }
+        }
+        asserts
+    }
+}
+
+#[derive(Default)]
+/// Counts of the facts handled by [Discharge] for each component that it
+/// visited, reported with `--discharge-summary`, `--stats`, and
+/// `--summarize-constraints`
+struct Summary {
+    comps: Vec<(String, Counts)>,
+}
+
+impl Summary {
+    /// The counts of all components
+    fn total(&self) -> Counts {
+        let mut total = Counts::default();
+        for (_, counts) in &self.comps {
+            total.merge(counts);
+        }
+        total
+    }
+
+    /// Print the counts of each component as a table
+    fn table(&self) {
+        let header =
+            ["component", "declared", "discharged", "assumed", "skipped"];
+        let width = self
+            .comps
+            .iter()
+            .map(|(name, _)| name.len())
+            .chain(Some(header[0].len()))
+            .max()
+            .unwrap();
+        eprintln!(
+            "{:<width$}  {:>8}  {:>10}  {:>7}  {:>7}",
+            header[0], header[1], header[2], header[3], header[4]
+        );
+        for (name, c) in self.comps.iter().sorted_by(|(a, _), (b, _)| a.cmp(b))
+        {
+            eprintln!(
+                "{:<width$}  {:>8}  {:>10}  {:>7}  {:>7}",
+                name,
+                c.declared,
+                c.solver + c.bounds,
+                c.assumed,
+                c.folded
+            );
+        }
+    }
+}
+
+thread_local! {
+    /// Solvers that earlier runs of [Discharge] on this thread are done with,
+    /// along with the commands that started them
+    static SOLVERS: RefCell<Vec<(Vec<String>, smt::Context)>> =
+        RefCell::new(Vec::new());
+}
+
+/// A solver that is kept for later runs of [Discharge] once the pass is done
+/// with it, so that recompiling the program in `--watch` mode does not start
+/// a new solver every time. The scopes opened by the pass are removed before
+/// the solver is used again.
+struct Solver {
+    ctx: Option<smt::Context>,
+    /// The command that started the solver. `None` if it cannot be reused.
+    cmd: Option<Vec<String>>,
+    /// Number of scopes opened with [Solver::push_scope]
+    scopes: usize,
+}
+
+impl Solver {
+    /// Reuse a solver started by `cmd` or start a new one. Solvers that write
+    /// their queries to a replay file are never reused so that the file only
+    /// contains the queries of one run.
+    fn start(cmd: Vec<String>, replay: Option<fs::File>) -> Self {
+        let reuse = replay.is_none();
+        let ctx = reuse
+            .then(|| {
+                SOLVERS.with(|solvers| {
+                    let mut solvers = solvers.borrow_mut();
+                    let idx = solvers.iter().position(|(c, _)| *c == cmd)?;
+                    Some(solvers.swap_remove(idx).1)
+                })
+            })
+            .flatten();
+        let ctx = ctx.unwrap_or_else(|| {
+            smt::ContextBuilder::new()
+                .replay_file(replay)
+                .solver(&cmd[0], cmd[1..].to_vec())
+                .build()
+                .unwrap()
+        });
+        Self {
+            ctx: Some(ctx),
+            cmd: reuse.then_some(cmd),
+            scopes: 0,
+        }
+    }
+
+    /// Open a scope for definitions and assertions
+    fn push_scope(&mut self) {
+        self.push_many(1).unwrap();
+        self.scopes += 1;
+    }
+
+    /// Remove the definitions and assertions of the innermost scope
+    fn pop_scope(&mut self) {
+        self.pop_many(1).unwrap();
+        self.scopes -= 1;
+    }
+}
+
+impl std::ops::Deref for Solver {
+    type Target = smt::Context;
+
+    fn deref(&self) -> &smt::Context {
+        self.ctx.as_ref().unwrap()
+    }
+}
+
+impl std::ops::DerefMut for Solver {
+    fn deref_mut(&mut self) -> &mut smt::Context {
+        self.ctx.as_mut().unwrap()
+    }
+}
+
+impl Drop for Solver {
+    fn drop(&mut self) {
+        let (Some(mut ctx), Some(cmd)) = (self.ctx.take(), self.cmd.take())
+        else {
+            return;
+        };
+        // Solvers that fail are not reused
+        if ctx.pop_many(self.scopes).is_ok() {
+            SOLVERS.with(|solvers| solvers.borrow_mut().push((cmd, ctx)));
+        }
+    }
+}
+
 /// Pass to discharge top-level `assert` statements in the IR and turn them into
 /// `assume` if they are true. Any assertions within the body are left as-is.
 /// Run [super::HoistFacts] before this pass to ensure that all facts are
 /// top-level.
 pub struct Discharge {
-    sol: smt::Context,
+    sol: Solver,
     /// Are we using a bitvector encoding
     bv_size: Option<u8>,
     /// Which solver are we using
     sol_base: cmdline::Solver,
+    /// Decide facts using numeric bounds before using the solver
+    abstract_bounds: bool,
     /// Defined global functions
     func_map: HashMap<ast::Fn, smt::SExpr>,
     /// Defined functions for `some` parameters on components
@@ -60,6 +249,18 @@ pub struct Discharge {
     prop_map: ir::DenseIndexInfo<ir::Prop, smt::SExpr>,
     // Propositions that have already been checked
     checked: HashMap<ir::PropIdx, Option<Assign>>,
+    /// Timeout in milliseconds for each query to the solver
+    timeout: Option<u64>,
+    /// Propositions the solver could not decide before the timeout
+    timed_out: HashSet<ir::PropIdx>,
+    /// Results of the queries answered by the solver, keyed by the query with
+    /// all definitions expanded, and whether the query timed out. Queries are
+    /// only compared within a run so indices can be used as names.
+    /// `None` when caching is disabled.
+    cache: Option<HashMap<String, (Option<Assign>, bool)>>,
+    /// Facts about the parameters of the current component that every query
+    /// depends on, expanded like the keys of the cache
+    context: String,
 
     // counter for activation literals generated
     act_lit_count: u32,
@@ -71,40 +272,82 @@ pub struct Discharge {
 
     // Diagnostics to be reported
     diagnostics: Vec<cr::Diagnostic<usize>>,
-    /// Number of errors encountered
-    error_count: u64,
+    /// Constraints that could not be proved in the components visited so far.
+    /// They are reported once all components have been discharged.
+    failures: Vec<(ir::CompIdx, cr::Diagnostic<usize>)>,
+    /// Counts of the facts of the component being visited
+    counts: Counts,
+    /// Counts of the facts of the components visited so far
+    summary: Option<Summary>,
 }
 
 impl Discharge {
-    /// Configure solver to use in this pass
-    fn conf_solver(opts: &cmdline::Opts) -> smt::Context {
-        let (name, s_opts) = match opts.solver {
-            cmdline::Solver::Z3 => {
-                log::debug!("Using z3 solver");
-                ("z3", vec!["-smt2", "-in"])
-            }
-            cmdline::Solver::Boolector => {
-                log::debug!("Using boolector solver");
-                ("boolector", vec!["--incremental"])
-            }
+    /// The binary and the command-line arguments used to run the solver
+    fn solver_cmd(
+        solver: cmdline::Solver,
+    ) -> (&'static str, Vec<&'static str>) {
+        match solver {
+            cmdline::Solver::Z3 => ("z3", vec!["-smt2", "-in"]),
+            cmdline::Solver::Boolector => ("boolector", vec!["--incremental"]),
             cmdline::Solver::CVC5 => {
-                log::debug!("Using cvc5 solver");
                 ("cvc5", vec!["--incremental", "--force-logic=ALL"])
             }
-            cmdline::Solver::Bitwuzla => {
-                log::debug!("Using bitwuzla solver");
-                ("bitwuzla", vec![])
+            cmdline::Solver::Bitwuzla => ("bitwuzla", vec![]),
+        }
+    }
+
+    /// Arguments that limit each query to the solver to `ms` milliseconds.
+    /// Returns `None` if the solver does not support per-query timeouts.
+    fn timeout_args(solver: cmdline::Solver, ms: u64) -> Option<String> {
+        match solver {
+            cmdline::Solver::Z3 => Some(format!("-t:{ms}")),
+            cmdline::Solver::CVC5 => Some(format!("--tlimit-per={ms}")),
+            cmdline::Solver::Bitwuzla => Some(format!("--time-limit-per={ms}")),
+            // Boolector only limits the time of the entire run
+            cmdline::Solver::Boolector => None,
+        }
+    }
+
+    /// Ensure that the solver supports the options and that its binary can be
+    /// found on `PATH`. Constructing the pass fails without a useful message
+    /// otherwise.
+    pub fn find_solver(opts: &cmdline::Opts) -> FilamentResult<()> {
+        let (name, _) = Self::solver_cmd(opts.solver);
+        if let Some(ms) = opts.smt_timeout {
+            if Self::timeout_args(opts.solver, ms).is_none() {
+                return Err(Error::misc(format!(
+                    "--smt-timeout is not supported by `{name}'. Select another solver using --solver"
+                )));
             }
-        };
-        smt::ContextBuilder::new()
-            .replay_file(
-                opts.solver_replay_file
-                    .as_ref()
-                    .map(|s| fs::File::create(s).unwrap()),
-            )
-            .solver(name, s_opts)
-            .build()
-            .unwrap()
+        }
+        let paths = env::var_os("PATH").unwrap_or_default();
+        if env::split_paths(&paths).any(|dir| dir.join(name).is_file()) {
+            Ok(())
+        } else {
+            Err(Error::misc(format!(
+                "solver `{name}' was not found on PATH. Install it or select another solver using --solver"
+            )))
+        }
+    }
+
+    /// Configure solver to use in this pass
+    fn conf_solver(opts: &cmdline::Opts) -> Solver {
+        let (name, s_opts) = Self::solver_cmd(opts.solver);
+        log::debug!("Using {name} solver");
+        let timeout = opts
+            .smt_timeout
+            .and_then(|ms| Self::timeout_args(opts.solver, ms));
+        let cmd = iter::once(name)
+            .chain(s_opts)
+            .map(String::from)
+            .chain(timeout)
+            .collect_vec();
+        Solver::start(
+            cmd,
+            opts.solver_replay_file
+                .as_ref()
+                .map(|s| fs::File::create(s).unwrap()),
+        )
     }
 
     fn app(&mut self, f: smt::SExpr, args: Vec<smt::SExpr>) -> smt::SExpr {
@@ -206,11 +449,49 @@ impl Discharge {
 
 impl Construct for Discharge {
     fn from(opts: &cmdline::Opts, ctx: &mut ir::Context) -> Self {
+        Self::new(opts, ctx)
+    }
+
+    fn clear_data(&mut self) {
+        self.param_map.clear();
+        self.prop_map.clear();
+        self.time_map.clear();
+        self.ev_map.clear();
+        self.expr_map.clear();
+        self.checked.clear();
+        self.timed_out.clear();
+        self.context.clear();
+        self.diagnostics.clear();
+        self.act_lit_count = 0;
+        self.to_prove.clear();
+        self.counts = Counts::default();
+
+        // Create a new solver context
+        self.sol.pop_scope();
+        self.sol.push_scope();
+    }
+}
+
+impl Discharge {
+    /// Start a solver and define the functions shared by all components.
+    /// Only reads the context so that workers can share it.
+    fn new(opts: &cmdline::Opts, ctx: &ir::Context) -> Self {
         let mut out = Self {
             bv_size: opts.solver_bv,
             sol: Self::conf_solver(opts),
             sol_base: opts.solver,
-            error_count: 0,
+            // Bounds do not model the overflow of bitvectors
+            abstract_bounds: matches!(
+                opts.interval_model,
+                cmdline::IntervalModel::IntervalAbstract
+            ) && opts.solver_bv.is_none(),
+            failures: vec![],
+            counts: Counts::default(),
+            summary: Self::summarize(opts).then(Summary::default),
+            timeout: opts.smt_timeout,
+            timed_out: HashSet::new(),
+            cache: (!opts.no_smt_cache).then(HashMap::new),
+            context: String::new(),
             act_lit_count: 0,
             to_prove: vec![],
             show_models: opts.show_models,
@@ -225,6 +506,9 @@ impl Construct for Discharge {
             comp_param_map: Default::default(),
         };
 
+        // Definitions are made in a scope that is removed before the solver
+        // is reused
+        out.sol.push_scope();
         out.define_funcs();
 
         // For each `some` parameter of a component, define function from the
@@ -257,25 +541,9 @@ impl Construct for Discharge {
             }
         }
 
-        out.sol.push_many(1).unwrap();
+        out.sol.push_scope();
         out
     }
-
-    fn clear_data(&mut self) {
-        self.param_map.clear();
-        self.prop_map.clear();
-        self.time_map.clear();
-        self.ev_map.clear();
-        self.expr_map.clear();
-        self.checked.clear();
-        self.diagnostics.clear();
-        self.act_lit_count = 0;
-        self.to_prove.clear();
-
-        // Create a new solver context
-        self.sol.pop_many(1).unwrap();
-        self.sol.push_many(1).unwrap();
-    }
 }
 
 impl Discharge {
@@ -312,6 +580,85 @@ impl Discharge {
         format!("t{}", time.get())
     }
 
+    /// Expand an expression for the keys of the cache
+    fn key_expr(expr: ir::ExprIdx, ctx: &ir::Component) -> String {
+        match ctx.get(expr) {
+            ir::Expr::Param(p) => format!("p{}", p.get()),
+            ir::Expr::Concrete(n) => n.to_string(),
+            ir::Expr::Bin { op, lhs, rhs } => format!(
+                "({op} {} {})",
+                Self::key_expr(*lhs, ctx),
+                Self::key_expr(*rhs, ctx)
+            ),
+            ir::Expr::Fn { op, args } => format!(
+                "({op:?} {})",
+                args.iter().map(|e| Self::key_expr(*e, ctx)).join(" ")
+            ),
+        }
+    }
+
+    fn key_time(time: ir::TimeIdx, ctx: &ir::Component) -> String {
+        let ir::Time { event, offset } = ctx.get(time);
+        format!("(+ ev{} {})", event.get(), Self::key_expr(*offset, ctx))
+    }
+
+    /// Expand a proposition for the keys of the cache
+    fn key_prop(prop: ir::PropIdx, ctx: &ir::Component) -> String {
+        let cmp =
+            |op: &ir::Cmp, l: String, r: String| format!("({op} {l} {r})");
+        match ctx.get(prop) {
+            ir::Prop::True => "true".to_string(),
+            ir::Prop::False => "false".to_string(),
+            ir::Prop::Cmp(c) => cmp(
+                &c.op,
+                Self::key_expr(c.lhs, ctx),
+                Self::key_expr(c.rhs, ctx),
+            ),
+            ir::Prop::TimeCmp(c) => cmp(
+                &c.op,
+                Self::key_time(c.lhs, ctx),
+                Self::key_time(c.rhs, ctx),
+            ),
+            ir::Prop::TimeSubCmp(c) => {
+                let ts = |ts: &ir::TimeSub| match ts {
+                    ir::TimeSub::Unit(e) => Self::key_expr(*e, ctx),
+                    ir::TimeSub::Sym { l, r } => format!(
+                        "(- {} {})",
+                        Self::key_time(*l, ctx),
+                        Self::key_time(*r, ctx)
+                    ),
+                };
+                cmp(&c.op, ts(&c.lhs), ts(&c.rhs))
+            }
+            ir::Prop::Not(p) => format!("(not {})", Self::key_prop(*p, ctx)),
+            ir::Prop::And(l, r) => format!(
+                "(and {} {})",
+                Self::key_prop(*l, ctx),
+                Self::key_prop(*r, ctx)
+            ),
+            ir::Prop::Or(l, r) => format!(
+                "(or {} {})",
+                Self::key_prop(*l, ctx),
+                Self::key_prop(*r, ctx)
+            ),
+            ir::Prop::Implies(l, r) => format!(
+                "(=> {} {})",
+                Self::key_prop(*l, ctx),
+                Self::key_prop(*r, ctx)
+            ),
+        }
+    }
+
+    /// The key of the query that checks the validity of all `props`
+    fn query_key<'a>(
+        &self,
+        props: impl IntoIterator<Item = &'a ir::PropIdx>,
+        ctx: &ir::Component,
+    ) -> String {
+        let props = props.into_iter().map(|p| Self::key_prop(*p, ctx));
+        format!("{} (valid {})", self.context, props.join(" "))
+    }
+
     fn new_act_lit(&mut self) -> smt::SExpr {
         self.act_lit_count += 1;
         self.sol
@@ -389,6 +736,19 @@ impl Discharge {
     /// Adds an error to the diagnositcs reporter if the proposition cannot be proved.
     fn check_valid(&mut self, fact: ir::Fact, ctx: &ir::Component) {
         let prop = fact.prop;
+        let key = self.cache.is_some().then(|| self.query_key([&prop], ctx));
+        let cached = key
+            .as_ref()
+            .and_then(|k| self.cache.as_ref().unwrap().get(k))
+            .cloned();
+        if let (Some((out, timed_out)), false) =
+            (cached, self.checked.contains_key(&prop))
+        {
+            if timed_out {
+                self.timed_out.insert(prop);
+            }
+            self.checked.insert(prop, out);
+        }
         #[allow(clippy::map_entry)]
         if !self.checked.contains_key(&prop) {
             let actlit = self.new_act_lit();
@@ -413,13 +773,29 @@ impl Discharge {
                     }
                 }
                 smt::Response::Unsat => None,
+                // Queries only return unknown when they time out
+                smt::Response::Unknown if self.timeout.is_some() => {
+                    self.timed_out.insert(prop);
+                    Some(Assign::default())
+                }
                 smt::Response::Unknown => panic!("Solver returned unknown"),
             };
             // Deassert the actlit after the `get-model` call.
             self.sol.assert(self.sol.not(actlit)).unwrap();
+            if let (Some(cache), Some(key)) = (&mut self.cache, key) {
+                cache
+                    .insert(key, (out.clone(), self.timed_out.contains(&prop)));
+            }
             self.checked.insert(prop, out);
         }
-        if let Some(assign) = &self.checked[&prop] {
+        if self.checked[&prop].is_some() {
+            self.report_failure(fact, ctx);
+        }
+    }
+
+    /// Add a diagnostic for a fact that could not be proved
+    fn report_failure(&mut self, fact: ir::Fact, ctx: &ir::Component) {
+        if let Some(assign) = &self.checked[&fact.prop] {
             let Some(ir::info::Assert(reason)) =
                 ctx.get(fact.reason).as_assert()
             else {
@@ -441,17 +817,44 @@ impl Discharge {
                     "Cannot prove constraint: {}",
                     ctx.display(fact.prop.consequent(ctx))
                 )]);
-                if !assign.is_empty() {
-                    diag = diag.with_notes(vec![format!(
-                        "Counterexample: {} (unmentioned parameters are 0)",
-                        assign.display(ctx)
-                    )]);
-                }
+            }
+            if self.timed_out.contains(&fact.prop) {
+                diag = diag.with_notes(vec![format!(
+                    "The solver timed out after {}ms so the constraint may still hold. Increase --smt-timeout or simplify the constraint",
+                    self.timeout.unwrap_or_default()
+                )]);
+            } else if self.show_models && !assign.is_empty() {
+                diag = diag.with_notes(vec![format!(
+                    "Counterexample: {} (unmentioned parameters are 0)",
+                    assign.display(ctx)
+                )]);
             }
             self.diagnostics.push(diag);
         }
     }
 
+    /// Decide the facts to prove using numeric bounds. Facts that are
+    /// disproved are reported and only the undecided facts are left to the
+    /// solver.
+    fn decide_with_bounds(&mut self, comp: &ir::Component) {
+        let bounds = NumericBounds::new(comp);
+        let facts = std::mem::take(&mut self.to_prove);
+        let total = facts.len();
+        for fact in facts {
+            match bounds.decide(fact.prop) {
+                Some(true) => (),
+                Some(false) => {
+                    self.checked.insert(fact.prop, Some(Assign::default()));
+                    self.report_failure(fact, comp);
+                }
+                None => self.to_prove.push(fact),
+            }
+        }
+        let decided = total - self.to_prove.len();
+        self.counts.bounds += decided;
+        log::info!("Decided {decided} of {total} facts using numeric bounds");
+    }
+
     /// Find the failing facts from the given component and add diagnostics for them
     fn failing_props(&mut self, comp: &ir::Component) {
         let props = std::mem::take(&mut self.to_prove);
@@ -549,16 +952,113 @@ impl Visitor for Discharge {
     }
 
     fn start(&mut self, data: &mut VisitorData) -> Action {
-        self.to_prove = HoistFacts::hoist(&mut data.comp);
+        (self.to_prove, self.counts) = Self::hoist(&mut data.comp);
+        self.declare(&data.comp);
+        // Pass does not need to traverse the control program.
+        Action::Continue
+    }
+
+    fn instance(&mut self, idx: ir::InstIdx, data: &mut VisitorData) -> Action {
+        self.declare_instance(idx, &data.comp);
+        Action::Continue
+    }
+
+    fn end(&mut self, data: &mut VisitorData) {
+        self.prove(&data.comp, data.opts.discharge_separate);
+        self.record(data.idx, &data.comp);
+    }
+
+    fn do_pass(
+        opts: &cmdline::Opts,
+        ctx: &mut ir::Context,
+    ) -> Result<(), Failure> {
+        // Workers would overwrite each other's solver log
+        let jobs = if opts.solver_replay_file.is_some() {
+            1
+        } else {
+            opts.jobs.max(1)
+        };
+        let comps = ctx.comps.idx_iter().collect_vec();
+        if jobs == 1 {
+            let visitor = Self::discharge(opts, ctx, comps);
+            return Self::report(&visitor.failures, &visitor.summary, opts)
+                .map_or(Ok(()), Err);
+        }
+
+        // The facts are hoisted up front so that the workers can share the
+        // context. The facts of each component are split into contiguous
+        // chunks that are proved independently.
+        let mut counts = Vec::with_capacity(comps.len());
+        let mut chunks = vec![];
+        for idx in comps {
+            let (facts, c) = Self::hoist(ctx.get_mut(idx));
+            counts.push((idx, c));
+            let size = ((facts.len() + jobs - 1) / jobs).max(1);
+            chunks.extend(facts.chunks(size).map(|f| (idx, f.to_vec())));
+        }
+
+        // Workers reuse their solver for the chunks that they prove
+        let ctx = &*ctx;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build()
+            .unwrap();
+        let proved = pool.install(|| {
+            chunks
+                .into_par_iter()
+                .map_init(
+                    || Self::new(opts, ctx),
+                    |visitor, (idx, facts)| {
+                        visitor.clear_data();
+                        let comp = ctx.get(idx);
+                        visitor.to_prove = facts;
+                        visitor.declare(comp);
+                        for inst in comp.instances().idx_iter() {
+                            visitor.declare_instance(inst, comp);
+                        }
+                        visitor.prove(comp, opts.discharge_separate);
+                        visitor.counts.failed = visitor.diagnostics.len();
+                        let diags = visitor.diagnostics.drain(..).collect_vec();
+                        (idx, std::mem::take(&mut visitor.counts), diags)
+                    },
+                )
+                .collect::<Vec<_>>()
+        });
+
+        // The chunks are collected in order so the failures are reported in
+        // the same order as with a single worker
+        let mut failures = vec![];
+        for (idx, c, diags) in proved {
+            let (_, total) =
+                counts.iter_mut().find(|(i, _)| *i == idx).unwrap();
+            total.merge(&c);
+            failures.extend(diags.into_iter().map(|diag| (idx, diag)));
+        }
+        let summary = Self::summarize(opts).then(|| Summary {
+            comps: counts
+                .into_iter()
+                .filter(|(idx, _)| {
+                    let comp = ctx.get(*idx);
+                    !comp.is_ext() && !comp.is_gen()
+                })
+                .map(|(idx, c)| (Self::comp_name(idx, ctx.get(idx)), c))
+                .collect(),
+        });
+        Self::report(&failures, &summary, opts).map_or(Ok(()), Err)
+    }
+}
 
+impl Discharge {
+    /// Define the parameters, events, expressions, times, and propositions
+    /// of the component in the solver
+    fn declare(&mut self, comp: &ir::Component) {
         for fact in &self.to_prove {
-            log::debug!("Checking {}", data.comp.display(fact.prop));
+            log::debug!("Checking {}", comp.display(fact.prop));
         }
 
-        let comp = &data.comp;
         // Declare all parameters
         let int = self.sort();
-        for (idx, _) in data.comp.params().iter() {
+        for (idx, _) in comp.params().iter() {
             let sexp = self
                 .sol
                 .declare_fun(self.fmt_param(idx, comp), vec![], int)
@@ -568,7 +1068,7 @@ impl Visitor for Discharge {
         }
 
         // Declare all events
-        for (idx, _) in data.comp.events().iter() {
+        for (idx, _) in comp.events().iter() {
             let sexp = self
                 .sol
                 .declare_fun(self.fmt_event(idx, comp), vec![], int)
@@ -578,7 +1078,7 @@ impl Visitor for Discharge {
         }
 
         // Declare all expressions
-        for (idx, expr) in data.comp.exprs().iter() {
+        for (idx, expr) in comp.exprs().iter() {
             let assign = self.expr_to_sexp(expr);
             let sexp = self
                 .sol
@@ -589,7 +1089,7 @@ impl Visitor for Discharge {
         }
 
         // Assert bindings for all let-bound parameters
-        for (idx, p) in data.comp.params().iter() {
+        for (idx, p) in comp.params().iter() {
             let ir::ParamOwner::Let { bind } = &p.owner else {
                 continue;
             };
@@ -597,10 +1097,24 @@ impl Visitor for Discharge {
             let bind_s = self.expr_map[*bind];
             let assign = self.sol.eq(param_s, bind_s);
             self.sol.assert(assign).unwrap();
+            if self.cache.is_some() {
+                let bind = Self::key_expr(*bind, comp);
+                self.context += &format!("(= p{} {bind})", idx.get());
+            }
+        }
+        // Bitvectors constrain the parameters in every expression to not
+        // overflow
+        if self.cache.is_some() && self.bv_size.is_some() {
+            for (idx, _) in comp.exprs().iter() {
+                self.context += &format!("(bv {})", Self::key_expr(idx, comp));
+            }
+            for (idx, _) in comp.times().iter() {
+                self.context += &format!("(bv {})", Self::key_time(idx, comp));
+            }
         }
 
         // Declare all time expressions
-        for (idx, ir::Time { event, offset }) in data.comp.times().iter() {
+        for (idx, ir::Time { event, offset }) in comp.times().iter() {
             let assign = self.plus(self.ev_map[*event], self.expr_map[*offset]);
             let sexp = self
                 .sol
@@ -612,7 +1126,7 @@ impl Visitor for Discharge {
 
         // Declare all propositions
         let bs = self.sol.bool_sort();
-        for (idx, prop) in data.comp.props().iter() {
+        for (idx, prop) in comp.props().iter() {
             // Define assertion equating the proposition to its assignment
             let assign = self.prop_to_sexp(prop);
             let sexp = self
@@ -621,12 +1135,11 @@ impl Visitor for Discharge {
                 .unwrap();
             self.prop_map.push(idx, sexp);
         }
-        // Pass does not need to traverse the control program.
-        Action::Continue
     }
 
-    fn instance(&mut self, idx: ir::InstIdx, data: &mut VisitorData) -> Action {
-        let comp = &data.comp;
+    /// Define the existential parameters of the instance using the functions
+    /// that compute them from its arguments
+    fn declare_instance(&mut self, idx: ir::InstIdx, comp: &ir::Component) {
         let inst = &comp[idx];
         let sexp_args =
             inst.args.iter().map(|e| self.expr_map[*e]).collect_vec();
@@ -641,62 +1154,226 @@ impl Visitor for Discharge {
                 let app = self.app(*f, sexp_args.clone());
                 let assign = self.sol.eq(param_s, app);
                 self.sol.assert(assign).unwrap();
+                if self.cache.is_some() {
+                    self.context += &format!(
+                        "(= p{} (comp{}_param{} {}))",
+                        param.get(),
+                        base.owner().get(),
+                        base.key().get(),
+                        inst.args
+                            .iter()
+                            .map(|e| Self::key_expr(*e, comp))
+                            .join(" ")
+                    );
+                }
             }
         }
-        Action::Continue
     }
 
-    fn end(&mut self, data: &mut VisitorData) {
+    /// Prove the facts hoisted from the component and add diagnostics for the
+    /// ones that fail. Each fact is checked on its own when `separate` is set.
+    fn prove(&mut self, comp: &ir::Component, separate: bool) {
         if self.to_prove.is_empty() {
             return;
         }
 
-        if !data.opts.discharge_separate {
-            // Attempt to prove all facts
-            let total_prop = self
-                .sol
-                .and_many(self.to_prove.iter().map(|f| self.prop_map[f.prop]));
-            let total_prop = self.sol.not(total_prop);
-            self.sol.assert(total_prop).unwrap();
-
-            // If there is at least one failing prop, roll back to individually checking the props for error reporting
-            if matches!(self.sol.check().unwrap(), smt::Response::Sat) {
-                log::info!("Failed to prove all facts. Checking each fact individually");
-                self.failing_props(&data.comp);
+        if self.abstract_bounds {
+            self.decide_with_bounds(comp);
+        }
+
+        self.counts.solver += self.to_prove.len();
+        if self.to_prove.is_empty() {
+            // All facts were decided without the solver
+        } else if !separate {
+            let key = self.cache.is_some().then(|| {
+                self.query_key(self.to_prove.iter().map(|f| &f.prop), comp)
+            });
+            let cached = key
+                .as_ref()
+                .and_then(|k| self.cache.as_ref().unwrap().get(k))
+                .map_or(false, |(failed, _)| failed.is_none());
+            if cached {
+                // The same facts were already proved in another component
+                self.to_prove.clear();
+            } else {
+                // Attempt to prove all facts
+                let total_prop = self.sol.and_many(
+                    self.to_prove.iter().map(|f| self.prop_map[f.prop]),
+                );
+                let total_prop = self.sol.not(total_prop);
+                self.sol.assert(total_prop).unwrap();
+
+                // If there is at least one failing prop or the query timed
+                // out, roll back to individually checking the props for error
+                // reporting
+                if matches!(self.sol.check().unwrap(), smt::Response::Unsat) {
+                    if let (Some(cache), Some(key)) = (&mut self.cache, key) {
+                        cache.insert(key, (None, false));
+                    }
+                } else {
+                    log::info!("Failed to prove all facts. Checking each fact individually");
+                    self.failing_props(comp);
+                }
             }
         } else {
             // Check each proposition individually
             let to_prove = std::mem::take(&mut self.to_prove);
             for fact in to_prove {
-                self.check_valid(fact, &data.comp);
+                self.check_valid(fact, comp);
+            }
+        }
+    }
+
+    /// Record the failures and the counts of the component once its facts
+    /// have been proved
+    fn record(&mut self, idx: ir::CompIdx, comp: &ir::Component) {
+        self.counts.failed = self.diagnostics.len();
+        let counts = std::mem::take(&mut self.counts);
+        if let Some(s) = &mut self.summary {
+            // External and generated components have no facts
+            if !comp.is_ext() && !comp.is_gen() {
+                s.comps.push((Self::comp_name(idx, comp), counts));
             }
         }
+        self.failures
+            .extend(self.diagnostics.drain(..).map(|diag| (idx, diag)));
+    }
 
-        // Report all the errors
+    /// The name of the component in summaries
+    fn comp_name(idx: ir::CompIdx, comp: &ir::Component) -> String {
+        comp.source_name()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| format!("comp{}", idx.get()))
+    }
+
+    /// Hoist the facts of the component that must be proved and count the
+    /// facts of the component
+    fn hoist(comp: &mut ir::Component) -> (Vec<ir::Fact>, Counts) {
+        let mut counts = Counts {
+            declared: comp.get_param_asserts().len()
+                + comp.get_event_asserts().len(),
+            ..Default::default()
+        };
+        let asserts = counts.facts(&comp.cmds);
+        let to_prove = HoistFacts::hoist(comp);
+        // Assertions that hoisting proves trivially true are never checked
+        counts.asserts = asserts;
+        counts.folded = asserts - to_prove.len();
+        (to_prove, counts)
+    }
+}
+
+impl Discharge {
+    /// Discharge the constraints of the components `comps` in order
+    fn discharge(
+        opts: &cmdline::Opts,
+        ctx: &mut ir::Context,
+        comps: Vec<ir::CompIdx>,
+    ) -> Self {
+        let mut visitor = Self::from(opts, ctx);
+        for idx in comps {
+            visitor.clear_data();
+            log::trace!("{}: Visiting component {}", Self::name(), idx);
+            visitor.visit((idx, opts, &mut *ctx).into());
+        }
+        visitor
+    }
+
+    /// Decide which of the propositions `props` of the component `idx` hold
+    /// given the facts that the component assumes. The assertions of the
+    /// component are not checked again so its constraints must already have
+    /// been discharged.
+    pub fn decide(
+        opts: &cmdline::Opts,
+        ctx: &mut ir::Context,
+        idx: ir::CompIdx,
+        props: &[ir::PropIdx],
+    ) -> Vec<bool> {
+        // Check each proposition on its own to find the ones that fail
+        let opts = cmdline::Opts {
+            discharge_separate: true,
+            show_models: false,
+            ..opts.clone()
+        };
+        let comp = ctx.get_mut(idx);
+        let reason =
+            comp.add(ir::info::Reason::misc("query", GPosIdx::UNKNOWN).into());
+        // Only the assumptions at the top level constrain the propositions
+        comp.cmds.retain(|cmd| match cmd {
+            ir::Command::Fact(f) => f.is_assume(),
+            ir::Command::If(_) | ir::Command::ForLoop(_) => false,
+            _ => true,
+        });
+        for prop in props {
+            if let Some(cmd) = comp.assert(*prop, reason) {
+                comp.cmds.push(cmd);
+            }
+        }
+
+        let visitor = Self::discharge(&opts, ctx, vec![idx]);
+        let comp = ctx.get(idx);
+        props
+            .iter()
+            .map(|prop| {
+                !visitor.checked.iter().any(|(p, failed)| {
+                    failed.is_some() && p.consequent(comp) == *prop
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the options request a summary of the facts
+    fn summarize(opts: &cmdline::Opts) -> bool {
+        opts.discharge_summary || opts.stats || opts.summarize_constraints
+    }
+
+    /// Report the constraints that could not be proved and the summaries
+    /// requested by the options. The statistics requested with `--stats` are
+    /// printed in both cases while the other summaries are only printed once
+    /// all constraints are proved. Everything is printed on the standard error
+    /// so that it does not mix with the generated program. Returns the number
+    /// of errors.
+    fn report(
+        failures: &[(ir::CompIdx, cr::Diagnostic<usize>)],
+        summary: &Option<Summary>,
+        opts: &cmdline::Opts,
+    ) -> Option<Failure> {
         let is_tty = atty::is(atty::Stream::Stderr);
         let writer = StandardStream::stderr(if is_tty {
             ColorChoice::Always
         } else {
             ColorChoice::Never
         });
-        let table = GlobalPositionTable::as_ref();
-        for diag in &self.diagnostics {
-            term::emit(
-                &mut writer.lock(),
-                &term::Config::default(),
-                table.files(),
-                diag,
-            )
-            .unwrap();
-            self.error_count += 1;
+        for (_, diag) in failures {
+            emit_diagnostic(&writer, diag);
         }
-    }
-
-    fn after_traversal(&mut self) -> Option<u64> {
-        if self.error_count > 0 {
-            Some(self.error_count)
-        } else {
-            None
+        let Some(summary) = summary else {
+            return (!failures.is_empty())
+                .then(|| (failures.len() as u64).into());
+        };
+        let total = summary.total();
+        if opts.stats {
+            eprintln!("Constraint statistics:");
+            eprintln!("  proof obligations: {}", total.asserts);
+            eprintln!("  discharged trivially: {}", total.folded);
+            eprintln!("  decided by numeric bounds: {}", total.bounds);
+            eprintln!("  sent to the solver: {}", total.solver);
+            eprintln!("  failed: {}", total.failed);
+        }
+        if !failures.is_empty() {
+            return Some((failures.len() as u64).into());
+        }
+        if opts.discharge_summary {
+            eprintln!("Discharge summary:");
+            eprintln!("  assertions: {}", total.asserts);
+            eprintln!("  discharged by the solver: {}", total.solver);
+            eprintln!("  decided by numeric bounds: {}", total.bounds);
+            eprintln!("  folded trivially: {}", total.folded);
+            eprintln!("  assumptions: {}", total.assumed);
+        }
+        if opts.summarize_constraints {
+            summary.table();
         }
+        None
     }
 }
@@ -1,95 +1,174 @@
 use crate::ir_passes::lower::max_states;
+use crate::ir_passes::CompileLog;
 use fil_ir::{self as ir, Ctx, DisplayCtx};
+use fil_utils::{Error, FilamentResult};
 use itertools::Itertools;
+use serde::{Serialize, Serializer};
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// An event of a component and the interface port that triggers it
+pub struct EventInterface {
+    /// Name of the interface port or `None` for phantom events
+    pub name: Option<String>,
+    pub event: String,
+    /// Number of cycles before the event can be triggered again
+    pub delay: u64,
+    /// Number of states in the FSM of the event
+    pub states: u64,
+    pub phantom: bool,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// An output port that is high in the cycle in which an event occurs
+pub struct OutputInterface {
+    pub name: String,
+    pub event: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// A port of a component that is available in the cycles `[event+start,
+/// event+end)`
+pub struct PortInterface {
+    pub event: String,
+    pub name: String,
+    pub width: u64,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// The interface of a monomorphized component: its ports, their widths, and
+/// when the events and ports are used
+pub struct InterfaceDescription {
+    pub interfaces: Vec<EventInterface>,
+    pub output_interfaces: Vec<OutputInterface>,
+    pub inputs: Vec<PortInterface>,
+    pub outputs: Vec<PortInterface>,
+}
 
 pub struct DumpInterface;
 
 impl DumpInterface {
-    /// Print out the interface of the main component in JSON format
-    pub fn print(ctx: &ir::Context) {
-        let entrypoint = ctx
-            .entrypoint
-            .unwrap_or_else(|| panic!("No entrypoint found."));
-        let main = ctx.get(entrypoint);
-        let src_info = main
-            .src_info
-            .as_ref()
-            .unwrap_or_else(|| panic!("No source info found for main."));
-
-        // For an interface port like this:
-        //      @interface['G, 'G+5] go_G
-        // Generate the JSON information:
-        // {
-        //   "name": "go_G",
-        //   "event": "G",
-        //   "delay": 5,
-        //   "states": 2,
-        //   "phantom": false
-        // }
-        let states = max_states(main);
-        let interfaces = main
+    /// Source information of an event
+    fn event(comp: &ir::Component, idx: ir::EventIdx) -> &ir::info::Event {
+        comp.get(comp.get(idx).info).as_event().unwrap_or_else(|| {
+            panic!("No source info found for event `{}`.", comp.display(idx))
+        })
+    }
+
+    /// The interface of a monomorphized component
+    fn describe(comp: &ir::Component) -> InterfaceDescription {
+        let states = max_states(comp);
+        let interfaces = comp
             .events()
             .iter()
             .map(|(idx, ev)| {
-                let id = src_info.interface_ports.find(idx).map_or("null", |v| v.as_ref());
-                let phantom = !ev.has_interface;
+                let info = Self::event(comp, idx);
                 let ir::TimeSub::Unit(delay) = ev.delay else {
-                    panic!("Event `{}` has a non-simple delay.", main.display(idx));
+                    panic!(
+                        "Event `{}` has a non-simple delay.",
+                        comp.display(idx)
+                    );
                 };
-                let delay = delay.as_concrete(main).unwrap();
-
-                format!(
-                    "{{\"name\": \"{}\", \"event\": \"{}\", \"delay\": {}, \"states\": {}, \"phantom\": {} }}",
-                    id,
-                    src_info.events.get(idx),
-                    delay,
-                    states[&idx],
-                    phantom
-                )
+                EventInterface {
+                    name: info.interface_name.as_ref().map(|v| v.to_string()),
+                    event: info.name.to_string(),
+                    delay: delay.as_concrete(comp).unwrap(),
+                    states: states[&idx],
+                    phantom: !ev.has_interface,
+                }
             })
-            .collect_vec().join(",\n");
-
-        // For each input and output that looks like:
-        // @[G+n, G+m] left: 32
-        // Generate the JSON information:
-        // {
-        //   "event": "G",
-        //   "name": "left",
-        //   "width": 32,
-        //   "start": n,
-        //   "end": m
-        // },
+            .collect_vec();
+
         let pd_to_info = |(idx, p): (ir::PortIdx, &ir::Port)| {
-            let w = p.width.as_concrete(main).unwrap();
             assert!(
-                idx.is_not_bundle(main),
-                "Main component cannot have bundle ports in signature."
+                idx.is_not_bundle(comp),
+                "Interface cannot have bundle ports in signature."
             );
             let ir::Liveness { range, .. } = &p.live;
-            let start = main.get(range.start);
-            let end = main.get(range.end);
+            let start = comp.get(range.start);
+            let end = comp.get(range.end);
 
             assert!(
                 start.event == end.event,
                 "Range `{}` cannot be represented as a simple offset",
-                main.display(range)
+                comp.display(range)
             );
 
-            format!(
-                "{{ \"event\": \"{event}\", \"name\": \"{name}\", \"width\": {w} , \"start\": {st}, \"end\": {end} }}",
-                event = src_info.events.get(start.event),
-                name = src_info.ports.get(idx),
-                st = start.offset.as_concrete(main).unwrap(),
-                end = end.offset.as_concrete(main).unwrap(),
-            )
+            PortInterface {
+                event: Self::event(comp, start.event).name.to_string(),
+                name: comp.get(p.info).as_port().unwrap().name.to_string(),
+                width: p.width.as_concrete(comp).unwrap(),
+                start: start.offset.as_concrete(comp).unwrap(),
+                end: end.offset.as_concrete(comp).unwrap(),
+            }
         };
 
-        let inputs = main.inputs().map(pd_to_info).collect_vec().join(",\n");
-        let outputs = main.outputs().map(pd_to_info).collect_vec().join(",\n");
+        let interface_of = |p: &ir::Port| {
+            comp.get(p.info).as_port().and_then(|info| info.interface)
+        };
+        let output_interfaces = comp
+            .outputs()
+            .filter_map(|(_, p)| {
+                let event = interface_of(p)?;
+                Some(OutputInterface {
+                    name: comp.get(p.info).as_port().unwrap().name.to_string(),
+                    event: event.to_string(),
+                })
+            })
+            .collect_vec();
 
-        // Look ma, a JSON serializer!
-        println!(
-            "{{\n\"interfaces\": [\n{interfaces}\n],\n\"inputs\": [\n{inputs}\n],\n\"outputs\": [\n{outputs}\n]\n}}",
+        let inputs = comp.inputs().map(pd_to_info).collect_vec();
+        let outputs = comp
+            .outputs()
+            .filter(|(_, p)| interface_of(p).is_none())
+            .map(pd_to_info)
+            .collect_vec();
+
+        InterfaceDescription {
+            interfaces,
+            output_interfaces,
+            inputs,
+            outputs,
+        }
+    }
+
+    /// The interface of the main component
+    pub fn compute(ctx: &ir::Context) -> FilamentResult<InterfaceDescription> {
+        let Some(entrypoint) = ctx.entrypoint else {
+            return Err(Error::malformed(
+                "cannot dump the interface: the program has no top-level component",
+            ));
+        };
+        Ok(Self::describe(ctx.get(entrypoint)))
+    }
+
+    /// Print out the interface of the main component in JSON format
+    pub fn print(ctx: &ir::Context) -> FilamentResult<()> {
+        let desc = Self::compute(ctx)?;
+        println!("{}", serde_json::to_string_pretty(&desc).unwrap());
+        Ok(())
+    }
+
+    /// Print out the interface of every specialization of the source
+    /// components in JSON format, keyed by the name of the component and the
+    /// values bound to its parameters.
+    pub fn print_specializations(ctx: &ir::Context, log: &CompileLog) {
+        let interfaces = Specializations(
+            log.specializations()
+                .map(|(name, idx)| (name, Self::describe(ctx.get(idx))))
+                .collect(),
         );
+        println!("{}", serde_json::to_string_pretty(&interfaces).unwrap());
+    }
+}
+
+/// Interfaces of specializations, serialized as a JSON object whose keys are
+/// in the order the specializations were generated
+struct Specializations<'a>(Vec<(&'a str, InterfaceDescription)>);
+
+impl Serialize for Specializations<'_> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_map(self.0.iter().map(|(k, v)| (k, v)))
     }
 }
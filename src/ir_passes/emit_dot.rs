@@ -0,0 +1,98 @@
+use fil_ir::{self as ir, Ctx, DisplayCtx};
+use itertools::Itertools;
+use std::collections::BTreeSet;
+
+/// Generates a graph of the instances in a monomorphized program in the DOT
+/// format. Each component is a cluster with a node for the component itself,
+/// one for each of its instances, and one for each local port. Connections are
+/// solid edges labeled with the ports they connect and the interval of the
+/// destination. Invocations are dashed edges from the component to the
+/// invoked instance labeled with the times they bind.
+pub struct EmitDot;
+
+impl EmitDot {
+    /// The node that owns the port. Nodes are identified by the indices of
+    /// the instances and ports because monomorphization can generate several
+    /// instances with the same name.
+    fn owner(id: &str, comp: &ir::Component, port: ir::PortIdx) -> String {
+        match &comp.get(port).owner {
+            ir::PortOwner::Sig { .. } => id.to_string(),
+            ir::PortOwner::Inv { inv, .. } => {
+                format!("{id}.{}", comp.get(*inv).inst)
+            }
+            ir::PortOwner::Local => format!("{id}.{port}"),
+        }
+    }
+
+    /// Add the cluster of the component to the graph
+    fn cluster(ctx: &ir::Context, idx: ir::CompIdx, lines: &mut Vec<String>) {
+        let comp = ctx.get(idx);
+        let id = format!("comp{}", idx.get());
+        let name = ctx.display(idx);
+        lines.push(format!("  subgraph \"cluster_{id}\" {{"));
+        lines.push(format!("    label=\"{name}\";"));
+        lines.push(format!("    \"{id}\" [label=\"{name}\", shape=box];"));
+        for (inst, i) in comp.instances().iter() {
+            lines.push(format!(
+                "    \"{id}.{inst}\" [label=\"{}: {}\"];",
+                comp.display(inst),
+                ctx.display(i.comp)
+            ));
+        }
+
+        let mut locals = BTreeSet::new();
+        let mut edges = vec![];
+        for cmd in &comp.cmds {
+            let ir::Command::Connect(ir::Connect { src, dst, .. }) = cmd else {
+                continue;
+            };
+            for port in [src.port, dst.port] {
+                if comp.get(port).is_local() {
+                    locals.insert(port);
+                }
+            }
+            edges.push(format!(
+                "    \"{}\" -> \"{}\" [label=\"{} -> {} {}\"];",
+                Self::owner(&id, comp, src.port),
+                Self::owner(&id, comp, dst.port),
+                comp.display(src.port),
+                comp.display(dst.port),
+                comp.display(&comp.get(dst.port).live.range)
+            ));
+        }
+        for local in locals {
+            lines.push(format!(
+                "    \"{id}.{local}\" [label=\"{}\", shape=plaintext];",
+                comp.display(local)
+            ));
+        }
+        for (inv, invoke) in comp.invocations().iter() {
+            let times = invoke
+                .events
+                .iter()
+                .map(|eb| comp.display(eb.arg))
+                .join(", ");
+            lines.push(format!(
+                "    \"{id}\" -> \"{id}.{}\" [label=\"{}<{times}>\", style=dashed];",
+                invoke.inst,
+                comp.display(inv)
+            ));
+        }
+        lines.extend(edges);
+        lines.push("  }".to_string());
+    }
+
+    /// Generate the graph of the components in the context
+    pub fn generate(ctx: &ir::Context) -> String {
+        let mut lines = vec!["digraph design {".to_string()];
+        for (idx, comp) in ctx.comps.iter() {
+            // External components have no instances
+            if comp.is_ext() || comp.is_gen() {
+                continue;
+            }
+            Self::cluster(ctx, idx, &mut lines);
+        }
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+}
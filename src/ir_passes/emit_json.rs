@@ -0,0 +1,174 @@
+use fil_ir::{self as ir, Ctx, DisplayCtx};
+use itertools::Itertools;
+use serde::Serialize;
+
+#[derive(Serialize)]
+/// An event in the signature of a component
+struct Event {
+    name: String,
+    delay: String,
+    /// Name of the interface port that triggers the event
+    interface: Option<String>,
+}
+
+#[derive(Serialize)]
+/// A port defined by a component or an invocation
+struct Port {
+    name: String,
+    /// `input` or `output` for ports in the signature or defined by an
+    /// invocation and `local` for ports defined in the body
+    direction: &'static str,
+    width: String,
+    live: String,
+}
+
+#[derive(Serialize)]
+/// An instance of a component
+struct Instance {
+    name: String,
+    /// The `id` of the instantiated component
+    component: String,
+    params: Vec<String>,
+}
+
+#[derive(Serialize)]
+/// An invocation of an instance
+struct Invocation {
+    name: String,
+    instance: String,
+    /// The times bound to the events of the invoked component
+    events: Vec<String>,
+    ports: Vec<Port>,
+}
+
+#[derive(Serialize)]
+/// A component in the program
+struct Component {
+    /// Unique identifier of the component
+    id: String,
+    /// Name of the component in the source program. Monomorphization can
+    /// generate several components with the same name.
+    name: Option<String>,
+    external: bool,
+    events: Vec<Event>,
+    ports: Vec<Port>,
+    instances: Vec<Instance>,
+    invocations: Vec<Invocation>,
+}
+
+#[derive(Serialize)]
+struct Program {
+    /// The `id` of the entrypoint
+    entrypoint: Option<String>,
+    components: Vec<Component>,
+}
+
+/// Serializes the components of a program to JSON so that external tools can
+/// inspect the IR without parsing Filament. Components are identified by
+/// their `id` and all times and expressions are printed in the same syntax as
+/// the IR printer.
+pub struct EmitJson;
+
+impl EmitJson {
+    /// Identifier of a component that is unique within the program
+    fn id(idx: ir::CompIdx) -> String {
+        format!("comp{}", idx.get())
+    }
+
+    fn port(comp: &ir::Component, port: &ir::Port) -> Port {
+        let name = comp
+            .get(port.info)
+            .as_port()
+            .map_or("_".to_string(), |p| p.name.to_string());
+        // Ports in the signature have flipped directions
+        let direction = match &port.owner {
+            ir::PortOwner::Sig { .. } if port.is_sig_in() => "input",
+            ir::PortOwner::Sig { .. } => "output",
+            ir::PortOwner::Inv { dir, .. } => match dir {
+                ir::Direction::In => "input",
+                ir::Direction::Out => "output",
+            },
+            ir::PortOwner::Local => "local",
+        };
+        Port {
+            name,
+            direction,
+            width: comp.display(port.width),
+            live: comp.display(&port.live.range),
+        }
+    }
+
+    fn component(ctx: &ir::Context, idx: ir::CompIdx) -> Component {
+        let comp = ctx.get(idx);
+        let events = comp
+            .events()
+            .iter()
+            .map(|(idx, ev)| {
+                let info = comp.get(ev.info).as_event();
+                Event {
+                    name: info.map_or(format!("{idx}"), |i| i.name.to_string()),
+                    delay: comp.display(&ev.delay),
+                    interface: info
+                        .and_then(|i| i.interface_name)
+                        .map(|n| n.to_string()),
+                }
+            })
+            .collect();
+        let ports = comp
+            .ports()
+            .iter()
+            .filter(|(_, p)| !p.is_inv())
+            .map(|(_, p)| Self::port(comp, p))
+            .collect();
+        let instances = comp
+            .instances()
+            .iter()
+            .map(|(idx, inst)| Instance {
+                name: comp.display(idx),
+                component: Self::id(inst.comp),
+                params: inst.args.iter().map(|a| comp.display(*a)).collect(),
+            })
+            .collect();
+        let invocations = comp
+            .invocations()
+            .iter()
+            .map(|(idx, inv)| Invocation {
+                name: comp.display(idx),
+                instance: comp.display(inv.inst),
+                events: inv
+                    .events
+                    .iter()
+                    .map(|e| comp.display(e.arg))
+                    .collect(),
+                ports: inv
+                    .ports
+                    .iter()
+                    .map(|p| Self::port(comp, comp.get(*p)))
+                    .collect(),
+            })
+            .collect();
+
+        Component {
+            id: Self::id(idx),
+            name: comp.source_name().map(|n| n.to_string()),
+            external: comp.is_ext(),
+            events,
+            ports,
+            instances,
+            invocations,
+        }
+    }
+
+    /// Serialize the components in the context to JSON
+    pub fn generate(ctx: &ir::Context) -> String {
+        let program = Program {
+            entrypoint: ctx.entrypoint.map(Self::id),
+            components: ctx
+                .comps
+                .idx_iter()
+                .map(|idx| Self::component(ctx, idx))
+                .collect_vec(),
+        };
+        serde_json::to_string_pretty(&program).unwrap() + "\n"
+    }
+}
@@ -0,0 +1,72 @@
+use super::port_timing::PortTiming;
+use fil_ir as ir;
+use fil_utils::FilamentResult;
+use itertools::Itertools;
+
+/// Generates SystemVerilog assertions that check, at simulation time, that the
+/// ports of the main component carry values during their availability
+/// intervals.
+/// The assertions are placed in a separate module that is bound to the
+/// generated module so that the generated code does not have to change.
+pub struct EmitSva;
+
+impl EmitSva {
+    /// Generate the assertion module for the main component.
+    /// `prefix` is the prefix added to the names of generated modules.
+    /// Ports whose interval is not a fixed number of cycles after one event
+    /// are not checked.
+    pub fn generate(ctx: &ir::Context, prefix: &str) -> FilamentResult<String> {
+        let (main, src_info, ports) = PortTiming::main(ctx, "assertions")?;
+        let name = format!("{prefix}{}", src_info.name);
+
+        let decl = |width: u64, name: &str| {
+            if width == 1 {
+                format!("  input logic {name}")
+            } else {
+                format!("  input logic [{}:0] {name}", width - 1)
+            }
+        };
+
+        let mut inputs = vec![decl(1, "clk"), decl(1, "reset")];
+        let mut asserts = vec![];
+
+        // Interface ports are used to trigger the assertions
+        for (idx, _) in main.events().iter() {
+            if let Some(go) = src_info.interface_ports.find(idx) {
+                inputs.push(decl(1, go.as_ref()));
+            }
+        }
+
+        // For a port with the interval ['G+n, 'G+m], the assertion checks that
+        // the port has a known value in the m-n cycles starting n cycles
+        // after the interface port of 'G is asserted.
+        for port in ports {
+            let PortTiming {
+                name: port,
+                width,
+                go,
+                start,
+                end,
+                range,
+                ..
+            } = port;
+            inputs.push(decl(width, &port));
+            let Some(go) = go else {
+                // Phantom events have no signal to synchronize with
+                asserts
+                    .push(format!("  // {port}: {range} uses a phantom event"));
+                continue;
+            };
+            let len = end.saturating_sub(start);
+            asserts.push(format!(
+                "  // {port}: {range}\n  {port}_avail: assert property (@(posedge clk) disable iff (reset) {go} |-> ##{start} !$isunknown({port})[*{len}]);",
+            ));
+        }
+
+        Ok(format!(
+            "// Availability intervals of `{name}` checked at simulation time\nmodule {name}_sva(\n{}\n);\n{}\nendmodule\nbind {name} {name}_sva {name}_sva_inst(.*);",
+            inputs.iter().join(",\n"),
+            asserts.iter().join("\n"),
+        ))
+    }
+}
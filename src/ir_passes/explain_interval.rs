@@ -0,0 +1,145 @@
+use fil_ir::{self as ir, Ctx, DisplayCtx};
+use fil_utils::{Error, FilamentResult};
+
+/// Explains how the availability interval of a port is derived.
+/// Ports are named using `<comp>.<port>` for signature ports and
+/// `<comp>.<invoke>.<port>` for ports defined by invocations.
+pub struct ExplainInterval;
+
+impl ExplainInterval {
+    /// Find the component and port named by `query`
+    pub fn find_port(
+        ctx: &ir::Context,
+        query: &str,
+    ) -> FilamentResult<(ir::CompIdx, ir::PortIdx)> {
+        let Some((comp_name, port_name)) = query.split_once('.') else {
+            return Err(Error::misc(format!(
+                "Expected a port of the form <comp>.<port> but found `{query}`"
+            )));
+        };
+        let (idx, comp) = ctx
+            .comps
+            .iter()
+            .find(|(_, c)| c.source_name().map_or(false, |n| n == comp_name))
+            .ok_or_else(|| {
+                Error::misc(format!("Unknown component `{comp_name}`"))
+            })?;
+        let port = comp
+            .ports()
+            .idx_iter()
+            .find(|p| comp.display(*p) == port_name)
+            .ok_or_else(|| {
+                Error::misc(format!(
+                    "Component `{comp_name}` does not define port `{port_name}`"
+                ))
+            })?;
+        Ok((idx, port))
+    }
+
+    /// Print the explanation for the port named by `query`
+    pub fn print(ctx: &ir::Context, query: &str) -> FilamentResult<()> {
+        let (idx, port) = Self::find_port(ctx, query)?;
+        let comp = ctx.get(idx);
+
+        let mut lines = vec![];
+        let p = comp.get(port);
+        match &p.owner {
+            ir::PortOwner::Sig { .. } => {
+                lines.push(format!(
+                    "`{query}` is declared with the interval {}",
+                    comp.display(&p.live.range)
+                ));
+                // Outputs are driven by connections in the body
+                if p.is_sig_out() {
+                    let mut drivers = vec![];
+                    Self::drivers(port, &comp.cmds, &mut drivers);
+                    for src in drivers {
+                        lines
+                            .push(format!("driven by `{}`", comp.display(src)));
+                        Self::invoke_port(ctx, comp, src, &mut lines);
+                    }
+                }
+            }
+            ir::PortOwner::Inv { .. } => {
+                Self::invoke_port(ctx, comp, port, &mut lines)
+            }
+            ir::PortOwner::Local => lines.push(format!(
+                "`{query}` is a local port available for {}",
+                comp.display(&p.live.range)
+            )),
+        }
+        lines.push(format!(
+            "resolved interval: {}",
+            comp.display(&p.live.range)
+        ));
+
+        for line in lines {
+            println!("{line}");
+        }
+        Ok(())
+    }
+
+    /// Ports that are connected to `port` in the commands
+    fn drivers(
+        port: ir::PortIdx,
+        cmds: &[ir::Command],
+        drivers: &mut Vec<ir::PortIdx>,
+    ) {
+        for cmd in cmds {
+            match cmd {
+                ir::Command::Connect(con) if con.dst.port == port => {
+                    drivers.push(con.src.port)
+                }
+                ir::Command::ForLoop(l) => {
+                    Self::drivers(port, &l.body, drivers)
+                }
+                ir::Command::If(i) => {
+                    Self::drivers(port, &i.then, drivers);
+                    Self::drivers(port, &i.alt, drivers);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Explain how a port defined by an invocation gets its interval from the
+    /// signature of the invoked component.
+    fn invoke_port(
+        ctx: &ir::Context,
+        comp: &ir::Component,
+        port: ir::PortIdx,
+        lines: &mut Vec<String>,
+    ) {
+        let ir::PortOwner::Inv { inv, base, .. } = &comp.get(port).owner else {
+            return;
+        };
+        let callee = ctx.get(base.owner());
+        let callee_name = callee
+            .source_name()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| ctx.display(base.owner()));
+        let callee_port = callee.get(base.key());
+        lines.push(format!(
+            "  `{}` invokes instance `{}` of `{callee_name}`",
+            comp.display(*inv),
+            comp.display(inv.inst(comp))
+        ));
+        lines.push(format!(
+            "  `{callee_name}.{}` is declared with the interval {}",
+            callee.display(base.key()),
+            callee.display(&callee_port.live.range)
+        ));
+        for eb in &comp.get(*inv).events {
+            lines.push(format!(
+                "  {} is bound to {}",
+                callee.display(eb.base.key()),
+                comp.display(eb.arg)
+            ));
+        }
+        lines.push(format!(
+            "  `{}` is available for {}",
+            comp.display(port),
+            comp.display(&comp.get(port).live.range)
+        ));
+    }
+}
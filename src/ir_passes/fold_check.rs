@@ -0,0 +1,150 @@
+use crate::{
+    cmdline,
+    ir_visitor::{Action, Construct, Visitor, VisitorData},
+};
+use fil_ir::{self as ir, Ctx};
+use fil_utils::{Diagnostics, Failure, GPosIdx};
+
+/// Reports arithmetic that cannot be computed once the parameters of a
+/// component are known, like divisions by zero or overflows when
+/// `--time-overflow` is `error`. Constant folding keeps these expressions
+/// symbolic, so this must run before the passes that require concrete values.
+/// Must occur after monomorphization.
+pub struct FoldCheck {
+    diag: Diagnostics,
+}
+
+impl Construct for FoldCheck {
+    fn from(_: &cmdline::Opts, _: &mut ir::Context) -> Self {
+        Self {
+            diag: Diagnostics::default(),
+        }
+    }
+
+    fn clear_data(&mut self) {}
+}
+
+impl FoldCheck {
+    fn expr(&mut self, comp: &ir::Component, e: ir::ExprIdx, loc: GPosIdx) {
+        if let Some(err) = e.fold_error(comp) {
+            let info =
+                self.diag.add_info("expression cannot be evaluated", loc);
+            self.diag.add_error(err.add_note(info));
+        }
+    }
+
+    fn time(&mut self, comp: &ir::Component, t: ir::TimeIdx, loc: GPosIdx) {
+        self.expr(comp, comp.get(t).offset, loc)
+    }
+
+    fn time_sub(
+        &mut self,
+        comp: &ir::Component,
+        ts: &ir::TimeSub,
+        loc: GPosIdx,
+    ) {
+        match ts {
+            ir::TimeSub::Unit(e) => self.expr(comp, *e, loc),
+            ir::TimeSub::Sym { l, r } => {
+                self.time(comp, *l, loc);
+                self.time(comp, *r, loc);
+            }
+        }
+    }
+
+    fn range(&mut self, comp: &ir::Component, r: &ir::Range, loc: GPosIdx) {
+        self.time(comp, r.start, loc);
+        self.time(comp, r.end, loc);
+    }
+}
+
+impl Visitor for FoldCheck {
+    fn name() -> &'static str {
+        "fold-check"
+    }
+
+    fn start(&mut self, data: &mut VisitorData) -> Action {
+        let comp = &data.comp;
+        // External components are never monomorphized
+        if comp.is_ext() {
+            return Action::Stop;
+        }
+
+        for (_, port) in comp.ports().iter() {
+            let (width_loc, live_loc) = comp
+                .get(port.info)
+                .as_port()
+                .map_or((GPosIdx::UNKNOWN, GPosIdx::UNKNOWN), |p| {
+                    (p.width_loc, p.live_loc)
+                });
+            self.expr(comp, port.width, width_loc);
+            for len in &port.live.lens {
+                self.expr(comp, *len, live_loc);
+            }
+            self.range(comp, &port.live.range, live_loc);
+        }
+
+        for (_, event) in comp.events().iter() {
+            let loc = comp
+                .get(event.info)
+                .as_event()
+                .map_or(GPosIdx::UNKNOWN, |ev| ev.delay_loc);
+            self.time_sub(comp, &event.delay, loc);
+        }
+
+        for (_, inst) in comp.instances().iter() {
+            let loc = comp
+                .get(inst.info)
+                .as_instance()
+                .map_or(GPosIdx::UNKNOWN, |inst| inst.bind_loc);
+            for arg in inst.args.iter() {
+                self.expr(comp, *arg, loc);
+            }
+            for live in &inst.lives {
+                self.range(comp, live, loc);
+            }
+        }
+
+        for (_, inv) in comp.invocations().iter() {
+            for bind in &inv.events {
+                let (delay_loc, bind_loc) = comp
+                    .get(bind.info)
+                    .as_event_bind()
+                    .map_or((GPosIdx::UNKNOWN, GPosIdx::UNKNOWN), |eb| {
+                        (eb.ev_delay_loc, eb.bind_loc)
+                    });
+                self.time_sub(comp, &bind.delay, delay_loc);
+                self.time(comp, bind.arg, bind_loc);
+            }
+        }
+
+        Action::Continue
+    }
+
+    fn connect(
+        &mut self,
+        con: &mut ir::Connect,
+        data: &mut VisitorData,
+    ) -> Action {
+        let comp = &data.comp;
+        let (dst_loc, src_loc) = comp
+            .get(con.info)
+            .as_connect()
+            .map_or((GPosIdx::UNKNOWN, GPosIdx::UNKNOWN), |c| {
+                (c.dst_loc, c.src_loc)
+            });
+        for (start, end) in &con.dst.ranges {
+            self.expr(comp, *start, dst_loc);
+            self.expr(comp, *end, dst_loc);
+        }
+        for (start, end) in &con.src.ranges {
+            self.expr(comp, *start, src_loc);
+            self.expr(comp, *end, src_loc);
+        }
+        Action::Continue
+    }
+
+    fn after_traversal(&mut self) -> Option<Failure> {
+        self.diag.report_all()
+    }
+}
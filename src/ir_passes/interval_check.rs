@@ -1,9 +1,12 @@
-use crate::ir_visitor::{Action, Visitor, VisitorData};
+use crate::{
+    cmdline,
+    ir_visitor::{Action, Construct, Visitor, VisitorData},
+};
+use fil_ast as ast;
 use fil_ir::{self as ir, AddCtx, Ctx};
-use fil_utils::GPosIdx;
+use fil_utils::{Diagnostics, Failure, FilamentResult, GPosIdx};
 use itertools::Itertools;
 
-#[derive(Default)]
 /// Filament's core interval checking algorithm. At a high-level it ensures that:
 /// 1. All delays are well-formed
 /// 2. Ports are connected for as long as expected
@@ -13,11 +16,38 @@ use itertools::Itertools;
 ///   delay of the invoked component.
 /// * The availability of bundle signals is less than the delay
 /// * Shared instances are live for shorter duration than the delay
+/// * Invocations of components marked `@shared` are never active at the same
+///   time. Invocations are active from the time bound to each event until its
+///   delay has elapsed.
+///
+/// The disjointness of `@shared` invocations is checked pairwise for the
+/// invocations in the component, including those in different branches of
+/// an `if`. Different iterations of a loop are not compared with each other.
 ///
 /// Like [super::TypeCheck], this pass simply generates all the assertions that
 /// enforce the above constraints.
 /// It is the job of a latter pass to ensure that the assertions are discharged.
-pub struct IntervalCheck;
+pub struct IntervalCheck {
+    /// Active ranges of the invocations of `@shared` components visited so
+    /// far, with the locations of their event bindings
+    shared: Vec<(ir::CompIdx, Vec<(GPosIdx, ir::TimeIdx, ir::TimeIdx)>)>,
+    /// Internal errors encountered while generating the assertions
+    diag: Diagnostics,
+}
+
+impl Construct for IntervalCheck {
+    fn from(_: &cmdline::Opts, _: &mut ir::Context) -> Self {
+        IntervalCheck {
+            shared: Vec::new(),
+            diag: Diagnostics::default(),
+        }
+    }
+
+    fn clear_data(&mut self) {
+        self.shared.clear();
+        /* Diagnostics struct is shared */
+    }
+}
 
 impl IntervalCheck {
     /// Constraints to ensure that the range is well-formed, i.e., the end of
@@ -61,6 +91,94 @@ impl IntervalCheck {
         comp.assert(prop, reason)
     }
 
+    /// The subtractions `l - r` that occur in `expr`
+    fn subtractions(
+        expr: ir::ExprIdx,
+        comp: &ir::Component,
+        subs: &mut Vec<(ir::ExprIdx, ir::ExprIdx)>,
+    ) {
+        match comp.get(expr) {
+            ir::Expr::Param(_) | ir::Expr::Concrete(_) => (),
+            ir::Expr::Bin { op, lhs, rhs } => {
+                if *op == ast::Op::Sub {
+                    subs.push((*lhs, *rhs));
+                }
+                Self::subtractions(*lhs, comp, subs);
+                Self::subtractions(*rhs, comp, subs);
+            }
+            ir::Expr::Fn { args, .. } => {
+                for arg in args {
+                    Self::subtractions(*arg, comp, subs);
+                }
+            }
+        }
+    }
+
+    /// Check that a time whose offset subtracts, e.g. `'G-1` or `'G+N-1`,
+    /// does not occur before its event. Offsets are natural numbers so no
+    /// subtraction in them may be negative. Subtractions saturate or wrap
+    /// around instead when the component is compiled with `--time-overflow`.
+    fn offset_wf(
+        &mut self,
+        // The path condition
+        pc: ir::PropIdx,
+        time: ir::TimeIdx,
+        loc: GPosIdx,
+        comp: &mut ir::Component,
+    ) -> Option<ir::Command> {
+        if comp.time_overflow != ir::TimeOverflow::Error {
+            return None;
+        }
+        let mut subs = vec![];
+        Self::subtractions(comp[time].offset, comp, &mut subs);
+        if subs.is_empty() {
+            return None;
+        }
+        let prop =
+            subs.into_iter()
+                .fold(comp.add(ir::Prop::True), |acc, (l, r)| {
+                    let non_neg = l.gte(r, comp);
+                    acc.and(non_neg, comp)
+                });
+        let prop = pc.implies(prop, comp);
+        let reason = comp.add(
+            ir::info::Reason::misc("time may occur before its event", loc)
+                .into(),
+        );
+        comp.assert(prop, reason)
+    }
+
+    /// Constraints to ensure that the outputs of a component with a latency
+    /// contract, i.e., a `@latency(n)` attribute, become available `n` cycles
+    /// after the event in their interval.
+    fn latency_contract(
+        &mut self,
+        latency: u64,
+        comp: &mut ir::Component,
+    ) -> Vec<ir::Command> {
+        let outputs = comp
+            .outputs()
+            .map(|(_, p)| (p.live.range.start, p.info))
+            .collect_vec();
+        outputs
+            .into_iter()
+            .filter_map(|(start, info)| {
+                let &ir::info::Port { live_loc, .. } = comp.get(info).into();
+                let offset = comp.get(start).offset;
+                let latency_expr = comp.num(latency);
+                let prop = offset.equal(latency_expr, comp);
+                let reason = comp.add(
+                    ir::info::Reason::misc(
+                        format!("latency contract requires output to be available {latency} cycles after its event"),
+                        live_loc,
+                    )
+                    .into(),
+                );
+                comp.assert(prop, reason)
+            })
+            .collect()
+    }
+
     /// Proposition that ensures that the given parameter is in range
     fn in_range(live: &ir::Liveness, comp: &mut ir::Component) -> ir::PropIdx {
         let &ir::Liveness { idxs, lens, .. } = &live;
@@ -115,6 +233,61 @@ impl IntervalCheck {
         let prop = this_delay.gte(inv_delay.clone(), comp);
         comp.assert(prop, reason)
     }
+
+    /// Constraints to ensure that the invocation of the `@shared` component
+    /// `callee` is not active at the same time as the invocations of it
+    /// visited before.
+    fn shared_disjoint(
+        &mut self,
+        callee: ir::CompIdx,
+        inv_idx: ir::InvIdx,
+        comp: &mut ir::Component,
+    ) -> Vec<ir::Command> {
+        let locs = comp
+            .get(comp.get(inv_idx).info)
+            .as_invoke()
+            .map(|info| info.event_bind_locs.clone())
+            .unwrap_or_default();
+        let ranges = comp[inv_idx]
+            .events
+            .clone()
+            .into_iter()
+            .enumerate()
+            .map(|(i, ir::EventBind { delay, arg, .. })| {
+                let loc = locs.get(i).copied().unwrap_or(GPosIdx::UNKNOWN);
+                Ok((loc, arg, arg.add(&delay, loc, comp)?))
+            })
+            .collect::<FilamentResult<Vec<_>>>();
+        let ranges = match ranges {
+            Ok(ranges) => ranges,
+            Err(err) => {
+                self.diag.add_error(err);
+                return vec![];
+            }
+        };
+
+        let mut cmds = Vec::default();
+        for (_, prev) in self.shared.iter().filter(|(c, _)| *c == callee) {
+            for (&(p_loc, p_start, p_end), &(loc, start, end)) in
+                prev.iter().zip(&ranges)
+            {
+                let prop =
+                    p_end.lte(start, comp).or(end.lte(p_start, comp), comp);
+                let reason = comp.add(
+                    ir::info::Reason::shared_overlap(
+                        p_loc,
+                        (p_start, p_end),
+                        loc,
+                        (start, end),
+                    )
+                    .into(),
+                );
+                cmds.extend(comp.assert(prop, reason));
+            }
+        }
+        self.shared.push((callee, ranges));
+        cmds
+    }
 }
 
 impl Visitor for IntervalCheck {
@@ -122,6 +295,10 @@ impl Visitor for IntervalCheck {
         "interval-check"
     }
 
+    fn after_traversal(&mut self) -> Option<Failure> {
+        self.diag.report_all()
+    }
+
     fn start(&mut self, data: &mut VisitorData) -> Action {
         let comp = &mut data.comp;
 
@@ -160,6 +337,8 @@ impl Visitor for IntervalCheck {
             let range = live.range;
             // Require that the range is well-formed
             cmds.extend(self.range_wf(assumes, &range, live_loc, comp));
+            cmds.extend(self.offset_wf(assumes, range.start, live_loc, comp));
+            cmds.extend(self.offset_wf(assumes, range.end, live_loc, comp));
 
             // We only constraint the event mentioned in the start of the range.
             let st_ev = comp[range.start].event;
@@ -193,6 +372,11 @@ impl Visitor for IntervalCheck {
             let imp = assumes.implies(prop, comp);
             cmds.extend(comp.assert(imp, reason));
         }
+
+        // Outputs must be available when the latency contract promises
+        if let Some(latency) = comp.attrs.value("latency") {
+            cmds.extend(self.latency_contract(latency, comp));
+        }
         Action::AddBefore(cmds)
     }
 
@@ -220,8 +404,18 @@ impl Visitor for IntervalCheck {
                     arg: use_start,
                     ..
                 } = live;
+                let loc = inv_info
+                    .as_ref()
+                    .and_then(|inv| inv.event_bind_locs.get(i).copied())
+                    .unwrap_or(GPosIdx::UNKNOWN);
+                let use_end = match use_start.add(delay, loc, comp) {
+                    Ok(use_end) => use_end,
+                    Err(err) => {
+                        self.diag.add_error(err);
+                        continue;
+                    }
+                };
                 let start_after = use_start.gte(*start, comp);
-                let use_end = use_start.add(delay, comp);
                 let end_before = use_end.lte(*end, comp);
 
                 // Location information
@@ -253,11 +447,21 @@ impl Visitor for IntervalCheck {
         }
 
         // Clone here because we need to pass mutable ownership of the component
+        let init = comp.add(ir::Prop::True);
         for eb in events.clone() {
+            let &ir::info::EventBind { bind_loc, .. } =
+                comp.get(eb.info).into();
+            cmds.extend(self.offset_wf(init, eb.arg, bind_loc, comp));
             if let Some(assert) = self.event_binding(eb, comp) {
                 cmds.push(assert)
             }
         }
+
+        // Invocations of shared components must not overlap
+        let callee = comp.get(inst_idx).comp;
+        if data.mut_ctx.get(callee).attrs.has("shared") {
+            cmds.extend(self.shared_disjoint(callee, inv_idx, comp));
+        }
         Action::AddBefore(cmds)
     }
 
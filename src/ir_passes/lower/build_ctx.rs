@@ -1,10 +1,13 @@
 use super::fsm::{FsmBind, FsmType};
 use super::utils::{cell_to_port_def, NameGenerator};
-use super::Fsm;
+use super::{Fsm, FsmEncoding, PrimitiveMap};
 use calyx_ir::{self as calyx, RRC};
 use fil_ir::{self as ir, Ctx, DenseIndexInfo, DisplayCtx};
 use itertools::Itertools;
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 #[derive(Default)]
 /// Bindings associated with the current compilation context
@@ -13,9 +16,21 @@ pub(super) struct Binding {
     comps: HashMap<ir::CompIdx, RRC<calyx::Cell>>,
     /// Mapping to the component representing FSM with particular number of states
     pub fsm_comps: FsmBind,
+    /// Primitives used to implement the generated hardware
+    pub prims: PrimitiveMap,
 }
 
 impl Binding {
+    /// Construct a binding whose FSM components are named with the given
+    /// prefix and built using the given primitives
+    pub fn new(prefix: &str, prims: &PrimitiveMap) -> Self {
+        Self {
+            comps: HashMap::default(),
+            fsm_comps: FsmBind::new(prefix, prims.clone()),
+            prims: prims.clone(),
+        }
+    }
+
     /// Inserts a [calyx::Cell] into the binding
     pub fn insert(&mut self, name: ir::CompIdx, sig: RRC<calyx::Cell>) {
         self.comps.insert(name, sig);
@@ -34,8 +49,8 @@ pub(super) struct BuildCtx<'a> {
     pub comp: &'a ir::Component,
     ctx: &'a ir::Context,
     lib: &'a calyx::LibrarySignatures,
-    /// Disable generation of slow FSMs
-    disable_slow_fsms: bool,
+    /// Encoding of the generated FSMs
+    encoding: FsmEncoding,
     /// Helper to generate names
     ng: &'a NameGenerator,
     /// Mapping from events to the FSM that reify them.
@@ -44,6 +59,8 @@ pub(super) struct BuildCtx<'a> {
     instances: DenseIndexInfo<ir::Instance, RRC<calyx::Cell>>,
     /// Mapping from [ir::InstIdx]s to a reference of the calyx cell instantiated/invoked
     invokes: DenseIndexInfo<ir::Invoke, RRC<calyx::Cell>>,
+    /// First pipeline register for each output port of a pipelined component
+    pipeline: HashMap<ir::PortIdx, RRC<calyx::Cell>>,
 }
 
 impl<'a> BuildCtx<'a> {
@@ -51,14 +68,14 @@ impl<'a> BuildCtx<'a> {
         ctx: &'a ir::Context,
         idx: ir::CompIdx,
         binding: &'a mut Binding,
-        disable_slow_fsms: bool,
+        encoding: FsmEncoding,
         ng: &'a NameGenerator,
         builder: calyx::Builder<'a>,
         lib: &'a calyx::LibrarySignatures,
     ) -> Self {
         BuildCtx {
             ctx,
-            disable_slow_fsms,
+            encoding,
             ng,
             comp: ctx.get(idx),
             binding,
@@ -67,6 +84,59 @@ impl<'a> BuildCtx<'a> {
             instances: DenseIndexInfo::default(),
             invokes: DenseIndexInfo::default(),
             fsms: HashMap::new(),
+            pipeline: HashMap::new(),
+        }
+    }
+
+    /// Inserts `stages` registers in front of every output port of the
+    /// component. Connections to an output port write into the first register.
+    pub fn add_pipeline(&mut self, stages: u64) {
+        if stages == 0 {
+            return;
+        }
+        let comp = self.comp;
+        let on = self.builder.add_constant(1, 1);
+        let this = self.builder.component.signature.clone();
+        for (idx, port) in comp.outputs() {
+            let name = self.ng.port_name(idx, self.ctx, comp);
+            let width = port.width.concrete(comp);
+            let regs = (0..stages)
+                .map(|stage| {
+                    self.builder.add_primitive(
+                        self.ng.prefixed(format!("{name}_pipe{stage}")),
+                        self.binding.prims.register.as_str(),
+                        &[width],
+                    )
+                })
+                .collect_vec();
+
+            // Registers are always enabled and feed into the next stage
+            for (stage, reg) in regs.iter().enumerate() {
+                let reg = reg.borrow();
+                let mut assigns = vec![self.builder.build_assignment(
+                    reg.get("write_en"),
+                    on.borrow().get("out"),
+                    calyx::Guard::True,
+                )];
+                if stage > 0 {
+                    assigns.push(self.builder.build_assignment(
+                        reg.get("in"),
+                        regs[stage - 1].borrow().get("out"),
+                        calyx::Guard::True,
+                    ));
+                }
+                self.builder
+                    .component
+                    .continuous_assignments
+                    .extend(assigns);
+            }
+            let out = self.builder.build_assignment(
+                this.borrow().get(name),
+                regs.last().unwrap().borrow().get("out"),
+                calyx::Guard::True,
+            );
+            self.builder.component.continuous_assignments.push(out);
+            self.pipeline.insert(idx, Rc::clone(&regs[0]));
         }
     }
 
@@ -190,6 +260,10 @@ impl<'a> BuildCtx<'a> {
         let name = self.ng.port_name(idx, self.ctx, self.comp);
 
         let guard = self.compile_range(&port.live.range);
+        // Outputs of pipelined components are written to the first register
+        if let Some(reg) = self.pipeline.get(&idx) {
+            return (reg.borrow().get("in"), guard);
+        }
         let cell = match port.owner {
             ir::PortOwner::Sig { .. } => {
                 self.builder.component.signature.borrow()
@@ -225,6 +299,34 @@ impl<'a> BuildCtx<'a> {
         self.builder.component.continuous_assignments.push(assign);
     }
 
+    /// Drives the output ports of the component that are never assigned with
+    /// zero. The assignment checks only accept such ports when
+    /// `--allow-unconnected-outputs` is set.
+    pub fn tie_off_outputs(&mut self) {
+        let comp = self.comp;
+        let driven: HashSet<ir::PortIdx> = comp
+            .cmds
+            .iter()
+            .filter_map(|cmd| match cmd {
+                ir::Command::Connect(con) => Some(con.dst.port),
+                _ => None,
+            })
+            .collect();
+        for (idx, port) in comp.outputs() {
+            if driven.contains(&idx) {
+                continue;
+            }
+            let zero = self.builder.add_constant(0, port.width.concrete(comp));
+            let (dst, _) = self.compile_port(idx);
+            let assign = self.builder.build_assignment(
+                dst,
+                zero.borrow().get("out"),
+                calyx::Guard::True,
+            );
+            self.builder.component.continuous_assignments.push(assign);
+        }
+    }
+
     /// Attempts to declare an fsm component (if not already declared) in the [Binding] stored by this [BuildCtx]
     /// and creates an [Fsm] from this [calyx::Component] FSM and stores it in the [BuildCtx]
     pub fn insert_fsm(&mut self, event: ir::EventIdx, states: u64) {
@@ -237,7 +339,7 @@ impl<'a> BuildCtx<'a> {
                 );
             };
             let delay = delay.concrete(self.comp);
-            let typ = FsmType::new(states, delay, self.disable_slow_fsms);
+            let typ = FsmType::new(states, delay, self.encoding);
             self.implement_fsm(&typ);
 
             // Construct the FSM
@@ -1,12 +1,14 @@
 use super::{
     build_ctx::{Binding, BuildCtx},
     max_states,
-    utils::{NameGenerator, INTERFACE_PORTS},
+    utils::{NameGenerator, CALYX_COMP_ATTRS, INTERFACE_PORTS},
+    FsmEncoding, PrimitiveMap,
 };
 use calyx_frontend as frontend;
 use calyx_ir as calyx;
 use calyx_utils::CalyxResult;
 use fil_ir::{self as ir, Ctx, Traversal};
+use fil_utils::FilamentResult;
 use std::{collections::HashSet, convert::identity, path::PathBuf, rc::Rc};
 
 #[derive(Default)]
@@ -35,9 +37,18 @@ impl Compile {
             )
         };
 
-        // adds the `@data` attribute to the port
+        // adds the `@fil_event` attribute to output interface ports and the
+        // `@data` attribute to all other ports
         let mut attributes = calyx::Attributes::default();
-        attributes.insert(calyx::BoolAttr::Data, 1);
+        if comp
+            .get(raw_port.info)
+            .as_port()
+            .map_or(false, |p| p.interface.is_some())
+        {
+            attributes.insert(calyx::Attribute::Unknown("fil_event".into()), 1);
+        } else {
+            attributes.insert(calyx::BoolAttr::Data, 1);
+        }
 
         calyx::PortDef::new(
             name_gen.port_name(port, ctx, comp),
@@ -185,7 +196,7 @@ impl Compile {
 
     /// Compiles an [ir::Component] into a [calyx::Component]
     fn component(
-        disable_slow_fsms: bool,
+        encoding: FsmEncoding,
         ctx: &ir::Context,
         idx: ir::CompIdx,
         bind: &mut Binding,
@@ -222,22 +233,31 @@ impl Compile {
             component.attributes.insert(calyx::BoolAttr::TopLevel, 1);
         }
 
+        // Carry over known component attributes for downstream Calyx passes
+        if name_gen.preserve_names() {
+            for attr in comp.attrs.iter().filter(|a| {
+                CALYX_COMP_ATTRS.iter().any(|n| a.name.inner() == *n)
+            }) {
+                component.attributes.insert(
+                    calyx::Attribute::Unknown(
+                        attr.name.inner().as_ref().into(),
+                    ),
+                    attr.value.unwrap_or(1),
+                );
+            }
+        }
+
         let builder = calyx::Builder::new(&mut component, lib).not_generated();
-        let mut buildctx = BuildCtx::new(
-            ctx,
-            idx,
-            bind,
-            disable_slow_fsms,
-            name_gen,
-            builder,
-            lib,
-        );
+        let mut buildctx =
+            BuildCtx::new(ctx, idx, bind, encoding, name_gen, builder, lib);
 
         // Construct all the FSMs
         for (event, states) in max_states(comp) {
             buildctx.insert_fsm(event, states);
         }
 
+        buildctx.add_pipeline(comp.attrs.value("pipeline").unwrap_or(0));
+
         for inst in comp.instances().idx_iter() {
             buildctx.add_instance(inst);
         }
@@ -269,10 +289,32 @@ impl Compile {
                 | ir::Command::Fact(_) => (),
             }
         }
+        buildctx.tie_off_outputs();
 
         component
     }
 
+    /// Documentation of the signature ports in the program as lines of the
+    /// form `<component>.<port>: <doc>`. Calyx attributes can only hold
+    /// numbers, so the documentation is carried in the metadata block.
+    fn port_docs(ctx: &ir::Context, name_gen: &NameGenerator) -> Vec<String> {
+        ctx.comps
+            .iter()
+            .flat_map(|(idx, comp)| {
+                comp.ports().iter().filter(|(_, p)| p.is_sig()).filter_map(
+                    move |(port, p)| {
+                        let doc = comp.get(p.info).as_port()?.doc.as_ref()?;
+                        Some(format!(
+                            "{}.{}: {doc}",
+                            name_gen.comp_name(idx, ctx),
+                            name_gen.port_name(port, ctx, comp)
+                        ))
+                    },
+                )
+            })
+            .collect()
+    }
+
     fn init(
         ctx: &ir::Context,
         externs: Vec<(&String, Vec<ir::CompIdx>)>,
@@ -298,31 +340,47 @@ impl Compile {
         Ok(ctx)
     }
 
-    /// Compiles filament into calyx
+    /// Compiles filament into calyx.
+    /// Fails if the primitives in `prims` are not defined by the program.
     pub fn compile(
         ctx: ir::Context,
-        disable_slow_fsms: bool,
+        encoding: FsmEncoding,
         debug: bool,
-    ) -> calyx::Context {
-        // Creates a map between the file name and the external components defined in that file
-        let externals =
+        prefix: String,
+        prims: &PrimitiveMap,
+    ) -> FilamentResult<calyx::Context> {
+        // Creates a map between the file name and the external components defined in that file.
+        // Files are sorted so that the output does not depend on hashing.
+        let mut externals: Vec<_> =
             ctx.externals.iter().map(|(k, v)| (k, v.clone())).collect();
+        externals.sort_by(|(a, _), (b, _)| a.cmp(b));
 
-        let name_gen = NameGenerator::new(debug);
+        let name_gen = NameGenerator::new(debug, prefix);
 
         let mut calyx_ctx = Compile::init(&ctx, externals, &name_gen)
             .unwrap_or_else(|e| {
                 panic!("Error initializing calyx context: {:?}", e);
             });
+        prims.validate(&calyx_ctx.lib)?;
+
+        let docs = Compile::port_docs(&ctx, &name_gen);
+        if !docs.is_empty() {
+            calyx_ctx.metadata = Some(docs.join("\n"));
+        }
+
+        // The entrypoint is named like the component it refers to
+        if let Some(idx) = ctx.entrypoint {
+            calyx_ctx.entrypoint = name_gen.comp_name(idx, &ctx).into();
+        }
 
-        let mut bindings = Binding::default();
+        let mut bindings = Binding::new(name_gen.prefix(), prims);
 
         let po = Traversal::from(ctx);
 
         // Compile the components in post-order.
         po.apply_pre_order(|ctx, idx| {
             let comp = Compile::component(
-                disable_slow_fsms,
+                encoding,
                 ctx,
                 idx,
                 &mut bindings,
@@ -336,6 +394,6 @@ impl Compile {
         // add the fsm components to the calyx context
         calyx_ctx.components.extend(bindings.fsm_comps.take());
 
-        calyx_ctx
+        Ok(calyx_ctx)
     }
 }
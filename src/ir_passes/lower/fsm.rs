@@ -1,7 +1,7 @@
 use super::utils::NameGenerator;
 use super::{
     utils::{cell_to_port_def, INTERFACE_PORTS},
-    BuildCtx,
+    BuildCtx, PrimitiveMap,
 };
 use calyx_ir::{self as calyx, RRC};
 use calyx_ir::{build_assignments, guard, structure, Guard, Nothing};
@@ -9,6 +9,17 @@ use fil_ir as ir;
 use itertools::Itertools;
 use std::{collections::HashMap, iter, ops::Not};
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Encoding of the fsms generated for the events of a component.
+pub enum FsmEncoding {
+    /// Chains of counters that count in binary.
+    Binary,
+    /// A shift register with one register per state.
+    OneHot,
+    /// Chains of counters that count in gray code.
+    Gray,
+}
+
 #[derive(Hash, PartialEq, Eq, Debug)]
 /// Enum representing the types of fsms that can be generated and their indexing.
 pub enum FsmType {
@@ -18,19 +29,32 @@ pub enum FsmType {
     Counter(u64),
     /// A counter chain fsm with `n` counters each with `d` states.
     CounterChain(u64, u64),
+    /// A counter fsm with `n` states that counts in gray code.
+    GrayCounter(u64),
+    /// A counter chain fsm with `n` gray code counters each with `d` states.
+    GrayCounterChain(u64, u64),
 }
 
 impl FsmType {
-    /// Generates an FsmType based on the default heuristic on the number of states and the delay (II).
-    /// If enable_slow_fsms is false, then only simple fsms are generated.
-    pub fn new(states: u64, delay: u64, disable_slow_fsms: bool) -> Self {
+    /// Generates an FsmType with the given encoding for the number of states and the delay (II).
+    /// Counters need more than one state so components with a delay of one always use simple fsms.
+    pub fn new(states: u64, delay: u64, encoding: FsmEncoding) -> Self {
         // TODO(UnsignedByte): Find a better metric to decide which type of fsm to generate.
-        if !disable_slow_fsms && delay > 1 {
-            FsmType::CounterChain(states, delay)
-        } else {
-            FsmType::Simple(states)
+        match encoding {
+            FsmEncoding::Binary if delay > 1 => {
+                FsmType::CounterChain(states, delay)
+            }
+            FsmEncoding::Gray if delay > 1 => {
+                FsmType::GrayCounterChain(states, delay)
+            }
+            _ => FsmType::Simple(states),
         }
     }
+
+    /// The gray code for the `n`th state
+    fn gray(n: u64) -> u64 {
+        n ^ (n >> 1)
+    }
 }
 
 #[derive(Default)]
@@ -38,9 +62,21 @@ impl FsmType {
 pub(super) struct FsmBind {
     /// Fsm components using `n` counters each with `d` states, stored under `(n, d)`.
     fsms: HashMap<FsmType, calyx::Component>,
+    /// Prefix added to the names of the fsm components and their cells
+    prefix: String,
+    /// Primitives used to implement the fsms
+    prims: PrimitiveMap,
 }
 
 impl FsmBind {
+    pub fn new(prefix: &str, prims: PrimitiveMap) -> Self {
+        Self {
+            fsms: HashMap::default(),
+            prefix: prefix.to_string(),
+            prims,
+        }
+    }
+
     /// Get an fsm with the number of states and minimum delay (II) from the binding
     pub fn get(&mut self, typ: &FsmType) -> &calyx::Component {
         self.add_opt(typ, None)
@@ -62,10 +98,15 @@ impl FsmBind {
         // Attempts to either add a new FSM or get an existing one.
         match typ {
             FsmType::Simple(states) => self.add_simple(*states, lib),
-            FsmType::Counter(states) => self.add_counter(*states, lib),
-            FsmType::CounterChain(states, delay) => {
+            FsmType::Counter(states) => self.add_counter(*states, false, lib),
+            FsmType::GrayCounter(states) => {
+                self.add_counter(*states, true, lib)
+            }
+            FsmType::CounterChain(states, delay)
+            | FsmType::GrayCounterChain(states, delay) => {
                 let fsm_num = states / delay + (states % delay != 0) as u64;
-                self.add_counter_chain(fsm_num, *delay, lib)
+                let gray = matches!(typ, FsmType::GrayCounterChain(..));
+                self.add_counter_chain(fsm_num, *delay, gray, lib)
             }
         }
     }
@@ -76,21 +117,28 @@ impl FsmBind {
     }
 
     /// Helper function that generates a [calyx::Component] for an fsm chaining `states` counters with `delay` states each.
+    /// If `gray` is true, the counters count in gray code.
     fn add_counter_chain(
         &mut self,
         fsm_num: u64,
         delay: u64,
+        gray: bool,
         lib: Option<&calyx::LibrarySignatures>,
     ) -> &calyx::Component {
         // Ensure that there is a counter component that counts up to delay.
-        let counter = self.add_counter(delay, lib);
+        let counter = self.add_counter(delay, gray, lib);
         let (name, sig) = (
             counter.name.to_string(),
             cell_to_port_def(&counter.signature),
         );
 
         // If we've already defined the component, return it.
-        let key = FsmType::CounterChain(fsm_num, delay);
+        let (key, kind) = if gray {
+            (FsmType::GrayCounterChain(fsm_num, delay), "gray_")
+        } else {
+            (FsmType::CounterChain(fsm_num, delay), "")
+        };
+        let prefix = &self.prefix;
         self.fsms.entry(key).or_insert_with(|| {
             // gets the number of bits needed to represent the counter state.
             let bitwidth = (64 - (delay - 1).leading_zeros()) as u64;
@@ -138,7 +186,10 @@ impl FsmBind {
                 .collect();
 
             let mut comp = calyx::Component::new(
-                calyx::Id::from(format!("counter_chain_{}_{}", fsm_num, delay)),
+                calyx::Id::from(format!(
+                    "{prefix}{kind}counter_chain_{}_{}",
+                    fsm_num, delay
+                )),
                 ports,
                 false,
                 false,
@@ -154,7 +205,7 @@ impl FsmBind {
             let counters = (0..fsm_num)
                 .map(|fsm| {
                     builder.add_component(
-                        format!("c{}", fsm),
+                        format!("{prefix}c{}", fsm),
                         name.clone(),
                         sig.clone(),
                     )
@@ -207,13 +258,22 @@ impl FsmBind {
     }
 
     /// Helper function that generates a [calyx:Component] for a counter with `n` states.
+    /// If `gray` is true, the counter counts in gray code.
     fn add_counter(
         &mut self,
         states: u64,
+        gray: bool,
         lib: Option<&calyx::LibrarySignatures>,
     ) -> &calyx::Component {
+        let prefix = &self.prefix;
+        let prims = &self.prims;
+        let kind = if gray { "gray_" } else { "" };
         self.fsms
-            .entry(FsmType::Counter(states))
+            .entry(if gray {
+                FsmType::GrayCounter(states)
+            } else {
+                FsmType::Counter(states)
+            })
             .or_insert_with(|| {
                 // gets the number of bits needed to represent the counter state.
                 let bitwidth = (64 - (states - 1).leading_zeros()) as u64;
@@ -258,7 +318,10 @@ impl FsmBind {
                     .collect();
 
                 let mut comp = calyx::Component::new(
-                    calyx::Id::from(format!("counter_{}", states)),
+                    calyx::Id::from(format!(
+                        "{prefix}{kind}counter_{}",
+                        states
+                    )),
                     ports,
                     false,
                     false,
@@ -270,16 +333,31 @@ impl FsmBind {
                 let mut builder = calyx::Builder::new(&mut comp, lib.unwrap())
                     .not_generated();
 
+                // The encoding of the final state
+                let last = if gray {
+                    FsmType::gray(states - 1)
+                } else {
+                    states - 1
+                };
+
                 // Constant signal
                 structure!(builder;
                     let signal_off = constant(0, 1);
                     let signal_on = constant(1, 1);
-                    let one = constant(1, bitwidth);
                     let zero = constant(0, bitwidth);
-                    let add = prim std_add(bitwidth);
-                    let state = prim std_reg(bitwidth);
-                    let done = prim std_reg(1);
-                    let final_state = constant(states-1, bitwidth);
+                );
+                let state = builder.add_primitive(
+                    "state",
+                    prims.register.as_str(),
+                    &[bitwidth],
+                );
+                let done = builder.add_primitive(
+                    "done",
+                    prims.register.as_str(),
+                    &[1],
+                );
+                structure!(builder;
+                    let final_state = constant(last, bitwidth);
                 );
 
                 // This component's interface
@@ -302,14 +380,47 @@ impl FsmBind {
                 let zero_check =
                     guard!(this["go"] & (state["out"] == zero["out"]));
 
+                if gray {
+                    // The next state of each state is a separate constant
+                    // because gray codes cannot be incremented with an adder
+                    for st in 0..states - 1 {
+                        structure!(builder;
+                            let cur = constant(FsmType::gray(st), bitwidth);
+                            let next =
+                                constant(FsmType::gray(st + 1), bitwidth);
+                        );
+                        let next_check = go_check
+                            .clone()
+                            .and(guard!(state["out"] == cur["out"]));
+                        builder.component.continuous_assignments.extend(
+                            build_assignments!(builder;
+                                state["in"] = next_check ? next["out"];
+                            ),
+                        );
+                    }
+                } else {
+                    structure!(builder;
+                        let one = constant(1, bitwidth);
+                    );
+                    let add = builder.add_primitive(
+                        "add",
+                        prims.adder.as_str(),
+                        &[bitwidth],
+                    );
+                    builder.component.continuous_assignments.extend(
+                        build_assignments!(builder;
+                            // build assignments for state+1
+                            add["left"] = ? state["out"];
+                            add["right"] = ? one["out"];
+                            // hook up adder to register
+                            state["in"] = go_check ? add["out"];
+                        ),
+                    );
+                }
+
                 // add base assignments
                 builder.component.continuous_assignments.extend(
                     build_assignments!(builder;
-                        // build assignments for state+1
-                        add["left"] = ? state["out"];
-                        add["right"] = ? one["out"];
-                        // hook up adder to register
-                        state["in"] = go_check ? add["out"];
                         // hook up zero when counter finished
                         state["in"] = rst_check ? zero["out"];
                         // always enable register when there is an input
@@ -338,6 +449,8 @@ impl FsmBind {
         states: u64,
         lib: Option<&calyx::LibrarySignatures>,
     ) -> &calyx::Component {
+        let prefix = &self.prefix;
+        let prims = &self.prims;
         self.fsms.entry(FsmType::Simple(states)).or_insert_with(|| {
             let ports: Vec<calyx::PortDef<u64>> = (0..states)
                 // create the state ports in the format `_state`.
@@ -374,7 +487,7 @@ impl FsmBind {
                 .collect();
 
             let mut comp = calyx::Component::new(
-                calyx::Id::from(format!("fsm_{}", states)),
+                calyx::Id::from(format!("{prefix}fsm_{}", states)),
                 ports,
                 false,
                 false,
@@ -388,7 +501,13 @@ impl FsmBind {
 
             // Add n-1 registers
             let regs = (0..states)
-                .map(|_| builder.add_primitive("r", "std_reg", &[1]))
+                .map(|_| {
+                    builder.add_primitive(
+                        format!("{prefix}r"),
+                        prims.register.as_str(),
+                        &[1],
+                    )
+                })
                 .collect_vec();
 
             // Constant signal
@@ -496,7 +615,34 @@ impl FsmBind {
                 // generate the final guard
                 guard.map_or(g.clone(), |gg| gg.or(g))
             }
-            FsmType::CounterChain(_, delay) => {
+            FsmType::GrayCounter(states) => {
+                let bitwidth = (64 - (states - 1).leading_zeros()) as u64;
+
+                // if start is zero, we need to use its special port instead
+                let (start, guard) = if start == 0 {
+                    (start + 1, Some(guard!(cell[format!("{prefix}_0")])))
+                } else {
+                    (start, None)
+                };
+
+                // gray codes are not ordered so each state is checked separately
+                guard
+                    .into_iter()
+                    .chain((start..end).map(|st| {
+                        let code =
+                            builder.add_constant(FsmType::gray(st), bitwidth);
+                        guard!(cell[format!("{prefix}state")] == code["out"])
+                    }))
+                    .reduce(calyx::Guard::or)
+                    .unwrap()
+            }
+            FsmType::CounterChain(_, delay)
+            | FsmType::GrayCounterChain(_, delay) => {
+                let counter = if matches!(ft, FsmType::GrayCounterChain(..)) {
+                    FsmType::GrayCounter(*delay)
+                } else {
+                    FsmType::Counter(*delay)
+                };
                 let fsm_start = start / delay;
                 let fsm_end = (end - 1) / delay;
 
@@ -508,7 +654,7 @@ impl FsmBind {
                     FsmBind::range_guard(
                         builder,
                         cell,
-                        &FsmType::Counter(*delay),
+                        &counter,
                         format!("_{}", fsm_start),
                         start,
                         end,
@@ -529,7 +675,7 @@ impl FsmBind {
                             FsmBind::range_guard(
                                 builder,
                                 cell.clone(),
-                                &FsmType::Counter(*delay),
+                                &counter,
                                 format!("_{}", i as u64 + fsm_start),
                                 s,
                                 e,
@@ -567,7 +713,7 @@ impl Fsm {
 
         // Creates a new component for the FSM.
         let cell = ctx.builder.add_component(
-            name.clone(),
+            name_gen.prefixed(&name),
             comp.name.to_string(),
             cell_to_port_def(&comp.signature),
         );
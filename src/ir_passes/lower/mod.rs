@@ -1,10 +1,13 @@
 mod build_ctx;
 mod compile;
 mod fsm;
+mod primitives;
 mod utils;
 
 use build_ctx::BuildCtx;
 use fsm::{Fsm, FsmType};
 
 pub use compile::Compile;
+pub use fsm::FsmEncoding;
+pub use primitives::PrimitiveMap;
 pub use utils::max_states;
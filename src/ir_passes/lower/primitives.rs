@@ -0,0 +1,77 @@
+use calyx_ir as calyx;
+use fil_utils::{Error, FilamentResult};
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+/// The Calyx primitives used to implement the hardware generated during
+/// compilation. Teams with custom primitive libraries can remap them using a
+/// JSON file of the form `{"register": "my_reg", "adder": "my_add"}`.
+/// Constructs that are not mentioned use the Calyx standard library.
+pub struct PrimitiveMap {
+    /// Registers used by FSMs and pipelined outputs
+    pub register: String,
+    /// Adders used by counter FSMs
+    pub adder: String,
+}
+
+impl Default for PrimitiveMap {
+    fn default() -> Self {
+        Self {
+            register: "std_reg".to_string(),
+            adder: "std_add".to_string(),
+        }
+    }
+}
+
+impl PrimitiveMap {
+    /// Read the mapping from the given file
+    pub fn load(path: &Path) -> FilamentResult<Self> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            Error::invalid_file(format!(
+                "Failed to read {}: {err}",
+                path.to_string_lossy()
+            ))
+        })?;
+        serde_json::from_str(&content).map_err(|err| {
+            Error::malformed(format!(
+                "Failed to parse primitive map {}: {err}",
+                path.to_string_lossy()
+            ))
+        })
+    }
+
+    /// Check that the primitives are defined in the library and have the
+    /// width parameter and the ports that compilation uses.
+    pub(super) fn validate(
+        &self,
+        lib: &calyx::LibrarySignatures,
+    ) -> FilamentResult<()> {
+        let uses = [
+            ("register", &self.register, ["in", "write_en", "out"]),
+            ("adder", &self.adder, ["left", "right", "out"]),
+        ];
+        for (construct, name, ports) in uses {
+            let Some(prim) = lib.find_primitive(name.as_str()) else {
+                return Err(Error::misc(format!(
+                    "Primitive `{name}' used for {construct}s is not defined"
+                )));
+            };
+            if prim.params.len() != 1 {
+                return Err(Error::misc(format!(
+                    "Primitive `{name}' used for {construct}s must have exactly one parameter for its width but has {}",
+                    prim.params.len()
+                )));
+            }
+            if let Some(port) = ports.iter().find(|port| {
+                !prim.signature.iter().any(|pd| pd.name().as_ref() == **port)
+            }) {
+                return Err(Error::misc(format!(
+                    "Primitive `{name}' used for {construct}s does not have the port `{port}'"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
@@ -19,14 +19,34 @@ pub(super) const INTERFACE_PORTS: [(AttrPair, (&str, u64, calyx::Direction));
     ),
 ];
 
+/// Component attributes that are passed on to Calyx when names are preserved.
+pub(super) const CALYX_COMP_ATTRS: [&str; 2] = ["inline", "opaque"];
+
 /// Helper struct that generates names for [crate::utils::Idx]s given their [Ctx].
 pub(super) struct NameGenerator {
     use_info: bool,
+    /// Prefix added to all generated instances and modules
+    prefix: String,
 }
 
 impl NameGenerator {
-    pub fn new(use_info: bool) -> Self {
-        Self { use_info }
+    pub fn new(use_info: bool, prefix: String) -> Self {
+        Self { use_info, prefix }
+    }
+
+    /// Whether the names of the source program are preserved
+    pub fn preserve_names(&self) -> bool {
+        self.use_info
+    }
+
+    /// The prefix added to generated names
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Add the prefix to a generated name
+    pub fn prefixed(&self, name: impl std::fmt::Display) -> String {
+        format!("{}{name}", self.prefix)
     }
 
     /// Helper function to generate the name of an [crate::utils::Idx] using its info if the debug flag is enabled.
@@ -106,17 +126,27 @@ impl NameGenerator {
         idx: InstIdx,
         ctx: &C,
     ) -> String {
-        self.info_name(ctx.get(idx).info, ctx)
-            .unwrap_or_else(|| format!("inst{}", idx.get()))
+        let name = self
+            .info_name(ctx.get(idx).info, ctx)
+            .unwrap_or_else(|| format!("inst{}", idx.get()));
+        self.prefixed(name)
     }
 
-    /// Returns the name of a [Component]
+    /// Returns the name of a [Component].
+    /// External and generated components keep their names since they are
+    /// defined outside of Filament.
     pub fn comp_name(&self, idx: CompIdx, ctx: &impl Ctx<Component>) -> String {
-        ctx.get(idx)
+        let comp = ctx.get(idx);
+        let name = comp
             .src_info
             .as_ref()
             .map(|src| src.name.to_string())
-            .unwrap_or_else(|| format!("comp{}", idx.get()))
+            .unwrap_or_else(|| format!("comp{}", idx.get()));
+        if comp.is_ext() || comp.is_gen() {
+            name
+        } else {
+            self.prefixed(name)
+        }
     }
 }
 
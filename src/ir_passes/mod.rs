@@ -2,24 +2,60 @@ mod assignment_check;
 mod assume;
 mod build_domination;
 mod bundle_elim;
+mod callgraph;
+mod comb_loop_check;
+mod constraints;
+mod dead_instances;
+mod dedup;
 mod discharge;
 mod dump_interface;
+mod emit_dot;
+mod emit_json;
+mod emit_sva;
+mod explain_interval;
+mod fold_check;
 mod interval_check;
 mod lower;
 mod mono;
+mod overlap;
+mod pass_through_lint;
 mod phantom_check;
+mod phantom_lint;
+mod port_timing;
 mod prop_simplify;
+mod timing_hints;
 mod type_check;
+mod undriven_outputs;
+mod unused_events;
 
 pub use assignment_check::AssignCheck;
 pub use assume::Assume;
 pub use build_domination::BuildDomination;
 pub use bundle_elim::BundleElim;
+pub use callgraph::CallGraph;
+pub use comb_loop_check::CombLoopCheck;
+pub use constraints::{Constraint, Constraints, Span};
+pub use dead_instances::DeadInstances;
+pub use dedup::Dedup;
 pub use discharge::Discharge;
-pub use dump_interface::DumpInterface;
+pub use dump_interface::{
+    DumpInterface, EventInterface, InterfaceDescription, OutputInterface,
+    PortInterface,
+};
+pub use emit_dot::EmitDot;
+pub use emit_json::EmitJson;
+pub use emit_sva::EmitSva;
+pub use explain_interval::ExplainInterval;
+pub use fold_check::FoldCheck;
 pub use interval_check::IntervalCheck;
-pub use lower::Compile;
-pub use mono::Monomorphize;
+pub use lower::{Compile, FsmEncoding, PrimitiveMap};
+pub use mono::{CompileLog, Monomorphize};
+pub use overlap::Overlap;
+pub use pass_through_lint::PassThroughLint;
 pub use phantom_check::PhantomCheck;
+pub use phantom_lint::PhantomLint;
 pub use prop_simplify::Simplify;
+pub use timing_hints::TimingHints;
 pub use type_check::TypeCheck;
+pub use undriven_outputs::UndrivenOutputs;
+pub use unused_events::UnusedEvents;
@@ -0,0 +1,65 @@
+use fil_ir as ir;
+use fil_utils::{Error, FilamentResult};
+use std::{fs, path::Path, time::Duration};
+
+#[derive(Default)]
+/// Records the components generated by [super::Monomorphize] in dependency
+/// order, i.e., every component appears after the components it instantiates.
+pub struct CompileLog {
+    /// Name of the source component, the number of instantiations generated
+    /// for it, and the time spent monomorphizing them
+    comps: Vec<(String, u64, Duration)>,
+    /// The component generated for each specialization of a source
+    /// component, named by the values bound to its parameters
+    specializations: Vec<(String, ir::CompIdx)>,
+}
+
+impl CompileLog {
+    /// Record that an instantiation of the component `name` was generated.
+    /// `time` excludes the time spent generating the components it uses.
+    pub(super) fn add(&mut self, name: String, time: Duration) {
+        if let Some((_, count, total)) =
+            self.comps.iter_mut().find(|(n, _, _)| *n == name)
+        {
+            *count += 1;
+            *total += time;
+        } else {
+            self.comps.push((name, 1, time));
+        }
+    }
+
+    /// Record that `idx` is the specialization `name` of a source component
+    pub(super) fn specialize(&mut self, name: String, idx: ir::CompIdx) {
+        self.specializations.push((name, idx));
+    }
+
+    /// The specializations of source components in dependency order
+    pub fn specializations(
+        &self,
+    ) -> impl Iterator<Item = (&str, ir::CompIdx)> + '_ {
+        self.specializations
+            .iter()
+            .map(|(name, idx)| (name.as_str(), *idx))
+    }
+
+    /// Write the log to the given file
+    pub fn save(&self, path: &Path) -> FilamentResult<()> {
+        let content = self
+            .comps
+            .iter()
+            .map(|(name, count, time)| {
+                format!(
+                    "{name}: {count} instantiation{} in {}ms\n",
+                    if *count == 1 { "" } else { "s" },
+                    time.as_millis()
+                )
+            })
+            .collect::<String>();
+        fs::write(path, content).map_err(|err| {
+            Error::write_error(format!(
+                "Failed to write {}: {err}",
+                path.to_string_lossy()
+            ))
+        })
+    }
+}
@@ -2,6 +2,7 @@ use super::{
     Base, CompKey, IntoUdl, MonoSig, Monomorphize, Underlying, UnderlyingComp,
 };
 use fil_ir::{self as ir, AddCtx, Ctx};
+use fil_utils::GPosIdx;
 use ir::DisplayCtx;
 use itertools::Itertools;
 
@@ -97,6 +98,8 @@ impl MonoDeferred<'_, '_> {
 
         let unannotated_ports = ul.unannotated_ports().clone();
         monosig.base.set_unannotated_ports(unannotated_ports);
+        monosig.base.set_attrs(ul.attrs().clone());
+        monosig.base.set_name(ul.name());
 
         // Mark the signature monormophization as incomplete
         self.sig_mono_complete = false;
@@ -111,9 +114,13 @@ impl MonoDeferred<'_, '_> {
         // components but externally generated components might violate their
         // requirements.
         for prop in self.underlying.all_exist_assumes() {
-            let out = self.prop(prop.ul());
-            let Some(v) = out.get().as_concrete(self.monosig.base.comp())
-            else {
+            let out = self.prop(prop.ul()).get();
+            let base_comp = self.monosig.base.comp();
+            if let Some(err) = out.fold_error(base_comp) {
+                self.pass.report_fold_error(err, GPosIdx::UNKNOWN);
+                continue;
+            }
+            let Some(v) = out.as_concrete(base_comp) else {
                 unreachable!(
                     "Failed to concretize proposition: {}",
                     self.underlying.display(prop.ul())
@@ -311,8 +318,9 @@ impl MonoDeferred<'_, '_> {
         let mono_start = self.monosig.expr(&self.underlying, start.ul()).get();
         let mono_end = self.monosig.expr(&self.underlying, end.ul()).get();
 
-        let mut i = mono_start.as_concrete(self.monosig.base.comp()).unwrap();
-        let bound = mono_end.as_concrete(self.monosig.base.comp()).unwrap();
+        let base_comp = self.monosig.base.comp();
+        let mut i = self.pass.concrete(base_comp, mono_start, GPosIdx::UNKNOWN);
+        let bound = self.pass.concrete(base_comp, mono_end, GPosIdx::UNKNOWN);
 
         while i < bound {
             let index = index.ul();
@@ -343,11 +351,16 @@ impl MonoDeferred<'_, '_> {
         let branch = match self.monosig.base.get(cond) {
             ir::Prop::True => then,
             ir::Prop::False => alt,
-            cond => self
-                .monosig
-                .base
-                .comp()
-                .internal_error(format!("Non-bool condition: {cond}")),
+            cond_prop => {
+                let base_comp = self.monosig.base.comp();
+                let Some(err) = cond.get().fold_error(base_comp) else {
+                    base_comp.internal_error(format!(
+                        "Non-bool condition: {cond_prop}"
+                    ))
+                };
+                self.pass.report_fold_error(err, GPosIdx::UNKNOWN);
+                return;
+            }
         };
 
         let body = branch
@@ -416,12 +429,11 @@ impl MonoDeferred<'_, '_> {
             ir::Command::Let(ir::Let { param, expr }) => {
                 let p = param.ul();
                 let e = self.monosig.expr(&self.underlying, expr.ul()).get();
-                let Some(v) = e.as_concrete(self.monosig.base.comp()) else {
-                    unreachable!(
-                        "let binding evaluated to: {}",
-                        self.monosig.base.comp().display(e)
-                    )
-                };
+                let v = self.pass.concrete(
+                    self.monosig.base.comp(),
+                    e,
+                    GPosIdx::UNKNOWN,
+                );
                 self.monosig.binding.push(p, v);
                 None
             }
@@ -437,13 +449,11 @@ impl MonoDeferred<'_, '_> {
             ir::Command::Exists(ir::Exists { param, expr }) => {
                 let comp_key = self.comp_key();
                 let e = self.monosig.expr(&self.underlying, expr.ul()).get();
-                let base_comp = self.monosig.base.comp();
-                let Some(v) = e.as_concrete(base_comp) else {
-                    unreachable!(
-                        "exists binding evaluated to: {}",
-                        base_comp.display(e)
-                    )
-                };
+                let v = self.pass.concrete(
+                    self.monosig.base.comp(),
+                    e,
+                    GPosIdx::UNKNOWN,
+                );
 
                 self.pass
                     .inst_info_mut(comp_key)
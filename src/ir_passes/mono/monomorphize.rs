@@ -1,12 +1,13 @@
 use super::{
-    Base, CompKey, InstanceInfo, IntoBase, IntoUdl, MonoDeferred, MonoSig,
-    Underlying, UnderlyingComp,
+    Base, CompKey, CompileLog, InstanceInfo, IntoBase, IntoUdl, MonoDeferred,
+    MonoSig, Underlying, UnderlyingComp,
 };
 use fil_gen as gen;
-use fil_ir::{self as ir, Ctx, IndexStore};
+use fil_ir::{self as ir, Ctx, DisplayCtx, IndexStore};
+use fil_utils::{Diagnostics, Error, Failure, GPosIdx};
 use ir::AddCtx;
 use itertools::Itertools;
-use std::collections::HashMap;
+use std::{collections::HashMap, mem, time::Duration};
 
 /// The Monomorphize pass.
 ///
@@ -69,12 +70,22 @@ pub struct Monomorphize<'a> {
     pub ext_map: HashMap<String, Vec<ir::CompIdx>>,
     /// Generator executor
     gen_exec: &'a mut Option<gen::GenExec>,
+    /// Components generated so far in dependency order
+    log: CompileLog,
+    /// Time spent monomorphizing the components used by the component
+    /// currently being monomorphized
+    nested: Duration,
+    /// Report the component generated for each instantiation
+    trace: bool,
+    /// Errors in the arithmetic of the instantiated components
+    diag: Diagnostics,
 }
 
 impl<'a> Monomorphize<'a> {
     fn new(
         old: &'a ir::Context,
         gen_exec: &'a mut Option<gen::GenExec>,
+        trace: bool,
     ) -> Self {
         Monomorphize {
             ctx: ir::Context::default(),
@@ -84,6 +95,10 @@ impl<'a> Monomorphize<'a> {
             inst_info: HashMap::new(),
             ext_map: HashMap::new(),
             gen_exec,
+            log: CompileLog::default(),
+            nested: Duration::ZERO,
+            trace,
+            diag: Diagnostics::default(),
         }
     }
 }
@@ -97,6 +112,35 @@ impl<'ctx> Monomorphize<'ctx> {
         })
     }
 
+    /// The value of an expression of `comp` that must be concrete once the
+    /// parameters are bound. Arithmetic that cannot be folded is reported at
+    /// `loc` and evaluates to `0` so that the rest of the program is still
+    /// checked.
+    pub fn concrete(
+        &mut self,
+        comp: &ir::Component,
+        e: ir::ExprIdx,
+        loc: GPosIdx,
+    ) -> u64 {
+        if let Some(v) = e.as_concrete(comp) {
+            return v;
+        }
+        let Some(err) = e.fold_error(comp) else {
+            comp.internal_error(format!(
+                "{} is not a concrete number",
+                comp.display(e)
+            ))
+        };
+        self.report_fold_error(err, loc);
+        0
+    }
+
+    /// Report an error from folding an expression at `loc`
+    pub fn report_fold_error(&mut self, err: Error, loc: GPosIdx) {
+        let info = self.diag.add_info("expression cannot be evaluated", loc);
+        self.diag.add_error(err.add_note(info));
+    }
+
     /// Returns a mutable reference to the instance info for a component or a
     /// default value if it does not exist.
     pub fn inst_info_mut(&mut self, comp_key: CompKey) -> &mut InstanceInfo {
@@ -237,9 +281,73 @@ impl<'ctx> Monomorphize<'ctx> {
 
         // If we've already processed this, return the component
         if let Some(&name) = self.processed.get(&n_ck) {
+            self.trace_step(&n_ck, name, true);
             return name;
         }
 
+        // Record the time spent on this component without the time spent
+        // on the components it uses
+        let outer = mem::take(&mut self.nested);
+        let (idx, time) =
+            crate::time!(self.process(comp, params, n_ck.clone()));
+        let name = underlying
+            .source_name()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| format!("comp{}", comp.idx().get()));
+        self.log.add(name, time.saturating_sub(self.nested));
+        if !underlying.is_ext() && !underlying.is_gen() {
+            let spec = self.specialized_name(&n_ck);
+            self.log.specialize(spec, idx.get());
+        }
+        self.nested = outer + time;
+        self.trace_step(&n_ck, idx, false);
+        idx
+    }
+
+    /// Report the bindings used to generate `idx` if tracing is enabled.
+    /// Externals are not specialized and are not reported.
+    fn trace_step(&self, ck: &CompKey, idx: Base<ir::Component>, reused: bool) {
+        let underlying = self.old.get(ck.comp.idx());
+        if !self.trace || (underlying.is_ext() && !underlying.is_gen()) {
+            return;
+        }
+        eprintln!(
+            "monomorphize: {} -> {}{}",
+            self.specialized_name(ck),
+            self.ctx.display(idx.get()),
+            if reused { " (reused)" } else { "" }
+        );
+    }
+
+    /// The name of the source component followed by the values bound to its
+    /// parameters, e.g., `Add[W=32]`
+    fn specialized_name(&self, ck: &CompKey) -> String {
+        let underlying = self.old.get(ck.comp.idx());
+        let name = underlying
+            .source_name()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| format!("comp{}", ck.comp.idx().get()));
+        let binds = underlying
+            .param_args()
+            .iter()
+            .zip(&ck.params)
+            .map(|(p, v)| format!("{}={v}", underlying.display(*p)))
+            .join(", ");
+        if binds.is_empty() {
+            name
+        } else {
+            format!("{name}[{binds}]")
+        }
+    }
+
+    /// Generate the component for a key that has not been processed yet
+    fn process(
+        &mut self,
+        comp: Underlying<ir::Component>,
+        params: Vec<u64>,
+        n_ck: CompKey,
+    ) -> Base<ir::Component> {
+        let underlying = self.old.get(comp.idx());
         if underlying.is_gen() {
             return self.gen(comp, params, n_ck);
         }
@@ -273,28 +381,38 @@ impl<'ctx> Monomorphize<'ctx> {
 impl Monomorphize<'_> {
     /// Monomorphize the context by tracing starting from the top-level component.
     /// Returns an empty context if there is no top-level component.
+    /// The components generated are recorded in `compile_log`. If `trace` is
+    /// set, each instantiation is reported with the component it uses.
+    /// Fails if a parameter of an instantiation evaluates to a value that
+    /// cannot be computed, like a division by zero.
     pub fn transform(
         ctx: &ir::Context,
         gen: &mut Option<gen::GenExec>,
-    ) -> ir::Context {
+        compile_log: &mut CompileLog,
+        trace: bool,
+    ) -> Result<ir::Context, Failure> {
         let Some(entrypoint) = ctx.entrypoint else {
             log::warn!("Program has no entrypoint. Result will be empty.");
-            return ir::Context {
+            return Ok(ir::Context {
                 comps: IndexStore::default(),
                 entrypoint: None,
                 externals: HashMap::new(),
-            };
+            });
         };
         let entrypoint = entrypoint.ul();
         // Monomorphize the entrypoint
-        let mut mono = Monomorphize::new(ctx, gen);
+        let mut mono = Monomorphize::new(ctx, gen, trace);
         let ck = CompKey::new(entrypoint, vec![]);
         mono.monomorphize(ck.clone());
+        if let Some(errors) = mono.diag.report_all() {
+            return Err(errors);
+        }
 
         let new_entrypoint = mono.processed.get(&ck).unwrap();
         mono.ctx.entrypoint = Some(new_entrypoint.get());
         mono.ctx.externals = mono.ext_map;
         ir::Validate::context(&mono.ctx);
-        mono.ctx
+        *compile_log = mono.log;
+        Ok(mono.ctx)
     }
 }
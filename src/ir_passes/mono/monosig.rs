@@ -6,6 +6,7 @@ use fil_ir::{
     self as ir, AddCtx, Ctx, DenseIndexInfo, DisplayCtx, Foreign, MutCtx,
     SparseInfoMap,
 };
+use fil_utils::GPosIdx;
 use itertools::Itertools;
 use std::collections::HashMap;
 
@@ -56,7 +57,8 @@ impl MonoSig {
                 .zip(params)
                 .collect_vec(),
         );
-        let comp = ir::Component::new(typ);
+        let mut comp = ir::Component::new(typ);
+        comp.time_overflow = underlying.time_overflow;
 
         Self {
             base: BaseComp::new(comp),
@@ -117,7 +119,7 @@ impl MonoSig {
         let comp_k = if pass.old.is_ext(*comp) {
             CompKey::new(comp.ul(), vec![])
         } else {
-            self.comp_key(underlying, inst_ul)
+            self.comp_key(underlying, pass, inst_ul)
         };
 
         let Some(&comp) = pass.processed.get(&comp_k) else {
@@ -200,12 +202,14 @@ impl MonoSig {
             | ir::info::Reason::EventConstraint { .. }
             | ir::info::Reason::BundleLenMatch { .. }
             | ir::info::Reason::BundleWidthMatch { .. }
+            | ir::info::Reason::InvokeArgWidth { .. }
             | ir::info::Reason::InBoundsAccess { .. }
             | ir::info::Reason::BundleDelay { .. }
             | ir::info::Reason::WellFormedInterval { .. }
             | ir::info::Reason::EventTrig { .. }
             | ir::info::Reason::EventLive { .. }
             | ir::info::Reason::EventLiveDelay { .. }
+            | ir::info::Reason::SharedOverlap { .. }
             | ir::info::Reason::Misc { .. }
             | ir::info::Reason::Generated { .. } => {
                 ir::info::Reason::generated(
@@ -579,17 +583,22 @@ impl MonoSig {
     fn comp_key(
         &mut self,
         underlying: &UnderlyingComp,
+        pass: &mut Monomorphize,
         iidx: Underlying<ir::Instance>,
     ) -> CompKey {
-        let ir::Instance { comp, args, .. } = underlying.get(iidx);
+        let ir::Instance {
+            comp, args, info, ..
+        } = underlying.get(iidx);
+        let loc = underlying
+            .get(info.ul())
+            .as_instance()
+            .map_or(GPosIdx::UNKNOWN, |inst| inst.bind_loc);
 
         let conc_params = args
             .iter()
             .map(|p| {
-                self.expr(underlying, p.ul())
-                    .get()
-                    .as_concrete(self.base.comp())
-                    .unwrap()
+                let e = self.expr(underlying, p.ul()).get();
+                pass.concrete(self.base.comp(), e, loc)
             })
             .collect_vec();
         CompKey::new(comp.ul(), conc_params)
@@ -628,7 +637,7 @@ impl MonoSig {
         } = underlying.get(inst);
 
         // Monomorphize the component
-        let ck = self.comp_key(underlying, inst);
+        let ck = self.comp_key(underlying, pass, inst);
         let mono_comp = pass.monomorphize(ck.clone());
 
         // Binding for parameters defined by this instance
@@ -31,6 +31,12 @@ impl<'a> UnderlyingComp<'a> {
     pub fn unannotated_ports(&self) -> &Vec<(ast::Id, u64)> {
         &self.0.unannotated_ports
     }
+    pub fn attrs(&self) -> &ast::Attributes {
+        &self.0.attrs
+    }
+    pub fn name(&self) -> Option<ast::Id> {
+        self.0.name
+    }
     pub fn exist_params(&self) -> impl Iterator<Item = ir::ParamIdx> + '_ {
         self.0.exist_params()
     }
@@ -91,6 +97,12 @@ impl BaseComp {
     pub fn set_unannotated_ports(&mut self, other: Vec<(ast::Id, u64)>) {
         self.0.unannotated_ports = Box::new(other);
     }
+    pub fn set_attrs(&mut self, other: ast::Attributes) {
+        self.0.attrs = other;
+    }
+    pub fn set_name(&mut self, other: Option<ast::Id>) {
+        self.0.name = other;
+    }
     pub fn set_src_info(&mut self, other: Option<InterfaceSrc>) {
         self.0.src_info = other;
     }
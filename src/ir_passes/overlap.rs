@@ -0,0 +1,75 @@
+use super::{Discharge, ExplainInterval};
+use crate::cmdline;
+use fil_ir::{self as ir, Ctx, DisplayCtx};
+use fil_utils::{Error, FilamentResult};
+
+/// Decides whether the availability intervals of two ports overlap which
+/// indicates that the ports may be used at the same time.
+/// Ports are named in the same way as [ExplainInterval] and must belong to the
+/// same component because the events of different components are unrelated.
+/// The intervals are compared using the solver under the facts that the
+/// component assumes.
+pub struct Overlap;
+
+impl Overlap {
+    /// Print whether the ports named by `a` and `b` overlap and the
+    /// overlapping interval if they do.
+    pub fn print(
+        opts: &cmdline::Opts,
+        ctx: &ir::Context,
+        a: &str,
+        b: &str,
+    ) -> FilamentResult<()> {
+        let (ca, pa) = ExplainInterval::find_port(ctx, a)?;
+        let (cb, pb) = ExplainInterval::find_port(ctx, b)?;
+        if ca != cb {
+            return Err(Error::misc(format!(
+                "Cannot compare `{a}` and `{b}` because they belong to different components"
+            )));
+        }
+        Discharge::find_solver(opts)?;
+
+        // The queries add propositions to the component
+        let mut ctx = ctx.clone();
+        let comp = ctx.get_mut(ca);
+        let ra = comp.get(pa).live.range.clone();
+        let rb = comp.get(pb).live.range.clone();
+        let overlap = {
+            let l = ra.start.lt(rb.end, comp);
+            let r = rb.start.lt(ra.end, comp);
+            l.and(r, comp)
+        };
+        let queries = [
+            overlap,
+            overlap.not(comp),
+            ra.start.gte(rb.start, comp),
+            rb.start.gte(ra.start, comp),
+            ra.end.lte(rb.end, comp),
+            rb.end.lte(ra.end, comp),
+        ];
+        let holds = Discharge::decide(opts, &mut ctx, ca, &queries);
+
+        let comp = ctx.get(ca);
+        println!("{a}: {}", comp.display(&ra));
+        println!("{b}: {}", comp.display(&rb));
+        let bound = |f: &str, a: ir::TimeIdx, b: ir::TimeIdx, i: usize| {
+            if holds[i] {
+                comp.display(a)
+            } else if holds[i + 1] {
+                comp.display(b)
+            } else {
+                format!("{f}({}, {})", comp.display(a), comp.display(b))
+            }
+        };
+        if holds[0] {
+            let start = bound("max", ra.start, rb.start, 2);
+            let end = bound("min", ra.end, rb.end, 4);
+            println!("yes: [{start}, {end}]");
+        } else if holds[1] {
+            println!("no");
+        } else {
+            println!("maybe: the intervals overlap for some values of the parameters and events");
+        }
+        Ok(())
+    }
+}
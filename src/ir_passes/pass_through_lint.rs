@@ -0,0 +1,144 @@
+use crate::{
+    cmdline,
+    ir_visitor::{Action, Construct, Visitor, VisitorData},
+};
+use fil_ir::{self as ir, Ctx};
+use fil_utils::{Failure, GPosIdx, Warning};
+use std::collections::HashMap;
+
+/// Warns about invocations whose outputs are reconnected without change.
+/// An output port is a pass-through if the component directly forwards an
+/// input port with the same liveness to it. Components that shift the timing
+/// of their inputs, for example by registering them, are never flagged.
+#[derive(Default)]
+pub struct PassThroughLint {
+    /// Maps the pass-through outputs of each component to the input they forward.
+    forwards: HashMap<ir::CompIdx, HashMap<ir::PortIdx, ir::PortIdx>>,
+    /// Number of warnings added by the pass
+    warnings: u64,
+    /// Fail compilation if there are any warnings
+    deny_warnings: bool,
+}
+
+impl Construct for PassThroughLint {
+    fn from(opts: &cmdline::Opts, _: &mut ir::Context) -> Self {
+        Self {
+            deny_warnings: opts.deny_warnings,
+            ..Default::default()
+        }
+    }
+
+    fn clear_data(&mut self) {
+        /* Forwarding information and warnings are shared */
+    }
+}
+
+impl PassThroughLint {
+    /// Compute the outputs of `comp` that directly forward an input.
+    fn forwards(comp: &ir::Component) -> HashMap<ir::PortIdx, ir::PortIdx> {
+        // External and generated components are black boxes
+        if comp.is_ext() || comp.is_gen() {
+            return HashMap::new();
+        }
+        comp.cmds
+            .iter()
+            .filter_map(|cmd| {
+                let ir::Command::Connect(ir::Connect { src, dst, .. }) = cmd
+                else {
+                    return None;
+                };
+                let (src_port, dst_port) =
+                    (comp.get(src.port), comp.get(dst.port));
+                if src_port.is_sig_in()
+                    && dst_port.is_sig_out()
+                    && src.is_port(comp)
+                    && dst.is_port(comp)
+                    && src_port.live.range == dst_port.live.range
+                {
+                    Some((dst.port, src.port))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Name of a port for error messages
+    fn port_name(comp: &ir::Component, port: ir::PortIdx) -> String {
+        comp.get(comp.get(port).info)
+            .as_port()
+            .map(|p| p.name.to_string())
+            .unwrap_or_else(|| "_".to_string())
+    }
+}
+
+impl Visitor for PassThroughLint {
+    fn name() -> &'static str {
+        "pass-through-lint"
+    }
+
+    fn start(&mut self, data: &mut VisitorData) -> Action {
+        if data.comp.is_ext() || data.comp.is_gen() {
+            return Action::Stop;
+        }
+        Action::Continue
+    }
+
+    fn connect(
+        &mut self,
+        con: &mut ir::Connect,
+        data: &mut VisitorData,
+    ) -> Action {
+        let comp = &data.comp;
+        let ir::PortOwner::Inv { inv, base, .. } =
+            &comp.get(con.src.port).owner
+        else {
+            return Action::Continue;
+        };
+        if !con.src.is_port(comp) {
+            return Action::Continue;
+        }
+
+        let callee_idx = base.owner();
+        let ctx = data.ctx();
+        let forwards = self
+            .forwards
+            .entry(callee_idx)
+            .or_insert_with(|| Self::forwards(ctx.get(callee_idx)));
+        let Some(&input) = forwards.get(&base.key()) else {
+            return Action::Continue;
+        };
+
+        let callee = ctx.get(callee_idx);
+        let inv_info = comp.get(comp.get(*inv).info).as_invoke();
+        let inv_name = inv_info
+            .map(|info| info.name.to_string())
+            .unwrap_or_else(|| "_".to_string());
+        let inv_loc = inv_info.map(|i| i.bind_loc).unwrap_or(GPosIdx::UNKNOWN);
+        let con_loc = comp
+            .get(con.info)
+            .as_connect()
+            .map(|c| c.src_loc)
+            .unwrap_or(GPosIdx::UNKNOWN);
+
+        let out = Self::port_name(callee, base.key());
+        let input = Self::port_name(callee, input);
+        Warning::new(format!(
+            "`{inv_name}.{out}` is a pass-through of `{inv_name}.{input}`"
+        ))
+        .with_label(inv_loc, "invocation only forwards its input")
+        .with_label(con_loc, "output is reconnected unchanged")
+        .with_note(format!(
+            "connect the value provided to `{inv_name}.{input}` directly instead"
+        ))
+        .add();
+        self.warnings += 1;
+        Action::Continue
+    }
+
+    fn after_traversal(&mut self) -> Option<Failure> {
+        // Lints only fail compilation when warnings are denied
+        (self.deny_warnings && self.warnings > 0)
+            .then_some(self.warnings.into())
+    }
+}
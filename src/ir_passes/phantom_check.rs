@@ -3,7 +3,7 @@ use crate::{
     ir_visitor::{Action, Construct, Visitor, VisitorData},
 };
 use fil_ir::{self as ir, Ctx, DisplayCtx};
-use fil_utils::{Diagnostics, Error, GPosIdx};
+use fil_utils::{Diagnostics, Error, Failure, GPosIdx};
 use itertools::Itertools;
 
 /// Checks if a user-level phantom events are valid.
@@ -224,7 +224,7 @@ impl Visitor for PhantomCheck {
         Action::Continue
     }
 
-    fn after_traversal(&mut self) -> Option<u64> {
+    fn after_traversal(&mut self) -> Option<Failure> {
         self.diag.report_all()
     }
 }
@@ -0,0 +1,118 @@
+use crate::{
+    cmdline,
+    ir_visitor::{Action, Construct, Visitor, VisitorData},
+};
+use fil_ir::{self as ir, Ctx};
+use fil_utils::{Failure, Warning};
+use std::collections::HashSet;
+
+/// Warns about phantom events that are not mentioned by any constraint in the
+/// signature of a component. Phantom events do not have an interface port so
+/// their constraints are the only guarantee about when the component can be
+/// used, and a phantom event without any usually means that a timing
+/// guarantee is missing. Components with a single event are not reported
+/// because constraints relate two events.
+#[derive(Default)]
+pub struct PhantomLint {
+    /// Number of warnings added by the pass
+    warnings: u64,
+    /// Fail compilation if there are any warnings
+    deny_warnings: bool,
+}
+
+impl PhantomLint {
+    /// Add the events mentioned by the time `t`
+    fn time_events(
+        comp: &ir::Component,
+        t: ir::TimeIdx,
+        acc: &mut HashSet<ir::EventIdx>,
+    ) {
+        acc.insert(comp.get(t).event);
+    }
+
+    /// Add the events mentioned by the proposition `prop`
+    fn prop_events(
+        comp: &ir::Component,
+        prop: ir::PropIdx,
+        acc: &mut HashSet<ir::EventIdx>,
+    ) {
+        match comp.get(prop) {
+            ir::Prop::True | ir::Prop::False | ir::Prop::Cmp(_) => (),
+            ir::Prop::TimeCmp(cmp) => {
+                Self::time_events(comp, cmp.lhs, acc);
+                Self::time_events(comp, cmp.rhs, acc);
+            }
+            ir::Prop::TimeSubCmp(cmp) => {
+                for ts in [&cmp.lhs, &cmp.rhs] {
+                    if let ir::TimeSub::Sym { l, r } = ts {
+                        Self::time_events(comp, *l, acc);
+                        Self::time_events(comp, *r, acc);
+                    }
+                }
+            }
+            ir::Prop::Not(p) => Self::prop_events(comp, *p, acc),
+            ir::Prop::And(l, r)
+            | ir::Prop::Or(l, r)
+            | ir::Prop::Implies(l, r) => {
+                Self::prop_events(comp, *l, acc);
+                Self::prop_events(comp, *r, acc);
+            }
+        }
+    }
+}
+
+impl Construct for PhantomLint {
+    fn from(opts: &cmdline::Opts, _: &mut ir::Context) -> Self {
+        Self {
+            deny_warnings: opts.deny_warnings,
+            ..Default::default()
+        }
+    }
+
+    fn clear_data(&mut self) {
+        /* Warnings are shared */
+    }
+}
+
+impl Visitor for PhantomLint {
+    fn name() -> &'static str {
+        "phantom-lint"
+    }
+
+    fn start(&mut self, data: &mut VisitorData) -> Action {
+        let comp = &data.comp;
+        if comp.is_ext() || comp.is_gen() || comp.events().len() < 2 {
+            return Action::Stop;
+        }
+        let mut constrained = HashSet::new();
+        for prop in comp.get_event_asserts() {
+            Self::prop_events(comp, *prop, &mut constrained);
+        }
+
+        for ev in comp.phantom_events() {
+            if constrained.contains(&ev) {
+                continue;
+            }
+            let Some(info) = comp.get(comp.get(ev).info).as_event() else {
+                continue;
+            };
+            Warning::new(format!(
+                "phantom event `{}` is not constrained",
+                info.name
+            ))
+            .with_label(info.bind_loc, "no constraint mentions this event")
+            .with_note(
+                "add a constraint on the event to the signature, like `where 'L > 'G`, to state when the component can be used",
+            )
+            .add();
+            self.warnings += 1;
+        }
+        Action::Stop
+    }
+
+    fn after_traversal(&mut self) -> Option<Failure> {
+        // Lints only fail compilation when warnings are denied
+        (self.deny_warnings && self.warnings > 0)
+            .then_some(self.warnings.into())
+    }
+}
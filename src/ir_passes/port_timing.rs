@@ -0,0 +1,97 @@
+use fil_ir::{self as ir, Ctx, DisplayCtx};
+use fil_utils::{Error, FilamentResult, GPosIdx, Warning};
+
+/// The availability interval of a port of the top-level component as a
+/// number of cycles after the interface port of one event is asserted.
+pub struct PortTiming {
+    /// Name of the port in the source program
+    pub name: String,
+    pub width: u64,
+    pub is_input: bool,
+    /// Interface port of the event. `None` if the event is a phantom event.
+    pub go: Option<String>,
+    /// First cycle in which the port is available
+    pub start: u64,
+    /// Cycle after the last cycle in which the port is available
+    pub end: u64,
+    /// The interval as written in the program
+    pub range: String,
+}
+
+impl PortTiming {
+    /// The top-level component, its source information, and the timing of its
+    /// ports.
+    /// Ports whose interval mentions two events or whose width or offsets are
+    /// not concrete cannot be described this way and are left out of `output`
+    /// with a warning.
+    pub fn main(
+        ctx: &ir::Context,
+        output: &str,
+    ) -> FilamentResult<(&ir::Component, &ir::InterfaceSrc, Vec<PortTiming>)>
+    {
+        let Some(entrypoint) = ctx.entrypoint else {
+            return Err(Error::misc(format!(
+                "cannot generate {output}: the program has no top-level component"
+            )));
+        };
+        let main = ctx.get(entrypoint);
+        let Some(src_info) = &main.src_info else {
+            return Err(Error::internal(
+                "top-level component has no source information",
+                GPosIdx::UNKNOWN,
+            ));
+        };
+
+        let inputs = main.inputs().map(|p| (p, true));
+        let outputs = main.outputs().map(|p| (p, false));
+        let mut ports = vec![];
+        for ((idx, p), is_input) in inputs.chain(outputs) {
+            let name = src_info.ports.get(idx).to_string();
+            if let Some(timing) =
+                Self::port(main, src_info, p, name.clone(), is_input)
+            {
+                ports.push(timing);
+                continue;
+            }
+            let loc = main
+                .get(p.info)
+                .as_port()
+                .map_or(GPosIdx::UNKNOWN, |p| p.live_loc);
+            Warning::new(format!("port `{name}' is left out of the {output}"))
+                .with_label(
+                    loc,
+                    "interval is not a fixed number of cycles after one event",
+                )
+                .add();
+        }
+        Ok((main, src_info, ports))
+    }
+
+    /// The timing of the port `p` of the top-level component `main`, if its
+    /// interval is a fixed number of cycles after one event
+    fn port(
+        main: &ir::Component,
+        src_info: &ir::InterfaceSrc,
+        p: &ir::Port,
+        name: String,
+        is_input: bool,
+    ) -> Option<PortTiming> {
+        let ir::Range { start, end } = &p.live.range;
+        let (start, end) = (main.get(*start), main.get(*end));
+        if start.event != end.event {
+            return None;
+        }
+        Some(PortTiming {
+            name,
+            width: p.width.as_concrete(main)?,
+            is_input,
+            go: src_info
+                .interface_ports
+                .find(start.event)
+                .map(|go| go.to_string()),
+            start: start.offset.as_concrete(main)?,
+            end: end.offset.as_concrete(main)?,
+            range: main.display(&p.live.range),
+        })
+    }
+}
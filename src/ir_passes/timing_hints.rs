@@ -0,0 +1,46 @@
+use super::port_timing::PortTiming;
+use fil_ir as ir;
+use fil_utils::FilamentResult;
+use itertools::Itertools;
+
+/// Generates timing hints for the ports of the main component that tools
+/// post-processing VCD traces can use to highlight the cycles in which a port
+/// carries valid data.
+/// Each line of the output has the form
+/// `<port> <direction> <interface port> <first cycle> <last cycle>` where the
+/// cycles are inclusive and counted from the cycle in which the interface port
+/// is asserted. Ports that use phantom events have `-` as their interface
+/// port because there is no signal to synchronize with. Ports whose interval
+/// is not a fixed number of cycles after one event are left out.
+pub struct TimingHints;
+
+impl TimingHints {
+    /// Generate the timing hints for the main component.
+    pub fn generate(ctx: &ir::Context) -> FilamentResult<String> {
+        let (_, src_info, ports) = PortTiming::main(ctx, "timing hints")?;
+
+        let hints = ports.into_iter().map(|port| {
+            let PortTiming {
+                name,
+                is_input,
+                go,
+                start,
+                end,
+                ..
+            } = port;
+            if end <= start {
+                return format!("# {name}: never valid");
+            }
+            let dir = if is_input { "input" } else { "output" };
+            let go = go.unwrap_or_else(|| "-".to_string());
+            // Availability intervals exclude their end
+            format!("{name} {dir} {go} {start} {}", end - 1)
+        });
+
+        Ok(format!(
+            "# Valid cycles of the ports of `{}`\n# port direction interface first last\n{}\n",
+            src_info.name,
+            hints.format("\n")
+        ))
+    }
+}
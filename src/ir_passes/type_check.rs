@@ -11,6 +11,7 @@ use itertools::Itertools;
 /// * Connections are between ports of same size
 /// * Connected ports have the same bitwidths
 /// * Add constraints on existentially quantified parameters
+/// * Ports are at most as wide as `--max-port-width`
 pub struct TypeCheck;
 
 impl TypeCheck {
@@ -66,6 +67,67 @@ impl TypeCheck {
             .flatten()
             .collect_vec()
     }
+
+    /// If `port` is an input of an invocation, returns the location of the
+    /// invocation and the index of the argument that provides the port.
+    fn invoke_arg(
+        port: ir::PortIdx,
+        comp: &ir::Component,
+    ) -> Option<(GPosIdx, usize)> {
+        let ir::PortOwner::Inv {
+            inv,
+            dir: ir::Direction::In,
+            ..
+        } = &comp.get(port).owner
+        else {
+            return None;
+        };
+        let invoke = comp.get(*inv);
+        let arg = invoke
+            .ports
+            .iter()
+            .filter(|p| comp.get(**p).is_inv_in())
+            .position(|p| *p == port)?;
+        let loc = comp
+            .get(invoke.info)
+            .as_invoke()
+            .map(|i| i.bind_loc)
+            .unwrap_or(GPosIdx::UNKNOWN);
+        Some((loc, arg))
+    }
+
+    /// Report ports whose width is known and larger than `max`. Ports of
+    /// invocations are reported at the invocation.
+    fn max_width(max: u64, comp: &mut ir::Component) -> Vec<ir::Command> {
+        let wide = comp
+            .ports()
+            .iter()
+            .filter_map(|(idx, p)| {
+                let width = p.width.as_concrete(comp)?;
+                let loc = match &p.owner {
+                    ir::PortOwner::Inv { inv, .. } => comp
+                        .get(comp.get(*inv).info)
+                        .as_invoke()
+                        .map(|i| i.bind_loc),
+                    _ => comp.get(p.info).as_port().map(|p| p.bind_loc),
+                };
+                (width > max)
+                    .then(|| (idx, width, loc.unwrap_or(GPosIdx::UNKNOWN)))
+            })
+            .collect_vec();
+
+        wide.into_iter()
+            .flat_map(|(idx, width, loc)| {
+                let msg = format!(
+                    "port `{}' has width {width} which exceeds the maximum port width of {max}",
+                    comp.display(idx)
+                );
+                let reason = comp.add(ir::info::Reason::misc(msg, loc).into());
+                let prop = comp.add(ir::Prop::False);
+                comp.assert(prop, reason)
+            })
+            .collect_vec()
+    }
 }
 
 impl Visitor for TypeCheck {
@@ -73,6 +135,15 @@ impl Visitor for TypeCheck {
         "type-check"
     }
 
+    fn start(&mut self, data: &mut VisitorData) -> Action {
+        match data.opts.max_port_width {
+            Some(max) => {
+                Action::AddBefore(Self::max_width(max, &mut data.comp))
+            }
+            None => Action::Continue,
+        }
+    }
+
     fn exists(&mut self, e: &mut ir::Exists, data: &mut VisitorData) -> Action {
         let ctx = &mut data.comp;
         // Ensure that the parameter is an existentially quantified parameter.
@@ -113,12 +184,17 @@ impl Visitor for TypeCheck {
         // Ensure that the bitwidths of the ports are the same
         let src_w = comp.get(src.port).width;
         let dst_w = comp.get(dst.port).width;
-        let reason = comp.add(
-            ir::info::Reason::bundle_width_match(
-                dst_loc, src_loc, dst_w, src_w,
+        // Arguments of invocations report the argument that is mismatched
+        let reason = if let Some((inv_loc, arg)) =
+            Self::invoke_arg(dst.port, comp)
+        {
+            ir::info::Reason::invoke_arg_width(
+                inv_loc, src_loc, dst_loc, arg, dst_w, src_w,
             )
-            .into(),
-        );
+        } else {
+            ir::info::Reason::bundle_width_match(dst_loc, src_loc, dst_w, src_w)
+        };
+        let reason = comp.add(reason.into());
         let prop = src_w.equal(dst_w, comp);
         cons.extend(comp.assert(prop, reason));
 
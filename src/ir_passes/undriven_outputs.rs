@@ -0,0 +1,106 @@
+use crate::{
+    cmdline,
+    ir_visitor::{Action, Construct, Visitor, VisitorData},
+};
+use fil_ir::{self as ir, Ctx};
+use fil_utils::{Diagnostics, Error, Failure, GPosIdx, Warning};
+use std::collections::HashSet;
+
+/// Reports output ports of components that are never assigned.
+/// With `--allow-unconnected-outputs`, the errors are downgraded to warnings
+/// and the backend drives the unconnected outputs with zero.
+/// Must occur after monomorphization. Components generated from the same
+/// definition share the locations of their ports so each port is only
+/// reported once.
+pub struct UndrivenOutputs {
+    /// Ports assigned in the current component
+    driven: HashSet<ir::PortIdx>,
+    /// Locations of the ports that have been reported
+    reported: HashSet<GPosIdx>,
+    /// Report warnings instead of errors
+    allow_unconnected: bool,
+    /// Fail compilation if there are any warnings
+    deny_warnings: bool,
+    /// Number of warnings added by the pass
+    warnings: u64,
+    diag: Diagnostics,
+}
+
+impl Construct for UndrivenOutputs {
+    fn from(opts: &cmdline::Opts, _: &mut ir::Context) -> Self {
+        Self {
+            driven: HashSet::new(),
+            reported: HashSet::new(),
+            allow_unconnected: opts.allow_unconnected_outputs,
+            deny_warnings: opts.deny_warnings,
+            warnings: 0,
+            diag: Diagnostics::default(),
+        }
+    }
+
+    fn clear_data(&mut self) {
+        self.driven = HashSet::new();
+    }
+}
+
+impl Visitor for UndrivenOutputs {
+    fn name() -> &'static str {
+        "undriven-outputs"
+    }
+
+    fn start(&mut self, data: &mut VisitorData) -> Action {
+        // External components are implemented elsewhere
+        if data.comp.is_ext() {
+            return Action::Stop;
+        }
+        Action::Continue
+    }
+
+    fn connect(
+        &mut self,
+        con: &mut ir::Connect,
+        _data: &mut VisitorData,
+    ) -> Action {
+        self.driven.insert(con.dst.port);
+        Action::Continue
+    }
+
+    fn end(&mut self, data: &mut VisitorData) {
+        let comp = &data.comp;
+        for (idx, port) in comp.outputs() {
+            if self.driven.contains(&idx) {
+                continue;
+            }
+            let (name, loc) = comp
+                .get(port.info)
+                .as_port()
+                .map(|p| (p.name.to_string(), p.bind_loc))
+                .unwrap_or_else(|| ("_".to_string(), GPosIdx::UNKNOWN));
+            if loc != GPosIdx::UNKNOWN && !self.reported.insert(loc) {
+                continue;
+            }
+
+            let msg = format!("output port `{name}' is never assigned");
+            if self.allow_unconnected {
+                Warning::new(msg).with_label(loc, "driven with zero").add();
+                self.warnings += 1;
+            } else {
+                let info = self.diag.add_info("never assigned", loc);
+                let hint = self.diag.add_message(
+                    "use --allow-unconnected-outputs to drive it with zero",
+                );
+                let err = Error::malformed(msg).add_note(info).add_note(hint);
+                self.diag.add_error(err);
+            }
+        }
+    }
+
+    fn after_traversal(&mut self) -> Option<Failure> {
+        let errors = self.diag.report_all();
+        if errors.is_none() && self.deny_warnings && self.warnings > 0 {
+            Some(self.warnings.into())
+        } else {
+            errors
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    cmdline,
+    ir_visitor::{Action, Construct, Visitor, VisitorData},
+};
+use fil_ir::{self as ir, Ctx};
+use fil_utils::{Failure, Warning};
+use std::collections::HashSet;
+
+/// Warns about events in the signature of a component that are never used by
+/// the interval of a port, a constraint, or an invocation. Events with an
+/// interface port are always used. Components that use none of their events,
+/// like components without ports, are not reported because they must still
+/// declare an event.
+#[derive(Default)]
+pub struct UnusedEvents {
+    /// Number of warnings added by the pass
+    warnings: u64,
+    /// Do not report unused events
+    allow: bool,
+    /// Fail compilation if there are any warnings
+    deny_warnings: bool,
+}
+
+impl Construct for UnusedEvents {
+    fn from(opts: &cmdline::Opts, _: &mut ir::Context) -> Self {
+        Self {
+            allow: opts.allow_unused_events,
+            deny_warnings: opts.deny_warnings,
+            ..Default::default()
+        }
+    }
+
+    fn clear_data(&mut self) {
+        /* Warnings are shared */
+    }
+}
+
+impl Visitor for UnusedEvents {
+    fn name() -> &'static str {
+        "unused-events"
+    }
+
+    fn start(&mut self, data: &mut VisitorData) -> Action {
+        let comp = &data.comp;
+        if self.allow || comp.is_ext() || comp.is_gen() {
+            return Action::Stop;
+        }
+        let used: HashSet<ir::EventIdx> = comp
+            .times()
+            .iter()
+            .map(|(_, t)| t.event)
+            .chain(
+                comp.events()
+                    .iter()
+                    .filter(|(_, ev)| ev.has_interface)
+                    .map(|(idx, _)| idx),
+            )
+            .collect();
+        if used.is_empty() {
+            return Action::Stop;
+        }
+
+        for (idx, ev) in comp.events().iter() {
+            if used.contains(&idx) {
+                continue;
+            }
+            let Some(info) = comp.get(ev.info).as_event() else {
+                continue;
+            };
+            Warning::new(format!("unused event `{}`", info.name))
+                .with_label(info.bind_loc, "event is never used")
+                .with_note(
+                    "use the event in the interval of a port or remove it",
+                )
+                .add();
+            self.warnings += 1;
+        }
+        Action::Stop
+    }
+
+    fn after_traversal(&mut self) -> Option<Failure> {
+        // Lints only fail compilation when warnings are denied
+        (self.deny_warnings && self.warnings > 0)
+            .then_some(self.warnings.into())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::cmdline;
 use fil_ir::{self as ir, MutCtx};
+use fil_utils::Failure;
 
 #[must_use]
 #[derive(PartialEq, Eq)]
@@ -107,7 +108,7 @@ where
     #[must_use]
     /// Executed after the visitor has visited all the components.
     /// If the return value is `Some`, the number is treated as an error code.
-    fn after_traversal(&mut self) -> Option<u64> {
+    fn after_traversal(&mut self) -> Option<Failure> {
         None
     }
 
@@ -300,7 +301,10 @@ where
     }
 
     /// Apply the pass to all components in the context
-    fn do_pass(opts: &cmdline::Opts, ctx: &mut ir::Context) -> Result<(), u64> {
+    fn do_pass(
+        opts: &cmdline::Opts,
+        ctx: &mut ir::Context,
+    ) -> Result<(), Failure> {
         let mut visitor = Self::from(opts, ctx);
         for idx in ctx.comps.idx_iter() {
             visitor.clear_data();
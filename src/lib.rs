@@ -1,7 +1,21 @@
+pub mod assumptions;
 pub mod cmdline;
+pub mod extern_stub;
+pub mod interface_diff;
 pub mod ir_passes;
 pub mod ir_visitor;
+pub mod manifest;
+pub mod only_tagged;
+pub mod pipeline;
+pub mod plan;
 pub mod resolver;
+pub mod resource_estimate;
+pub mod timing;
+pub mod unused_externs;
+pub mod watch;
 pub(crate) mod utils;
 
 mod macros;
+
+mod compile;
+pub use compile::compile;
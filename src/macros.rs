@@ -16,10 +16,13 @@ macro_rules! log_time {
         log::info!("{}: {}ms", stringify!($e), t.as_millis());
         r
     }};
-    // Variant to log the time with a custom message.
+    // Variant to log the time with a custom message. The time is also
+    // recorded for the timing report.
     ($e:expr, $msg:expr) => {{
         let (r, t) = $crate::time!($e);
-        log::info!("{}: {}ms", $msg, t.as_millis());
+        let msg = $msg;
+        log::info!("{}: {}ms", msg, t.as_millis());
+        $crate::timing::Timings::record(msg, t);
         r
     }};
     // Variant to log the time with a custom message only when a bound is reached
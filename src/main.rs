@@ -1,101 +1,373 @@
 use calyx_backend::Backend;
 use calyx_opt::pass_manager::PassManager;
 use fil_ir as ir;
+use fil_utils::Failure;
+use filament::interface_diff::InterfaceDiff;
 use filament::ir_passes::BuildDomination;
-use filament::{cmdline, ir_passes as ip, resolver::Resolver};
-use filament::{log_pass, log_time, pass_pipeline};
+use filament::manifest::Manifest;
+use filament::pipeline::{self, Pipeline, Stage};
+use filament::plan::Plan;
+use filament::resource_estimate::ResourceEstimate;
+use filament::time;
+use filament::timing::Timings;
+use filament::watch::{Poll, Watcher};
+use filament::{cmdline, extern_stub, ir_passes as ip, resolver::Resolver};
+use itertools::{EitherOrBoth, Itertools};
+use std::io::Write;
 
-// Prints out the interface for main component in the input program.
-fn run(opts: &cmdline::Opts) -> Result<(), u64> {
-    // enable tracing
-    env_logger::Builder::from_default_env()
-        .format_timestamp(None)
-        .format_module_path(false)
-        .format_target(false)
-        .filter_level(opts.log_level)
-        .target(env_logger::Target::Stderr)
-        .init();
+/// Exit code used when compilation fails because of a bug in the compiler
+const INTERNAL_ERROR_CODE: i32 = 70;
 
-    let ns = match Resolver::from(opts).parse_namespace() {
-        Ok(mut ns) => {
-            ns.toplevel = opts.toplevel.clone();
-            ns
+/// Reported when the constraints of the program are not checked
+const SKIP_DISCHARGE: &str = "constraint discharge was skipped using --unsafe-skip-discharge. The design may violate its timing constraints.";
+
+// Prints out the interface for main component in the input program.
+fn run(opts: &cmdline::Opts, manifest: &mut Manifest) -> Result<(), Failure> {
+    if opts.unsafe_skip_discharge {
+        if opts.deny_warnings {
+            eprintln!("error: {SKIP_DISCHARGE}");
+            return Err(1.into());
         }
+        eprintln!("warning: {SKIP_DISCHARGE}");
+    }
+
+    let mut resolver = Resolver::from(opts);
+    let ns = match Pipeline::parse(opts, &mut resolver) {
+        Ok(ns) => ns,
         Err(e) => {
-            eprintln!("Error: {e:?}");
-            return Err(1);
+            e.report();
+            return Err((&e).into());
         }
     };
+    if let Some(path) = &opts.dump_dep_file {
+        let Some(target) = &opts.output else {
+            eprintln!("Error: --dump-dep-file requires --output to name the target of the rule");
+            return Err(1.into());
+        };
+        let deps = resolver
+            .dependencies(&ns)
+            .iter()
+            .map(|dep| make_path(dep.as_path()))
+            .join(" ");
+        manifest.add("dep-file", path);
+        std::fs::write(path, format!("{}: {deps}\n", make_path(target)))
+            .map_err(|err| {
+                eprintln!("Error: Failed to write {}: {err}", path.display());
+                1.into()
+            })?;
+    }
+    // Stubs only depend on the signature and are emitted before checking
+    if let Some(comp) = &opts.emit_extern_stub {
+        return match extern_stub::generate(&ns, comp, opts) {
+            Ok(stub) => {
+                print!("{stub}");
+                Ok(())
+            }
+            Err(e) => {
+                e.report();
+                Err((&e).into())
+            }
+        };
+    }
     // Initialize the generator
-    let mut gen_exec = if ns.requires_gen() {
-        if opts.out_dir.is_none()
-            && matches!(opts.backend, cmdline::Backend::Calyx)
-        {
-            log::warn!(concat!(
-                "Generated calyx program will NOT compile because it depends ",
-                "on generated files. Please provide an output directory using ",
-                "`--out-dir <dir>` to store the generated files."
-            ))
-        }
-        Some(ns.init_gen(opts.out_dir.clone()))
-    } else {
-        None
-    };
+    if ns.requires_gen()
+        && opts.out_dir.is_none()
+        && matches!(opts.backend, cmdline::Backend::Calyx)
+    {
+        log::warn!(concat!(
+            "Generated calyx program will NOT compile because it depends ",
+            "on generated files. Please provide an output directory using ",
+            "`--out-dir <dir>` to store the generated files."
+        ))
+    }
+    let mut pipeline = Pipeline::new(opts, &ns);
 
     // Transform AST to IR
-    let mut ir = log_pass! { opts; ir::transform(ns)?, "astconv" };
-    pass_pipeline! {opts, ir;
-        ip::BuildDomination,
-        ip::TypeCheck,
-        ip::IntervalCheck,
-        ip::PhantomCheck,
-        ip::Assume
-    }
-    if !opts.unsafe_skip_discharge {
-        pass_pipeline! {opts, ir; ip::Discharge }
-    }
-    pass_pipeline! { opts, ir;
-        BuildDomination
-    };
-    ir = log_pass! { opts; ip::Monomorphize::transform(&ir, &mut gen_exec), "monomorphize"};
-    pass_pipeline! { opts, ir;
-        ip::Simplify,
-        ip::AssignCheck,
-        ip::BundleElim,
-        ip::AssignCheck
+    let mut ir = pipeline.astconv(ns)?;
+    if let Some(path) = &opts.callgraph {
+        manifest.add("callgraph", path);
+        return std::fs::write(path, ip::CallGraph::dot(&ir)).map_err(|err| {
+            eprintln!("Error: Failed to write {}: {err}", path.display());
+            1.into()
+        });
     }
-
-    // Return early if we're asked to dump the interface
-    if opts.dump_interface {
-        ip::DumpInterface::print(&ir);
-        return Ok(());
+    // Explain the interval before checking so that failing programs can be
+    // debugged
+    if let Some(query) = &opts.explain_interval {
+        return ip::ExplainInterval::print(&ir, query).map_err(|e| {
+            e.report();
+            (&e).into()
+        });
+    }
+    // Fail early if the constraints cannot be discharged
+    if pipeline::discharges(opts) {
+        if let Err(e) = ip::Discharge::find_solver(opts) {
+            e.report();
+            return Err((&e).into());
+        }
+    }
+    for stage in Stage::all(opts) {
+        ir = pipeline.run(stage, ir)?;
+        match stage {
+            // Constraints are reported once the program is known to type
+            // check
+            Stage::Discharge => {
+                if let Some(comp) = &opts.dump_constraints_json {
+                    return ip::Constraints::print(&ir, comp).map_err(|e| {
+                        e.report();
+                        (&e).into()
+                    });
+                }
+                if let [a, b] = opts.ports.as_slice() {
+                    return ip::Overlap::print(opts, &ir, a, b).map_err(|e| {
+                        e.report();
+                        (&e).into()
+                    });
+                }
+            }
+            Stage::Dominance => {
+                if let Some(path) = &opts.dump_dominance {
+                    if let Err(err) =
+                        std::fs::write(path, BuildDomination::dump(&ir))
+                    {
+                        eprintln!(
+                            "Error: Failed to write {}: {err}",
+                            path.display()
+                        );
+                        return Err(1.into());
+                    }
+                    manifest.add("dominance", path);
+                }
+            }
+            Stage::Monomorphize => {
+                if let Some(path) = &opts.compile_log {
+                    if let Err(e) = pipeline.compile_log.save(path) {
+                        e.report();
+                        return Err((&e).into());
+                    }
+                    manifest.add("compile-log", path);
+                }
+                // Files generated in a temporary directory are deleted with
+                // the executor
+                if let (Some(gen_exec), Some(_)) =
+                    (&pipeline.gen_exec, &opts.out_dir)
+                {
+                    for file in gen_exec.generated_files() {
+                        manifest.add("generated", file);
+                    }
+                }
+            }
+            Stage::Lower => emit_lowered(opts, &ir, &pipeline, manifest)?,
+            _ => (),
+        }
     }
-
     // Return if we are only checking
-    if opts.check {
+    if !pipeline::compiles(opts) {
         return Ok(());
     }
-    let calyx = log_time!(
-        ip::Compile::compile(ir, opts.disable_slow_fsms, opts.preserve_names),
-        "compile"
-    );
+    let prims = match pipeline::primitives(opts) {
+        Ok(prims) => prims,
+        Err(e) => {
+            e.report();
+            return Err((&e).into());
+        }
+    };
+    // Read the extern files before compilation so that missing files are
+    // reported before we generate any output.
+    let externs = if opts.inline_externs {
+        match read_externs(&ir) {
+            Ok(externs) => externs,
+            Err(e) => {
+                e.report();
+                return Err((&e).into());
+            }
+        }
+    } else {
+        vec![]
+    };
+    // Paths are emitted relative to the input file so that the output does
+    // not depend on the machine or the working directory. The verilog backend
+    // reads the extern files so it must use the original paths.
+    let externs = if opts.reproducible {
+        externs
+            .into_iter()
+            .map(|(path, content)| (relative_path(&path, &opts.input), content))
+            .collect()
+    } else {
+        externs
+    };
+    if opts.reproducible
+        && !opts.cross_check
+        && matches!(opts.backend, cmdline::Backend::Calyx)
+    {
+        ir.externals = std::mem::take(&mut ir.externals)
+            .into_iter()
+            .map(|(path, comps)| (relative_path(&path, &opts.input), comps))
+            .collect();
+    }
+    // Assertions are generated from the interface of the main component
+    let sva = opts.emit_sva.then(|| {
+        ip::EmitSva::generate(&ir, opts.name_prefix.as_deref().unwrap_or(""))
+    });
+    let sva = match sva.transpose() {
+        Ok(sva) => sva,
+        Err(e) => {
+            e.report();
+            return Err((&e).into());
+        }
+    };
+    let calyx = match pipeline.compile(ir, &prims) {
+        Ok(calyx) => calyx,
+        Err(e) => {
+            e.report();
+            return Err((&e).into());
+        }
+    };
+    if opts.resource_estimate {
+        println!("{}", ResourceEstimate::new(&calyx, &prims).table());
+        return Ok(());
+    }
+    if opts.cross_check {
+        return match cross_check(calyx) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(1.into()),
+            Err(e) => {
+                // Calyx renders its errors along with their positions
+                // through Debug
+                let e = fil_utils::Error::misc(format!(
+                    "cross-check could not compile the program: {e:?}"
+                ));
+                e.report();
+                Err((&e).into())
+            }
+        };
+    }
+    let mut out: Box<dyn std::io::Write> = match &opts.output {
+        Some(path) => match create_output(path) {
+            Ok(file) => {
+                manifest.add("output", path);
+                Box::new(file)
+            }
+            Err(err) => {
+                eprintln!("Error: Failed to write {}: {err}", path.display());
+                return Err(1.into());
+            }
+        },
+        None => Box::new(std::io::stdout()),
+    };
+    // Mark artifacts generated without checking the timing constraints
+    if opts.unsafe_skip_discharge {
+        writeln!(out, "// {SKIP_DISCHARGE}").unwrap();
+    }
     match opts.backend {
         cmdline::Backend::Verilog => {
-            gen_verilog(calyx).unwrap();
+            gen_verilog(calyx, sva, externs, opts.output.as_deref(), &mut out)
+                .unwrap();
         }
         cmdline::Backend::Calyx => {
-            let out = &mut std::io::stdout();
-            calyx_ir::Printer::write_context(&calyx, false, out).unwrap();
+            calyx_ir::Printer::write_context(&calyx, false, &mut out).unwrap();
         }
     }
 
     // Drop the generator executor after the execution finishes to ensure that
     // Calyx has access to the generated file.
-    drop(gen_exec);
+    drop(pipeline);
+    Ok(())
+}
+
+/// Write the outputs that describe the checked and monomorphized program.
+/// Interfaces are printed on the standard output.
+fn emit_lowered(
+    opts: &cmdline::Opts,
+    ir: &ir::Context,
+    pipeline: &Pipeline,
+    manifest: &mut Manifest,
+) -> Result<(), Failure> {
+    if let Some(path) = &opts.emit_timing_hints {
+        let hints = match ip::TimingHints::generate(ir) {
+            Ok(hints) => hints,
+            Err(e) => {
+                e.report();
+                return Err((&e).into());
+            }
+        };
+        if let Err(err) = std::fs::write(path, hints) {
+            eprintln!("Error: Failed to write {}: {err}", path.display());
+            return Err(1.into());
+        }
+        manifest.add("timing-hints", path);
+    }
+    if let Some(path) = &opts.emit_json {
+        if let Err(err) = std::fs::write(path, ip::EmitJson::generate(ir)) {
+            eprintln!("Error: Failed to write {}: {err}", path.display());
+            return Err(1.into());
+        }
+        manifest.add("ir-json", path);
+    }
+    if let Some(path) = &opts.emit_dot {
+        if let Err(err) = std::fs::write(path, ip::EmitDot::generate(ir)) {
+            eprintln!("Error: Failed to write {}: {err}", path.display());
+            return Err(1.into());
+        }
+        manifest.add("dot", path);
+    }
+    if opts.dump_interface {
+        if let Err(e) = ip::DumpInterface::print(ir) {
+            e.report();
+            return Err((&e).into());
+        }
+    } else if opts.dump_specialized_interfaces {
+        ip::DumpInterface::print_specializations(ir, &pipeline.compile_log);
+    }
     Ok(())
 }
 
-fn gen_verilog(mut ctx: calyx_ir::Context) -> Result<(), calyx_utils::Error> {
+/// Read the contents of all extern files used by the program.
+fn read_externs(
+    ctx: &ir::Context,
+) -> fil_utils::FilamentResult<Vec<(String, String)>> {
+    let mut paths = ctx.externals.keys().cloned().collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = std::fs::read_to_string(&path).map_err(|err| {
+                fil_utils::Error::invalid_file(format!(
+                    "Failed to inline extern {path}: {err}"
+                ))
+            })?;
+            Ok((path, content))
+        })
+        .collect()
+}
+
+/// The path of `path` relative to the directory containing `input`.
+/// Returns `path` unchanged if either path cannot be resolved.
+fn relative_path(path: &str, input: &std::path::Path) -> String {
+    let (Ok(path), Ok(input)) =
+        (std::fs::canonicalize(path), std::fs::canonicalize(input))
+    else {
+        return path.to_string();
+    };
+    let base = input.parent().unwrap();
+    let common = path
+        .components()
+        .zip(base.components())
+        .take_while(|(p, b)| p == b)
+        .count();
+    std::iter::repeat(std::path::Component::ParentDir)
+        .take(base.components().count() - common)
+        .chain(path.components().skip(common))
+        .collect::<std::path::PathBuf>()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Lower the Calyx program to verilog and write it to `out`.
+fn lower_verilog(
+    mut ctx: calyx_ir::Context,
+    out: calyx_utils::OutputFile,
+) -> Result<(), calyx_utils::Error> {
     let pm = PassManager::default_passes()?;
     let backend_conf = calyx_ir::BackendConf {
         synthesis_mode: false,
@@ -110,19 +382,288 @@ fn gen_verilog(mut ctx: calyx_ir::Context) -> Result<(), calyx_utils::Error> {
         false,
     )?;
     let backend = calyx_backend::VerilogBackend;
-    backend.run(ctx, calyx_utils::OutputFile::Stdout)
+    backend.run(ctx, out)
+}
+
+/// Escape the spaces in a path used in a make rule
+fn make_path(path: &std::path::Path) -> String {
+    path.to_string_lossy().replace(' ', "\\ ")
+}
+
+/// Create the file `path` for the compiled program along with its missing
+/// parent directories.
+fn create_output(path: &std::path::Path) -> std::io::Result<std::fs::File> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::File::create(path)
+}
+
+/// Write the verilog for the program to `out`. `output` is the file that `out`
+/// writes to or `None` for the standard output.
+fn gen_verilog(
+    ctx: calyx_ir::Context,
+    sva: Option<String>,
+    externs: Vec<(String, String)>,
+    output: Option<&std::path::Path>,
+    out: &mut dyn std::io::Write,
+) -> Result<(), calyx_utils::Error> {
+    // The backend opens the files it writes to so the verilog is written to a
+    // temporary file and copied into the output
+    let tmp = match output {
+        Some(path) => path.with_extension("filament-tmp"),
+        None => std::env::temp_dir()
+            .join(format!("filament-{}.sv", std::process::id())),
+    };
+    lower_verilog(ctx, calyx_utils::OutputFile::File(tmp.clone()))?;
+    let verilog = std::fs::read_to_string(&tmp)?;
+    std::fs::remove_file(&tmp)?;
+    out.write_all(verilog.as_bytes())?;
+    if let Some(sva) = sva {
+        writeln!(out, "{sva}")?;
+    }
+    // The backend already copies the extern files it links against into its
+    // output so only the ones that are missing from it are inlined
+    for (path, content) in externs {
+        if verilog.contains(content.trim()) {
+            continue;
+        }
+        writeln!(out, "// ====== Inlined from {path} ======")?;
+        writeln!(out, "{content}")?;
+    }
+    Ok(())
+}
+
+/// A temporary directory that is removed along with its contents when it is
+/// dropped
+struct TempDir(std::path::PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // The directory may not have been created
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Check that lowering the program directly to verilog produces the same
+/// netlist as printing it in the Calyx format, parsing it back, and lowering
+/// the result. Returns `false` and reports the differences if they diverge.
+fn cross_check(calyx: calyx_ir::Context) -> Result<bool, calyx_utils::Error> {
+    // Removed on every return, including the errors
+    let dir = TempDir(
+        std::env::temp_dir()
+            .join(format!("filament-cross-check-{}", std::process::id())),
+    );
+    std::fs::create_dir_all(&dir.0)?;
+    let futil = dir.0.join("program.futil");
+    let direct = dir.0.join("direct.sv");
+    let via_calyx = dir.0.join("calyx.sv");
+
+    // Print the program before it is consumed by the lowering
+    let mut file = std::fs::File::create(&futil)?;
+    calyx_ir::Printer::write_context(&calyx, false, &mut file)?;
+    lower_verilog(calyx, calyx_utils::OutputFile::File(direct.clone()))?;
+
+    let ws = calyx_frontend::Workspace::construct(
+        &Some(futil),
+        std::path::Path::new("."),
+    )?;
+    let reparsed = calyx_ir::from_ast::ast_to_ir(ws)?;
+    lower_verilog(reparsed, calyx_utils::OutputFile::File(via_calyx.clone()))?;
+
+    let direct = std::fs::read_to_string(&direct)?;
+    let via_calyx = std::fs::read_to_string(&via_calyx)?;
+
+    // Compare the netlists line by line, ignoring indentation
+    let diffs = direct
+        .lines()
+        .map(str::trim)
+        .zip_longest(via_calyx.lines().map(str::trim))
+        .enumerate()
+        .filter_map(|(line, pair)| match pair {
+            EitherOrBoth::Both(l, r) if l == r => None,
+            EitherOrBoth::Both(l, r) => Some((line + 1, l, r)),
+            EitherOrBoth::Left(l) => Some((line + 1, l, "")),
+            EitherOrBoth::Right(r) => Some((line + 1, "", r)),
+        })
+        .collect_vec();
+    if diffs.is_empty() {
+        println!("cross-check passed: generated verilog is identical");
+        return Ok(true);
+    }
+    eprintln!(
+        "cross-check failed: generated verilog differs in {} lines",
+        diffs.len()
+    );
+    for (line, direct, via_calyx) in diffs {
+        eprintln!("line {line}:\n  direct: {direct}\n  calyx:  {via_calyx}");
+    }
+    Ok(false)
+}
+
+/// Compare two interface dumps and report the changes between them.
+fn interface_diff(old: &std::path::Path, new: &std::path::Path) -> i32 {
+    match InterfaceDiff::compute(old, new) {
+        Ok(diff) => {
+            diff.print();
+            i32::from(diff.is_breaking())
+        }
+        Err(e) => {
+            e.report();
+            2
+        }
+    }
+}
+
+/// Write the tokens of the input program to `out` in JSON format.
+fn tokens(
+    input: &std::path::Path,
+    out: &std::path::Path,
+) -> fil_utils::FilamentResult<()> {
+    let tokens = fil_ast::FilamentParser::tokens(input)?
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "kind": t.kind,
+                "text": t.text,
+                "start": t.start,
+                "end": t.end,
+                "line": t.line,
+                "col": t.col,
+            })
+        })
+        .collect::<Vec<_>>();
+    let content = serde_json::to_string_pretty(&tokens).unwrap();
+    std::fs::write(out, content + "\n").map_err(|err| {
+        fil_utils::Error::write_error(format!(
+            "Failed to write {}: {err}",
+            out.to_string_lossy()
+        ))
+    })
+}
+
+/// Print the version of Filament and of the Calyx crates it was built with.
+/// Programs generated for a different version of Calyx may not compile.
+fn print_version() {
+    println!("filament {}", env!("CARGO_PKG_VERSION"));
+    for krate in env!("FILAMENT_CALYX_VERSIONS").split(';') {
+        let (name, version) = krate.split_once('=').unwrap();
+        println!("{name} {version}");
+    }
+}
+
+/// Print the diagnostics collected for the JSON error format and exit
+fn exit(code: i32) -> ! {
+    fil_utils::ErrorFormat::flush();
+    std::process::exit(code)
+}
+
+/// Recompile the program every time the input file or one of its imports
+/// changes. Diagnostics are reported after each compilation.
+fn watch(opts: &cmdline::Opts) {
+    Watcher::new(Poll::default()).run(opts.watch_limit, || {
+        // Nothing is carried over from the previous compilation
+        fil_utils::Warning::take_all();
+        fil_utils::GlobalPositionTable::reset();
+        let res = run(opts, &mut Manifest::default());
+        fil_utils::Warning::report_all();
+        fil_utils::ErrorFormat::flush();
+        match res {
+            Ok(()) => eprintln!("Compilation succeeded."),
+            Err(err) => eprintln!("Compilation failed with {err} errors."),
+        }
+        // Errors were reported by the compilation. The files parsed before
+        // the error are still watched so that fixing them triggers a
+        // recompile.
+        let mut resolver = Resolver::from(opts);
+        let _ = resolver.parse_namespace();
+        resolver.files().map(std::path::Path::to_path_buf).collect()
+    });
 }
 
 fn main() {
-    let opts: cmdline::Opts = argh::from_env();
-    match run(&opts) {
-        Ok(_) => (),
+    let mut opts = cmdline::Opts::from_env();
+    if opts.version {
+        return print_version();
+    }
+    opts.error_format.set();
+    if opts.interface_diff {
+        let new = std::path::Path::new(&opts.ports[0]);
+        exit(interface_diff(&opts.input, new))
+    }
+    if let Some(out) = &opts.tokens {
+        if let Err(e) = tokens(&opts.input, out) {
+            e.report();
+            exit(1)
+        }
+        return;
+    }
+    if let Some(path) = opts.load_plan.clone() {
+        if let Err(e) = Plan::load(&path).and_then(|p| p.apply(&mut opts)) {
+            e.report();
+            exit(1)
+        }
+    }
+    // enable tracing
+    env_logger::Builder::from_default_env()
+        .format_timestamp(None)
+        .format_module_path(false)
+        .format_target(false)
+        .filter_level(opts.log_level)
+        .target(env_logger::Target::Stderr)
+        .init();
+
+    if opts.watch {
+        if Resolver::reads_stdin(&opts.input) {
+            eprintln!("Error: --watch cannot be used when the program is read from the standard input");
+            exit(1)
+        }
+        return watch(&opts);
+    }
+    let mut manifest = Manifest::default();
+    if let Some(path) = &opts.dump_plan {
+        if let Err(e) = Plan::from(&opts).save(path) {
+            e.report();
+            exit(1)
+        }
+        manifest.add("plan", path);
+    }
+    if opts.timing_json.is_some() {
+        Timings::enable();
+    }
+    let (res, total) = time!(run(&opts, &mut manifest));
+    if let Some(path) = &opts.timing_json {
+        if let Err(e) = Timings::save(path, total) {
+            e.report();
+            exit(1)
+        }
+        manifest.add("timing", path);
+    }
+    // Report the warnings collected during compilation
+    fil_utils::Warning::report_all();
+    match res {
+        Ok(_) => {
+            if let Some(path) = &opts.emit_manifest {
+                if let Err(e) = manifest.save(path) {
+                    e.report();
+                    exit(1)
+                }
+            }
+            fil_utils::ErrorFormat::flush();
+        }
         Err(err) => {
-            eprintln!("Compilation failed with {err} errors.");
-            if !opts.show_models {
+            // The JSON output only contains the diagnostics
+            let human = opts.error_format == fil_utils::ErrorFormat::Human;
+            if human {
+                eprintln!("Compilation failed with {err} errors.");
+            }
+            if err.internal {
+                exit(INTERNAL_ERROR_CODE)
+            }
+            if human && !opts.show_models {
                 eprintln!("Run with --show-models to generate assignments for failing constraints.");
             }
-            std::process::exit(1)
+            exit(1)
         }
     }
 }
@@ -0,0 +1,76 @@
+//! Manifests of the files produced by a compilation.
+use fil_utils::{Error, FilamentResult};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Serialize)]
+/// A file written by the compiler
+struct Artifact {
+    /// The option that produced the file
+    kind: &'static str,
+    path: String,
+    size: u64,
+    /// 64-bit FNV-1a hash of the contents
+    fnv1a64: String,
+}
+
+#[derive(Serialize)]
+/// The contents of a manifest file
+struct Artifacts {
+    artifacts: Vec<Artifact>,
+}
+
+#[derive(Default)]
+/// Records the files written during a compilation so that build systems can
+/// track them.
+pub struct Manifest {
+    files: Vec<(&'static str, PathBuf)>,
+}
+
+impl Manifest {
+    /// Record that the file at `path` was written for `kind`
+    pub fn add(&mut self, kind: &'static str, path: impl Into<PathBuf>) {
+        self.files.push((kind, path.into()));
+    }
+
+    /// The FNV-1a hash of the given bytes
+    fn fnv1a64(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+            (hash ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    /// Write the manifest to the given file. The sizes and hashes are
+    /// computed from the current contents of the recorded files.
+    pub fn save(&self, path: &Path) -> FilamentResult<()> {
+        let artifacts = self
+            .files
+            .iter()
+            .map(|(kind, file)| {
+                let content = fs::read(file).map_err(|err| {
+                    Error::invalid_file(format!(
+                        "Failed to read {}: {err}",
+                        file.to_string_lossy()
+                    ))
+                })?;
+                Ok(Artifact {
+                    kind: *kind,
+                    path: file.to_string_lossy().to_string(),
+                    size: content.len() as u64,
+                    fnv1a64: format!("{:016x}", Self::fnv1a64(&content)),
+                })
+            })
+            .collect::<FilamentResult<Vec<_>>>()?;
+        let content =
+            serde_json::to_string_pretty(&Artifacts { artifacts }).unwrap();
+        fs::write(path, content + "\n").map_err(|err| {
+            Error::write_error(format!(
+                "Failed to write {}: {err}",
+                path.to_string_lossy()
+            ))
+        })
+    }
+}
@@ -0,0 +1,37 @@
+//! Selection of the components tagged with a group.
+use fil_ast as ast;
+use std::collections::HashSet;
+
+/// Drop the components that are not tagged with `@group("<tag>")` and are
+/// not instantiated, directly or transitively, by a tagged component.
+/// External components are kept since they only define signatures.
+pub fn retain(ns: &mut ast::Namespace, tag: &str) {
+    let mut keep: HashSet<_> = ns
+        .components
+        .iter()
+        .filter(|comp| comp.sig.attributes.text("group") == Some(tag))
+        .map(|comp| *comp.sig.name.inner())
+        .collect();
+    let mut worklist: Vec<_> = keep.iter().copied().collect();
+    while let Some(name) = worklist.pop() {
+        let Some(comp) =
+            ns.components.iter().find(|c| c.sig.name.inner() == &name)
+        else {
+            continue;
+        };
+        for inst in comp.instances() {
+            let dep = *inst.component.inner();
+            if keep.insert(dep) {
+                worklist.push(dep);
+            }
+        }
+    }
+
+    let before = ns.components.len();
+    ns.components
+        .retain(|comp| keep.contains(comp.sig.name.inner()));
+    log::info!(
+        "Dropped {} components not tagged with `{tag}'",
+        before - ns.components.len()
+    );
+}
@@ -0,0 +1,328 @@
+//! The pass pipeline of the compiler. The binary, the [crate::compile]
+//! library entry point, and [crate::plan::Plan] are all derived from the
+//! stages defined here.
+use crate::{
+    assumptions,
+    cmdline::{self, CheckLevel, Opts},
+    ir_passes as ip,
+    ir_visitor::Visitor,
+    log_pass, log_time, only_tagged,
+    resolver::Resolver,
+    unused_externs,
+};
+use calyx_ir as calyx;
+use fil_ast as ast;
+use fil_gen as gen;
+use fil_ir as ir;
+use fil_utils::{Failure, FilamentResult};
+
+#[derive(Clone, Copy)]
+/// A pass run on the IR of the program
+pub enum Pass {
+    /// A pass implemented by a [Visitor]
+    Visit(
+        &'static str,
+        fn(&Opts, &mut ir::Context) -> Result<(), Failure>,
+    ),
+    /// A pass that rebuilds the program
+    Transform(&'static str, fn(ir::Context) -> ir::Context),
+    /// Monomorphization, which runs the generators used by the program
+    Monomorphize,
+}
+
+impl Pass {
+    fn visit<V: Visitor>() -> Self {
+        Pass::Visit(V::name(), V::do_pass)
+    }
+
+    /// The name of the pass used by `--dump-after` and by plans
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pass::Visit(name, _) | Pass::Transform(name, _) => *name,
+            Pass::Monomorphize => "monomorphize",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// A group of passes. Outputs that inspect the program, like the debugging
+/// dumps of the binary, are generated between stages.
+pub enum Stage {
+    /// Only check the bitwidths and bundle accesses of the program
+    Widths,
+    /// Type check the program
+    Types,
+    /// Check the availability intervals and generate the constraints
+    Intervals,
+    /// Discharge the constraints of the program
+    Discharge,
+    /// Rebuild the order of definitions before monomorphization
+    Dominance,
+    /// Generate a component for each instantiation of a parametric component
+    Monomorphize,
+    /// Check and simplify the monomorphized program
+    Lower,
+    /// Remove the parts of the program that compilation does not need
+    Optimize,
+}
+
+impl Stage {
+    /// The stages that compile the program to Calyx, in order
+    pub const COMPILE: [Stage; 7] = [
+        Stage::Types,
+        Stage::Intervals,
+        Stage::Discharge,
+        Stage::Dominance,
+        Stage::Monomorphize,
+        Stage::Lower,
+        Stage::Optimize,
+    ];
+
+    /// The stages run by the compiler with the given options, in order
+    pub fn all(opts: &Opts) -> Vec<Stage> {
+        // These outputs only depend on the program before it is checked
+        if opts.emit_extern_stub.is_some()
+            || opts.callgraph.is_some()
+            || opts.explain_interval.is_some()
+        {
+            return vec![];
+        }
+        if opts.check_widths_only {
+            return vec![Stage::Widths];
+        }
+        let last = match opts.check_level() {
+            Some(CheckLevel::Types) => Stage::Types,
+            Some(CheckLevel::Intervals) => Stage::Intervals,
+            // Constraints are inspected once the program is known to type
+            // check
+            _ if opts.dump_constraints_json.is_some() || opts.overlap => {
+                Stage::Discharge
+            }
+            Some(CheckLevel::All) => Stage::Lower,
+            None if opts.dump_interface || opts.dump_specialized_interfaces => {
+                Stage::Lower
+            }
+            None => Stage::Optimize,
+        };
+        let end = Self::COMPILE.iter().position(|s| *s == last).unwrap();
+        Self::COMPILE[..=end].to_vec()
+    }
+
+    /// The passes of this stage with the given options, in order
+    pub fn passes(self, opts: &Opts) -> Vec<Pass> {
+        let mut passes = vec![];
+        match self {
+            Stage::Widths => passes.extend([
+                Pass::visit::<ip::BuildDomination>(),
+                Pass::visit::<ip::TypeCheck>(),
+                Pass::visit::<ip::Assume>(),
+                Pass::visit::<ip::Discharge>(),
+            ]),
+            Stage::Types => passes.extend([
+                Pass::visit::<ip::BuildDomination>(),
+                Pass::visit::<ip::TypeCheck>(),
+            ]),
+            Stage::Intervals => {
+                passes.extend([
+                    Pass::visit::<ip::IntervalCheck>(),
+                    Pass::visit::<ip::PhantomCheck>(),
+                ]);
+                if opts.warn_pass_through {
+                    passes.push(Pass::visit::<ip::PassThroughLint>());
+                }
+                passes.extend([
+                    Pass::visit::<ip::UnusedEvents>(),
+                    Pass::visit::<ip::Assume>(),
+                ]);
+                if opts.warn_unconstrained_phantoms {
+                    passes.push(Pass::visit::<ip::PhantomLint>());
+                }
+            }
+            Stage::Discharge => {
+                if !opts.unsafe_skip_discharge {
+                    passes.push(Pass::visit::<ip::Discharge>());
+                }
+            }
+            Stage::Dominance => {
+                passes.push(Pass::visit::<ip::BuildDomination>())
+            }
+            Stage::Monomorphize => passes.push(Pass::Monomorphize),
+            Stage::Lower => passes.extend([
+                Pass::visit::<ip::FoldCheck>(),
+                Pass::visit::<ip::Simplify>(),
+                Pass::visit::<ip::UndrivenOutputs>(),
+                Pass::visit::<ip::AssignCheck>(),
+                Pass::visit::<ip::BundleElim>(),
+                Pass::visit::<ip::AssignCheck>(),
+                Pass::visit::<ip::CombLoopCheck>(),
+            ]),
+            Stage::Optimize => {
+                if !opts.keep_dead {
+                    passes.push(Pass::Transform(
+                        "dead-instances",
+                        ip::DeadInstances::transform,
+                    ));
+                }
+                if opts.dedup_components {
+                    passes.push(Pass::Transform("dedup", ip::Dedup::transform));
+                }
+            }
+        }
+        passes
+    }
+}
+
+/// The names of the passes run by the compiler with the given options, in
+/// order. Conversion of the program to the IR and compilation to Calyx are
+/// named `astconv` and `compile`.
+pub fn passes(opts: &Opts) -> Vec<&'static str> {
+    // Stubs only depend on the signatures and are emitted before the
+    // program is converted
+    if opts.emit_extern_stub.is_some() {
+        return vec![];
+    }
+    let mut passes = vec!["astconv"];
+    passes.extend(
+        Stage::all(opts)
+            .into_iter()
+            .flat_map(|stage| stage.passes(opts))
+            .map(|pass| pass.name()),
+    );
+    if compiles(opts) {
+        passes.push("compile");
+    }
+    passes
+}
+
+/// Whether the program is compiled to Calyx with the given options
+pub fn compiles(opts: &Opts) -> bool {
+    Stage::all(opts).last() == Some(&Stage::Optimize)
+}
+
+/// Whether the constraints of the program are discharged with the given
+/// options
+pub fn discharges(opts: &Opts) -> bool {
+    passes(opts).contains(&ip::Discharge::name())
+}
+
+/// Load the primitives used to compile the program
+pub fn primitives(opts: &Opts) -> FilamentResult<ip::PrimitiveMap> {
+    match &opts.primitive_map {
+        Some(path) => ip::PrimitiveMap::load(path),
+        None => Ok(ip::PrimitiveMap::default()),
+    }
+}
+
+/// Runs the stages of the pipeline on a program
+pub struct Pipeline<'a> {
+    opts: &'a Opts,
+    /// Runs the generators used by the program. Files generated in a
+    /// temporary directory are deleted when the pipeline is dropped.
+    pub gen_exec: Option<gen::GenExec>,
+    /// The components generated by monomorphization
+    pub compile_log: ip::CompileLog,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Parse the program described by `opts` and its imports with `resolver`
+    /// and only keep the components selected by the options.
+    pub fn parse(
+        opts: &Opts,
+        resolver: &mut Resolver,
+    ) -> FilamentResult<ast::Namespace> {
+        let mut ns = resolver.parse_namespace()?;
+        ns.toplevel = opts.toplevel.clone();
+        if let Some(tag) = &opts.only_tagged {
+            only_tagged::retain(&mut ns, tag);
+        }
+        Ok(ns)
+    }
+
+    pub fn new(opts: &'a Opts, ns: &ast::Namespace) -> Self {
+        Self {
+            opts,
+            gen_exec: ns
+                .requires_gen()
+                .then(|| ns.init_gen(opts.out_dir.clone())),
+            compile_log: ip::CompileLog::default(),
+        }
+    }
+
+    /// Run the checks on the source of the program and convert it to the IR
+    pub fn astconv(&self, ns: ast::Namespace) -> Result<ir::Context, Failure> {
+        let opts = self.opts;
+        if opts.warn_unused_externs {
+            let warnings = unused_externs::report(&ns, &opts.input);
+            if opts.deny_warnings && warnings > 0 {
+                return Err(warnings.into());
+            }
+        }
+        if !opts.allow_assume {
+            let warnings = assumptions::report(&ns);
+            if opts.deny_warnings && warnings > 0 {
+                return Err(warnings.into());
+            }
+        }
+        Ok(
+            log_pass! { opts; ir::transform(ns, opts.time_overflow)?, "astconv" },
+        )
+    }
+
+    /// Run the passes of `stage` on the program
+    pub fn run(
+        &mut self,
+        stage: Stage,
+        mut ir: ir::Context,
+    ) -> Result<ir::Context, Failure> {
+        let opts = self.opts;
+        for pass in stage.passes(opts) {
+            ir = match pass {
+                Pass::Visit(name, run) => log_pass! { opts; {
+                    run(opts, &mut ir)?;
+                    ir
+                }, name },
+                Pass::Transform(name, transform) => {
+                    log_pass! { opts; transform(ir), name }
+                }
+                Pass::Monomorphize => log_pass! { opts;
+                    ip::Monomorphize::transform(
+                        &ir,
+                        &mut self.gen_exec,
+                        &mut self.compile_log,
+                        opts.trace_monomorphization,
+                    )?,
+                    pass.name()
+                },
+            };
+        }
+        Ok(ir)
+    }
+
+    /// Compile the checked program to Calyx
+    pub fn compile(
+        &self,
+        ir: ir::Context,
+        prims: &ip::PrimitiveMap,
+    ) -> FilamentResult<calyx::Context> {
+        let opts = self.opts;
+        let encoding = if opts.disable_slow_fsms {
+            ip::FsmEncoding::OneHot
+        } else {
+            match opts.fsm_encoding {
+                cmdline::FsmEncoding::Binary => ip::FsmEncoding::Binary,
+                cmdline::FsmEncoding::OneHot => ip::FsmEncoding::OneHot,
+                cmdline::FsmEncoding::Gray => ip::FsmEncoding::Gray,
+            }
+        };
+        log_time!(
+            ip::Compile::compile(
+                ir,
+                encoding,
+                opts.preserve_names,
+                opts.name_prefix.clone().unwrap_or_default(),
+                prims,
+            ),
+            "compile"
+        )
+    }
+}
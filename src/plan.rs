@@ -0,0 +1,79 @@
+//! Reproducible descriptions of a compilation.
+use crate::cmdline::Opts;
+use crate::pipeline;
+use fil_utils::{Error, FilamentResult};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+#[derive(Serialize, Deserialize, Debug)]
+/// The ordered pass pipeline and the options that affect the compilation.
+/// Loading a plan restores the options so that the same pipeline is run.
+pub struct Plan {
+    /// Passes in the order they are run
+    pub passes: Vec<String>,
+    /// Options of the compilation. See [Opts] for the options that are not
+    /// recorded.
+    #[serde(flatten)]
+    pub options: Opts,
+}
+
+impl From<&Opts> for Plan {
+    fn from(opts: &Opts) -> Self {
+        Self {
+            passes: Self::passes(opts),
+            options: opts.clone(),
+        }
+    }
+}
+
+impl Plan {
+    /// The passes run by the compiler with the given options
+    fn passes(opts: &Opts) -> Vec<String> {
+        pipeline::passes(opts)
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Read a plan from the given file
+    pub fn load(path: &Path) -> FilamentResult<Self> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            Error::invalid_file(format!(
+                "Failed to read {}: {err}",
+                path.to_string_lossy()
+            ))
+        })?;
+        serde_json::from_str(&content).map_err(|err| {
+            Error::malformed(format!(
+                "Failed to parse plan {}: {err}",
+                path.to_string_lossy()
+            ))
+        })
+    }
+
+    /// Write the plan to the given file
+    pub fn save(&self, path: &Path) -> FilamentResult<()> {
+        let content = serde_json::to_string_pretty(self).unwrap();
+        fs::write(path, content + "\n").map_err(|err| {
+            Error::write_error(format!(
+                "Failed to write {}: {err}",
+                path.to_string_lossy()
+            ))
+        })
+    }
+
+    /// Update the options to run this plan.
+    /// Fails if the options do not reproduce the recorded pass pipeline.
+    pub fn apply(&self, opts: &mut Opts) -> FilamentResult<()> {
+        opts.restore(self.options.clone());
+        let passes = Self::passes(opts);
+        if passes != self.passes {
+            return Err(Error::misc(format!(
+                "Plan cannot be reproduced. Expected passes: {}, but the compiler will run: {}",
+                self.passes.join(", "),
+                passes.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
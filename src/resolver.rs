@@ -1,16 +1,92 @@
 use crate::cmdline;
 use fil_ast as ast;
 use fil_utils::{Error, FilamentResult};
+use itertools::Itertools;
 use std::{
-    collections::HashSet,
+    collections::{BTreeSet, HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
 };
 
+#[derive(Default)]
+/// The files imported by each file, identified by their canonical paths
+struct ImportGraph {
+    imports: HashMap<PathBuf, Vec<PathBuf>>,
+    /// Path used to refer to each file in errors
+    names: HashMap<PathBuf, PathBuf>,
+}
+
+impl ImportGraph {
+    fn canonical(&mut self, path: &Path) -> PathBuf {
+        let canon =
+            fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.names
+            .entry(canon.clone())
+            .or_insert_with(|| path.to_path_buf());
+        canon
+    }
+
+    /// Record that `from` imports `to`
+    fn add(&mut self, from: &Path, to: &Path) {
+        let from = self.canonical(from);
+        let to = self.canonical(to);
+        self.imports.entry(from).or_default().push(to);
+    }
+
+    /// Search for a cycle of imports reachable from `file`. Returns the files
+    /// in the cycle starting and ending with the same file.
+    fn visit<'a>(
+        &'a self,
+        file: &'a PathBuf,
+        stack: &mut Vec<&'a PathBuf>,
+        done: &mut HashSet<&'a PathBuf>,
+    ) -> Option<Vec<&'a PathBuf>> {
+        if let Some(start) = stack.iter().position(|f| *f == file) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(file);
+            return Some(cycle);
+        }
+        if done.contains(file) {
+            return None;
+        }
+        stack.push(file);
+        for next in self.imports.get(file).into_iter().flatten() {
+            if let Some(cycle) = self.visit(next, stack, done) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        done.insert(file);
+        None
+    }
+
+    /// Report an error if a file imports itself, directly or through other
+    /// files. `root` is the input file.
+    fn check_cycles(&self, root: &Path) -> FilamentResult<()> {
+        let root =
+            fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        let mut done = HashSet::new();
+        // Search from the input file first so that cycles start with it
+        let files = std::iter::once(&root).chain(self.imports.keys().sorted());
+        for file in files {
+            if let Some(cycle) = self.visit(file, &mut vec![], &mut done) {
+                let cycle = cycle
+                    .into_iter()
+                    .map(|f| self.names[f].display())
+                    .join(" -> ");
+                return Err(Error::malformed(format!(
+                    "cyclic import: {cycle}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Completely parse all dependecies of a Filament program
 pub struct Resolver {
-    // Location of the library
-    lib: PathBuf,
+    // Locations of the libraries
+    lib: Vec<PathBuf>,
     // Location of the base file
     input: PathBuf,
     // Files that have already been imported
@@ -20,7 +96,11 @@ pub struct Resolver {
 impl From<&cmdline::Opts> for Resolver {
     fn from(opts: &cmdline::Opts) -> Self {
         Self {
-            lib: opts.library.clone(),
+            lib: if opts.library.is_empty() {
+                vec![".".into()]
+            } else {
+                opts.library.clone()
+            },
             input: opts.input.clone(),
             already_imported: HashSet::new(),
         }
@@ -28,43 +108,45 @@ impl From<&cmdline::Opts> for Resolver {
 }
 
 impl Resolver {
-    /// Resolve import either using opts.library or relative the parent directory of the input file.
+    /// Resolve an import relative to the directory `dir` of the importing
+    /// file or, if it does not exist there, the first library path that
+    /// contains it. Absolute imports are used as is.
     fn resolve_import(
         &self,
         imp: &String,
         dir: &Path,
     ) -> FilamentResult<PathBuf> {
-        // Resolve against the library path
-        let mut lib_base = self.lib.clone();
-        lib_base.push(imp);
-
-        // Resove against base
-        let mut cur_base = dir.to_path_buf();
-        cur_base.push(imp);
-        if cur_base.exists() && lib_base.exists() {
-            let canon_base = fs::canonicalize(cur_base.clone()).unwrap();
-            let canon_lib_base = fs::canonicalize(lib_base.clone()).unwrap();
-            if canon_base != canon_lib_base {
+        let local = dir.join(imp);
+        let lib = self.lib.iter().map(|l| l.join(imp)).find(|l| l.exists());
+        match (local.exists(), lib) {
+            (true, Some(lib))
+                if fs::canonicalize(&local).unwrap()
+                    != fs::canonicalize(&lib).unwrap() =>
+            {
                 Err(Error::misc(format!(
-                "Refusing to resolve ambiguous import: {}. Conflicting candidates found:\n{}\n{}",
-                imp,
-                fs::canonicalize(lib_base).unwrap().display(),
-                fs::canonicalize(cur_base).unwrap().display(),
-            )))
-            } else {
-                Ok(cur_base)
+                    "Refusing to resolve ambiguous import: {}. Conflicting candidates found:\n{}\n{}",
+                    imp,
+                    fs::canonicalize(lib).unwrap().display(),
+                    fs::canonicalize(local).unwrap().display(),
+                )))
+            }
+            (true, _) => Ok(local),
+            (false, Some(lib)) => Ok(lib),
+            (false, None) => {
+                let dirs = std::iter::once(dir)
+                    .chain(self.lib.iter().map(PathBuf::as_path))
+                    .map(|d| {
+                        if d.as_os_str().is_empty() {
+                            ".".to_string()
+                        } else {
+                            d.display().to_string()
+                        }
+                    })
+                    .join(", ");
+                Err(Error::misc(format!(
+                    "Could not resolve import path: {imp}. Searched the directories: {dirs}"
+                )))
             }
-        } else if cur_base.exists() {
-            Ok(cur_base)
-        } else if lib_base.exists() {
-            Ok(lib_base)
-        } else {
-            Err(Error::misc(format!(
-                "Could not resolve import path: {}. Neither {} nor {} exist.",
-                imp,
-                lib_base.display(),
-                cur_base.display()
-            )))
         }
     }
 
@@ -100,20 +182,105 @@ impl Resolver {
         }
     }
 
+    /// Resolve the `imports` of the file `from` and return the files that have
+    /// not been imported yet. The imports are recorded in `graph`.
+    fn resolve_imports(
+        &mut self,
+        imports: Vec<String>,
+        from: &Path,
+        graph: &mut ImportGraph,
+    ) -> FilamentResult<Vec<PathBuf>> {
+        let dir = Self::parent(from);
+        let mut fresh = vec![];
+        for imp in imports {
+            let file = self.resolve_import(&imp, &dir)?;
+            graph.add(from, &file);
+            fresh.extend(self.add_import(file));
+        }
+        Ok(fresh)
+    }
+
+    /// Ensure that components only use private components defined in the same
+    /// file. `defined_in` maps each component to the file that defines it.
+    fn check_visibility(
+        ns: &ast::Namespace,
+        defined_in: &HashMap<ast::Id, PathBuf>,
+    ) -> FilamentResult<()> {
+        let comps: HashMap<_, _> = ns
+            .components
+            .iter()
+            .map(|comp| (*comp.sig.name.inner(), comp))
+            .collect();
+        for comp in &ns.components {
+            let file = &defined_in[comp.sig.name.inner()];
+            for inst in comp.instances() {
+                let name = inst.component.inner();
+                // External components are visible everywhere
+                let Some(used) = comps.get(name) else {
+                    continue;
+                };
+                let used_file = &defined_in[name];
+                if !used.public && used_file != file {
+                    return Err(Error::misc(format!(
+                        "component `{name}' is private to {} and cannot be used in {}. Remove `priv' from its definition to use it in other files",
+                        used_file.display(),
+                        file.display()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if the program is read from the standard input
+    pub fn reads_stdin(input: &Path) -> bool {
+        input == Path::new("-")
+    }
+
+    /// The files parsed so far, starting with the input file
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        std::iter::once(self.input.as_path())
+            .chain(self.already_imported.iter().map(PathBuf::as_path))
+    }
+
+    /// The files that the compilation of `ns` depends on: the input file,
+    /// the imported files, and the files of the external components. Paths
+    /// are canonicalized so that each file appears once.
+    pub fn dependencies(&self, ns: &ast::Namespace) -> BTreeSet<PathBuf> {
+        let input = (!Self::reads_stdin(&self.input)).then_some(&self.input);
+        input
+            .into_iter()
+            .chain(&self.already_imported)
+            .map(PathBuf::as_path)
+            .chain(ns.externs.iter().map(|ext| Path::new(&ext.path)))
+            .map(|p| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf()))
+            .collect()
+    }
+
     pub fn parse_namespace(&mut self) -> FilamentResult<ast::Namespace> {
         // Parse the top-level file
-        let mut ns = ast::FilamentParser::parse_file(&self.input)?;
+        let mut ns = if Self::reads_stdin(&self.input) {
+            ast::FilamentParser::parse_stdin()?
+        } else {
+            ast::FilamentParser::parse_file(&self.input)?
+        };
+        let mut defined_in: HashMap<_, _> = ns
+            .components
+            .iter()
+            .map(|comp| (*comp.sig.name.inner(), self.input.clone()))
+            .collect();
 
         // Extern are resolved to thier absolute path relative to the input file.
+        // Programs read from the standard input resolve them relative to the
+        // current directory.
         let base = Self::parent(&self.input);
-        let mut imports: Vec<PathBuf> = ns
-            .imports
-            .drain(..)
-            .filter_map(|s| match self.resolve_import(&s, &base) {
-                Ok(file) => self.add_import(file).map(Ok),
-                f @ Err(_) => Some(f),
-            })
-            .collect::<FilamentResult<_>>()?;
+        let mut graph = ImportGraph::default();
+        let input = self.input.clone();
+        let mut imports = self.resolve_imports(
+            ns.imports.drain(..).collect(),
+            &input,
+            &mut graph,
+        )?;
 
         ns.externs = ns
             .externs
@@ -123,6 +290,11 @@ impl Resolver {
 
         while let Some(path) = imports.pop() {
             let mut imp = ast::FilamentParser::parse_file(&path)?;
+            defined_in.extend(
+                imp.components
+                    .iter()
+                    .map(|comp| (*comp.sig.name.inner(), path.clone())),
+            );
             let base = Self::parent(&path);
             imp.components.append(&mut ns.components);
             ns.components = imp.components;
@@ -131,18 +303,16 @@ impl Resolver {
                     .into_iter()
                     .map(|ext| ext.map_path(|p| Self::absolute(p, &base))),
             );
-            let base = Self::parent(&path);
-            imports.extend(
-                imp.imports
-                    .into_iter()
-                    .filter_map(|s| match self.resolve_import(&s, &base) {
-                        Ok(file) => self.add_import(file).map(Ok),
-                        f @ Err(_) => Some(f),
-                    })
-                    .collect::<FilamentResult<Vec<_>>>()?,
-            );
+            imports.extend(self.resolve_imports(
+                imp.imports,
+                &path,
+                &mut graph,
+            )?);
         }
 
+        graph.check_cycles(&self.input)?;
+        Self::check_visibility(&ns, &defined_in)?;
+
         log::trace!("Imported: {:#?}", self.already_imported);
         log::trace!(
             "Components: {:#?}",
@@ -0,0 +1,118 @@
+//! Rough estimates of the hardware used by compiled components.
+use crate::ir_passes::PrimitiveMap;
+use calyx_ir as calyx;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// Kinds of primitives counted by the estimate
+const KINDS: [&str; 5] = ["adders", "muxes", "registers", "memories", "other"];
+
+/// Number of primitives of each kind in [KINDS]
+type Counts = [u64; KINDS.len()];
+
+/// Counts the primitives instantiated by each component of a compiled
+/// program. The count for a component includes the primitives of all the
+/// components it instantiates.
+pub struct ResourceEstimate<'a> {
+    ctx: &'a calyx::Context,
+    prims: &'a PrimitiveMap,
+    counts: HashMap<calyx::Id, Counts>,
+}
+
+impl<'a> ResourceEstimate<'a> {
+    pub fn new(ctx: &'a calyx::Context, prims: &'a PrimitiveMap) -> Self {
+        Self {
+            ctx,
+            prims,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// The kind of a primitive based on its name
+    fn kind(&self, prim: &str) -> usize {
+        let name = prim.to_lowercase();
+        if prim == self.prims.adder
+            || name.contains("add")
+            || name.contains("sub")
+        {
+            0
+        } else if name.contains("mux") {
+            1
+        } else if prim == self.prims.register
+            || ["reg", "delay", "prev"].iter().any(|r| name.contains(r))
+        {
+            2
+        } else if name.contains("mem") {
+            3
+        } else {
+            4
+        }
+    }
+
+    /// Primitives used by the component `name`
+    fn count(&mut self, name: calyx::Id) -> Counts {
+        if let Some(counts) = self.counts.get(&name) {
+            return *counts;
+        }
+        let ctx = self.ctx;
+        let comp = ctx
+            .components
+            .iter()
+            .find(|c| c.name == name)
+            .unwrap_or_else(|| panic!("Unknown component `{name}'"));
+        let mut counts = Counts::default();
+        for cell in comp.cells.iter() {
+            match &cell.borrow().prototype {
+                calyx::CellType::Primitive { name, .. } => {
+                    counts[self.kind(&name.to_string())] += 1
+                }
+                calyx::CellType::Component { name } => {
+                    let sub = self.count(*name);
+                    counts.iter_mut().zip(sub).for_each(|(c, s)| *c += s);
+                }
+                // Constants are wires
+                calyx::CellType::Constant { .. }
+                | calyx::CellType::ThisComponent => (),
+            }
+        }
+        self.counts.insert(name, counts);
+        counts
+    }
+
+    /// Render a table with the primitives used by each component, sorted by
+    /// the name of the component.
+    pub fn table(mut self) -> String {
+        let ctx = self.ctx;
+        let rows = ctx
+            .components
+            .iter()
+            .map(|c| c.name)
+            .sorted_by_key(|n| n.to_string())
+            .map(|n| {
+                let counts = self.count(n);
+                (n.to_string(), counts.map(|c| c.to_string()))
+            })
+            .collect_vec();
+
+        let header = ("component".to_string(), KINDS.map(String::from));
+        let name_width = rows
+            .iter()
+            .map(|(n, _)| n.len())
+            .max()
+            .unwrap_or(0)
+            .max(header.0.len());
+        std::iter::once(header)
+            .chain(rows)
+            .map(|(name, counts)| {
+                let cols = counts
+                    .iter()
+                    .zip(KINDS)
+                    .map(|(c, k)| format!("{c:<width$}", width = k.len()))
+                    .join("  ");
+                format!("{name:<name_width$}  {cols}")
+                    .trim_end()
+                    .to_string()
+            })
+            .join("\n")
+    }
+}
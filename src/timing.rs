@@ -0,0 +1,65 @@
+//! Reports of the time spent in each pass of a compilation.
+use fil_utils::{Error, FilamentResult};
+use serde::Serialize;
+use std::{fs, mem, path::Path, sync::Mutex, time::Duration};
+
+/// Durations recorded by the timing macros. `None` when durations are only
+/// logged.
+static TIMINGS: Mutex<Option<Vec<(String, Duration)>>> = Mutex::new(None);
+
+#[derive(Serialize)]
+/// The time spent in a pass
+struct PassTime {
+    name: String,
+    ms: f64,
+}
+
+#[derive(Serialize)]
+/// The contents of a timing report
+struct Report {
+    total_ms: f64,
+    passes: Vec<PassTime>,
+}
+
+/// Collects the wall-clock durations of the passes so that they can be
+/// written as a JSON report.
+pub struct Timings;
+
+impl Timings {
+    /// Start recording the durations of the passes
+    pub fn enable() {
+        *TIMINGS.lock().unwrap() = Some(Vec::new());
+    }
+
+    /// Record that the pass `name` took `time`. Does nothing unless the
+    /// timings are enabled.
+    pub fn record(name: impl ToString, time: Duration) {
+        if let Some(times) = TIMINGS.lock().unwrap().as_mut() {
+            times.push((name.to_string(), time));
+        }
+    }
+
+    /// Write the durations recorded so far and the total time of the
+    /// compilation to the given file.
+    pub fn save(path: &Path, total: Duration) -> FilamentResult<()> {
+        let times =
+            mem::take(TIMINGS.lock().unwrap().as_mut().unwrap_or(&mut vec![]));
+        let report = Report {
+            total_ms: total.as_secs_f64() * 1000.0,
+            passes: times
+                .into_iter()
+                .map(|(name, time)| PassTime {
+                    name,
+                    ms: time.as_secs_f64() * 1000.0,
+                })
+                .collect(),
+        };
+        let content = serde_json::to_string_pretty(&report).unwrap();
+        fs::write(path, content + "\n").map_err(|err| {
+            Error::write_error(format!(
+                "Failed to write {}: {err}",
+                path.to_string_lossy()
+            ))
+        })
+    }
+}
@@ -0,0 +1,46 @@
+//! Detection of external components that are never instantiated.
+use crate::resolver::Resolver;
+use fil_ast as ast;
+use fil_utils::{GlobalPositionTable, Warning};
+use std::{collections::HashSet, path::Path};
+
+/// Warn about the external components defined in the `input` file that are
+/// never instantiated and return the number of warnings added.
+/// Externs defined in imported files, like the primitive library, are not
+/// reported because programs only use some of them.
+pub fn report(ns: &ast::Namespace, input: &Path) -> u64 {
+    let used: HashSet<_> = ns
+        .components
+        .iter()
+        .flat_map(|comp| comp.instances())
+        .map(|inst| *inst.component.inner())
+        .collect();
+
+    let input = if Resolver::reads_stdin(input) {
+        ast::STDIN.into()
+    } else {
+        input.to_string_lossy()
+    };
+    let table = GlobalPositionTable::as_ref();
+    let warnings: Vec<_> = ns
+        .externs
+        .iter()
+        .flat_map(|ext| &ext.comps)
+        .filter(|sig| !used.contains(sig.name.inner()))
+        .filter_map(|sig| {
+            let pos = sig.name.pos().into_option()?;
+            let (file, _) = table.get_file_info(pos.0);
+            (file == input).then(|| {
+                Warning::new(format!(
+                    "extern component `{}` is never instantiated",
+                    sig.name
+                ))
+                .with_label(pos, "unused extern component")
+            })
+        })
+        .collect();
+
+    let count = warnings.len() as u64;
+    warnings.into_iter().for_each(Warning::add);
+    count
+}
@@ -1,3 +1,5 @@
 mod hoist_facts;
+mod numeric_bounds;
 
 pub use hoist_facts::HoistFacts;
+pub use numeric_bounds::NumericBounds;
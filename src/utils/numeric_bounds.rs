@@ -0,0 +1,171 @@
+use fil_ast as ast;
+use fil_ir::{self as ir, Ctx};
+
+#[derive(Clone, Copy)]
+/// Bounds on the values of an expression. Missing bounds are infinite.
+struct Interval {
+    lo: Option<i128>,
+    hi: Option<i128>,
+}
+
+impl Interval {
+    /// The interval that contains every value
+    const TOP: Self = Self { lo: None, hi: None };
+
+    fn exact(n: i128) -> Self {
+        Self {
+            lo: Some(n),
+            hi: Some(n),
+        }
+    }
+
+    fn as_exact(self) -> Option<i128> {
+        match (self.lo, self.hi) {
+            (Some(lo), Some(hi)) if lo == hi => Some(lo),
+            _ => None,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        let add = |a: Option<i128>, b: Option<i128>| {
+            a.zip(b).and_then(|(a, b)| a.checked_add(b))
+        };
+        Self {
+            lo: add(self.lo, other.lo),
+            hi: add(self.hi, other.hi),
+        }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        let sub = |a: Option<i128>, b: Option<i128>| {
+            a.zip(b).and_then(|(a, b)| a.checked_sub(b))
+        };
+        Self {
+            lo: sub(self.lo, other.hi),
+            hi: sub(self.hi, other.lo),
+        }
+    }
+
+    /// Apply `op` when both intervals contain exactly one value and widen to
+    /// [Interval::TOP] otherwise.
+    fn exact_op(
+        self,
+        other: Self,
+        op: impl Fn(i128, i128) -> Option<i128>,
+    ) -> Self {
+        self.as_exact()
+            .zip(other.as_exact())
+            .and_then(|(a, b)| op(a, b))
+            .map_or(Self::TOP, Self::exact)
+    }
+
+    /// Decide `v op 0` for all values `v` in the interval.
+    fn cmp_zero(self, op: &ir::Cmp) -> Option<bool> {
+        // Lower bound is at least `n`
+        let lo_ge = |n| self.lo.map_or(false, |l| l >= n);
+        // Upper bound is at most `n`
+        let hi_le = |n| self.hi.map_or(false, |h| h <= n);
+        let (valid, invalid) = match op {
+            ir::Cmp::Gt => (lo_ge(1), hi_le(0)),
+            ir::Cmp::Gte => (lo_ge(0), hi_le(-1)),
+            ir::Cmp::Eq => (self.as_exact() == Some(0), lo_ge(1) || hi_le(-1)),
+        };
+        if valid {
+            Some(true)
+        } else if invalid {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// Decides propositions using coarse numeric bounds instead of a solver.
+/// Parameters are unconstrained, time expressions can only be compared when
+/// they use the same event, and non-linear arithmetic is only evaluated on
+/// concrete values.
+/// The bounds over-approximate the values of expressions so a decided
+/// proposition has the same truth value in the exact model.
+pub struct NumericBounds<'a> {
+    comp: &'a ir::Component,
+}
+
+impl<'a> NumericBounds<'a> {
+    pub fn new(comp: &'a ir::Component) -> Self {
+        Self { comp }
+    }
+
+    fn expr(&self, expr: ir::ExprIdx) -> Interval {
+        match self.comp.get(expr) {
+            ir::Expr::Param(p) => match &self.comp.get(*p).owner {
+                ir::ParamOwner::Let { bind } => self.expr(*bind),
+                _ => Interval::TOP,
+            },
+            ir::Expr::Concrete(n) => Interval::exact(i128::from(*n)),
+            ir::Expr::Bin { op, lhs, rhs } => {
+                let (l, r) = (self.expr(*lhs), self.expr(*rhs));
+                match op {
+                    ast::Op::Add => l.add(r),
+                    ast::Op::Sub => l.sub(r),
+                    ast::Op::Mul => l.exact_op(r, i128::checked_mul),
+                    ast::Op::Div => l.exact_op(r, i128::checked_div_euclid),
+                    ast::Op::Mod => l.exact_op(r, i128::checked_rem_euclid),
+                }
+            }
+            // Functions are uninterpreted in the solver
+            ir::Expr::Fn { .. } => Interval::TOP,
+        }
+    }
+
+    /// The difference between two time expressions if they use the same
+    /// event.
+    fn time_diff(&self, l: ir::TimeIdx, r: ir::TimeIdx) -> Option<Interval> {
+        let (l, r) = (self.comp.get(l), self.comp.get(r));
+        (l.event == r.event)
+            .then(|| self.expr(l.offset).sub(self.expr(r.offset)))
+    }
+
+    fn timesub(&self, ts: &ir::TimeSub) -> Option<Interval> {
+        match ts {
+            ir::TimeSub::Unit(e) => Some(self.expr(*e)),
+            ir::TimeSub::Sym { l, r } => self.time_diff(*l, *r),
+        }
+    }
+
+    /// Returns the truth value of the proposition or `None` if the bounds
+    /// cannot decide it.
+    pub fn decide(&self, prop: ir::PropIdx) -> Option<bool> {
+        match self.comp.get(prop) {
+            ir::Prop::True => Some(true),
+            ir::Prop::False => Some(false),
+            ir::Prop::Cmp(ir::CmpOp { op, lhs, rhs }) => {
+                self.expr(*lhs).sub(self.expr(*rhs)).cmp_zero(op)
+            }
+            ir::Prop::TimeCmp(ir::CmpOp { op, lhs, rhs }) => {
+                self.time_diff(*lhs, *rhs)?.cmp_zero(op)
+            }
+            ir::Prop::TimeSubCmp(ir::CmpOp { op, lhs, rhs }) => {
+                let (l, r) = (self.timesub(lhs)?, self.timesub(rhs)?);
+                l.sub(r).cmp_zero(op)
+            }
+            ir::Prop::Not(p) => self.decide(*p).map(|b| !b),
+            ir::Prop::And(l, r) => match (self.decide(*l), self.decide(*r)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            ir::Prop::Or(l, r) => match (self.decide(*l), self.decide(*r)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            ir::Prop::Implies(l, r) => {
+                match (self.decide(*l), self.decide(*r)) {
+                    (Some(false), _) | (_, Some(true)) => Some(true),
+                    (Some(true), Some(false)) => Some(false),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
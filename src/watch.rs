@@ -0,0 +1,136 @@
+//! Detect changes to the files of a program so that it can be recompiled.
+use itertools::Itertools;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// How often the files are checked for changes
+const POLL: Duration = Duration::from_millis(50);
+
+/// Files must stay unchanged for this long before a change is reported so
+/// that editors writing a file in several steps only trigger one recompile
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// A source of changes to files
+pub trait Events {
+    /// Block until some of `files` change or until `timeout` passes and
+    /// return the files that changed. Waits for as long as it takes without a
+    /// timeout. Returns no files when no more changes can be observed.
+    fn next(
+        &mut self,
+        files: &[PathBuf],
+        timeout: Option<Duration>,
+    ) -> Vec<PathBuf>;
+}
+
+/// Identifies the contents of a file without reading it. Missing files have
+/// no stamp so that deleting and recreating a file is a change.
+type Stamp = Option<(SystemTime, u64)>;
+
+#[derive(Default)]
+/// Detects changes to files by polling their modification times. Files
+/// change when their stamp differs from the one seen by the previous poll.
+pub struct Poll {
+    stamps: HashMap<PathBuf, Stamp>,
+}
+
+impl Poll {
+    fn stamp(file: &Path) -> Stamp {
+        let meta = fs::metadata(file).ok()?;
+        Some((meta.modified().ok()?, meta.len()))
+    }
+
+    /// Files that changed since the last poll. Files that were never polled
+    /// before have not changed.
+    fn changed(&mut self, files: &[PathBuf]) -> Vec<PathBuf> {
+        files
+            .iter()
+            .filter(|file| {
+                let new = Self::stamp(file);
+                self.stamps
+                    .insert(file.to_path_buf(), new)
+                    .map_or(false, |old| old != new)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+impl Events for Poll {
+    fn next(
+        &mut self,
+        files: &[PathBuf],
+        timeout: Option<Duration>,
+    ) -> Vec<PathBuf> {
+        let start = Instant::now();
+        loop {
+            let changed = self.changed(files);
+            if !changed.is_empty()
+                || timeout.map_or(false, |t| start.elapsed() >= t)
+            {
+                return changed;
+            }
+            thread::sleep(POLL);
+        }
+    }
+}
+
+/// Rebuilds a program every time one of its files changes
+pub struct Watcher<E: Events> {
+    events: E,
+}
+
+impl<E: Events> Watcher<E> {
+    pub fn new(events: E) -> Self {
+        Self { events }
+    }
+
+    /// Call `build`, which returns the files it read, and call it again
+    /// every time some of them change. Stops after `limit` rebuilds or when
+    /// no more changes can be observed. Returns the number of rebuilds.
+    pub fn run(
+        &mut self,
+        limit: Option<u64>,
+        mut build: impl FnMut() -> Vec<PathBuf>,
+    ) -> u64 {
+        let mut rebuilds = 0;
+        loop {
+            let mut files = build();
+            if limit.map_or(false, |limit| rebuilds >= limit) {
+                return rebuilds;
+            }
+            files.sort();
+            files.dedup();
+            eprintln!("Watching {} files for changes.", files.len());
+            let changed = self.wait(&files);
+            if changed.is_empty() {
+                return rebuilds;
+            }
+            rebuilds += 1;
+            eprintln!(
+                "Recompiling after changes to {}.",
+                changed.iter().map(|f| f.display()).join(", ")
+            );
+        }
+    }
+
+    /// Block until some files change and then stay unchanged for [DEBOUNCE].
+    /// Returns the files that changed in sorted order.
+    fn wait(&mut self, files: &[PathBuf]) -> Vec<PathBuf> {
+        let mut changed = self.events.next(files, None);
+        while !changed.is_empty() {
+            let more = self.events.next(files, Some(DEBOUNCE));
+            if more.is_empty() {
+                break;
+            }
+            changed.extend(more);
+        }
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+}
@@ -0,0 +1,51 @@
+//! Tests for compiling programs through the library entry point.
+use argh::FromArgs;
+use fil_utils::Warning;
+use filament::cmdline::Opts;
+
+/// Options to compile the given file without a solver
+fn opts(file: &str) -> Opts {
+    Opts::from_args(&["filament"], &[file, "--unsafe-skip-discharge"]).unwrap()
+}
+
+// The compiler reports diagnostics through global state, so the programs are
+// compiled one after the other.
+#[test]
+fn compile() {
+    // Warnings are kept for the caller to take along with the program
+    let calyx = filament::compile(&opts("tests/lint/unused-event.fil"));
+    let calyx = calyx.unwrap();
+    assert_eq!(calyx.entrypoint.to_string(), "main");
+    assert!(calyx
+        .components
+        .iter()
+        .any(|c| c.name.to_string() == "main"));
+    let warnings = Warning::take_all();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, "unused event `H`");
+    assert!(warnings[0].to_string().contains("event is never used"));
+
+    // The entrypoint is renamed along with the toplevel component
+    let prefixed = Opts::from_args(
+        &["filament"],
+        &[
+            "tests/name-prefix/prefix.fil",
+            "--unsafe-skip-discharge",
+            "--name-prefix",
+            "core0_",
+        ],
+    )
+    .unwrap();
+    let calyx = filament::compile(&prefixed);
+    assert_eq!(calyx.unwrap().entrypoint.to_string(), "core0_main");
+
+    // The errors in the program are returned instead of being reported
+    let calyx = filament::compile(&opts("tests/errors/binding/port.fil"));
+    let Err(err) = calyx else {
+        panic!("program with an undefined port compiled")
+    };
+    assert_eq!(err.kind.to_string(), "undefined port name: out");
+    assert_eq!(err.count(), 1);
+    assert!(err.to_string().contains("`out' is not a defined port"));
+    assert!(Warning::take_all().is_empty());
+}
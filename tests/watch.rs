@@ -0,0 +1,86 @@
+//! Tests for recompiling programs when their files change.
+use filament::watch::{Events, Watcher};
+use std::{collections::VecDeque, path::PathBuf, time::Duration};
+
+/// Replays a sequence of changes. Each entry contains the files that change
+/// before the next call returns, or no files if the call times out.
+struct Simulated {
+    changes: VecDeque<Vec<PathBuf>>,
+}
+
+impl Simulated {
+    fn new(changes: Vec<Vec<&str>>) -> Self {
+        Self {
+            changes: changes
+                .into_iter()
+                .map(|files| files.into_iter().map(PathBuf::from).collect())
+                .collect(),
+        }
+    }
+}
+
+impl Events for Simulated {
+    fn next(
+        &mut self,
+        files: &[PathBuf],
+        timeout: Option<Duration>,
+    ) -> Vec<PathBuf> {
+        while let Some(changed) = self.changes.pop_front() {
+            let changed: Vec<_> =
+                changed.into_iter().filter(|f| files.contains(f)).collect();
+            // Without a timeout, only changes to the files end the wait
+            if !changed.is_empty() || timeout.is_some() {
+                return changed;
+            }
+        }
+        vec![]
+    }
+}
+
+/// Watch a program whose input file imports `lib/reg.fil` and return the
+/// number of rebuilds and the number of builds
+fn watch(events: Simulated, limit: Option<u64>) -> (u64, u64) {
+    let mut builds = 0;
+    let rebuilds = Watcher::new(events).run(limit, || {
+        builds += 1;
+        vec![PathBuf::from("main.fil"), PathBuf::from("lib/reg.fil")]
+    });
+    (rebuilds, builds)
+}
+
+#[test]
+fn dependency_change() {
+    // Files that the program does not read are ignored and successive writes
+    // to the dependency are debounced into a single recompile
+    let events = Simulated::new(vec![
+        vec!["other.fil"],
+        vec!["lib/reg.fil"],
+        vec!["lib/reg.fil"],
+        vec!["lib/reg.fil"],
+        vec![],
+    ]);
+    assert_eq!(watch(events, None), (1, 2));
+}
+
+#[test]
+fn separate_changes() {
+    // Changes separated by a quiet period each trigger a recompile
+    let events = Simulated::new(vec![
+        vec!["lib/reg.fil"],
+        vec![],
+        vec!["main.fil"],
+        vec![],
+    ]);
+    assert_eq!(watch(events, None), (2, 3));
+}
+
+#[test]
+fn limit() {
+    let events = Simulated::new(vec![
+        vec!["lib/reg.fil"],
+        vec![],
+        vec!["main.fil"],
+        vec![],
+    ]);
+    assert_eq!(watch(events, Some(1)), (1, 2));
+}
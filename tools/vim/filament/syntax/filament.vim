@@ -42,7 +42,7 @@ syn region filamentString start=/\v"/ skip=/\v\\./ end=/\v("|$)/
 hi link filamentString String
 
 " Other keywords
-syn keyword filamentSpecialKeywords extern
+syn keyword filamentSpecialKeywords extern pub priv
 
 hi link filamentSpecialKeywords Directory
 hi link filamentPort Directory